@@ -0,0 +1,76 @@
+// src/advisor.rs
+//
+// Redemption Rate Advisor. From a host zone's redemption rate history it derives short and long
+// moving averages, projects the rate forward along the recent trend and maps the projection onto
+// a phase:
+// - Accumulation: the rate is below the caution threshold; staking is cheap.
+// - Caution: the rate is between the caution and redemption thresholds; hold.
+// - Redemption: the rate has reached the redemption threshold; redeem.
+//
+// The functions here are pure; the contract loads the history and thresholds from storage.
+
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::state::{AdvisorConfig, AdvisorPhase};
+
+/// Average of the last `window` rates. `rates` is ordered oldest first.
+pub fn moving_average(rates: &[(u64, Decimal)], window: u32) -> Option<Decimal> {
+    let window = (window as usize).min(rates.len());
+    if window == 0 {
+        return None;
+    }
+    let sum = rates[rates.len() - window..]
+        .iter()
+        .fold(Decimal::zero(), |sum, (_, rate)| sum + *rate);
+    Some(sum / Decimal::from_ratio(window as u128, 1u128))
+}
+
+/// Extend the trend over the last `window` points by `horizon_seconds` past the latest point.
+/// The trend is the straight line between the oldest and newest point of the window; a falling
+/// rate is projected down to no lower than zero.
+pub fn projected_rate(rates: &[(u64, Decimal)], window: u32, horizon_seconds: u64) -> Option<Decimal> {
+    let (latest_time, latest) = *rates.last()?;
+    let first = rates.len().saturating_sub((window as usize).max(2));
+    let (first_time, first_rate) = rates[first];
+    let elapsed = latest_time.saturating_sub(first_time);
+    if elapsed == 0 {
+        return Some(latest);
+    }
+
+    let scale = Decimal::from_ratio(horizon_seconds, elapsed);
+    if latest >= first_rate {
+        Some(latest + (latest - first_rate) * scale)
+    } else {
+        Some(latest.saturating_sub((first_rate - latest) * scale))
+    }
+}
+
+/// Phase for a rate under the configured thresholds.
+pub fn phase(config: &AdvisorConfig, rate: Decimal) -> AdvisorPhase {
+    if rate >= config.redemption_rate {
+        AdvisorPhase::Redemption
+    } else if rate >= config.caution_rate {
+        AdvisorPhase::Caution
+    } else {
+        AdvisorPhase::Accumulation
+    }
+}
+
+/// Recommended action for a phase.
+pub fn recommended_action(phase: &AdvisorPhase) -> &'static str {
+    match phase {
+        AdvisorPhase::Accumulation => "stake",
+        AdvisorPhase::Caution => "hold",
+        AdvisorPhase::Redemption => "redeem",
+    }
+}
+
+/// Liquid staking tokens minted for `amount` base tokens at `rate` base tokens per LST.
+pub fn stake_output(amount: Uint128, rate: Decimal) -> Uint128 {
+    amount.checked_div_floor(rate).unwrap_or_default()
+}
+
+/// Base tokens redeemed for `amount` liquid staking tokens at `rate`.
+pub fn redeem_output(amount: Uint128, rate: Decimal) -> Uint128 {
+    amount.mul_floor(rate)
+}
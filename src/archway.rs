@@ -0,0 +1,45 @@
+// src/archway.rs
+//
+// Archway rewards module messages. Contract rewards accrue to the contract's rewards address as
+// rewards records until that address withdraws them with `MsgWithdrawRewards`. The message is
+// sent as a stargate message encoded with prost.
+
+use cosmwasm_std::{Binary, CosmosMsg, Env};
+use prost::Message;
+
+pub const MSG_WITHDRAW_REWARDS_TYPE_URL: &str = "/archway.rewards.v1.MsgWithdrawRewards";
+
+// Maximum number of rewards records withdrawn per message.
+pub const WITHDRAW_RECORDS_LIMIT: u64 = 100;
+
+/// Protobuf encoding of `archway.rewards.v1.MsgWithdrawRewards` in records limit mode.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgWithdrawRewards {
+    #[prost(string, tag = "1")]
+    pub rewards_address: String,
+    #[prost(message, optional, tag = "2")]
+    pub records_limit: Option<RecordsLimit>,
+}
+
+/// Protobuf encoding of `archway.rewards.v1.MsgWithdrawRewards.RecordsLimit`.
+#[derive(Clone, PartialEq, Message)]
+pub struct RecordsLimit {
+    #[prost(uint64, tag = "1")]
+    pub limit: u64,
+}
+
+/// Build a stargate `MsgWithdrawRewards` withdrawing up to `WITHDRAW_RECORDS_LIMIT` rewards
+/// records of this contract.
+pub fn withdraw_rewards_msg(env: &Env) -> CosmosMsg {
+    let msg = MsgWithdrawRewards {
+        rewards_address: env.contract.address.to_string(),
+        records_limit: Some(RecordsLimit {
+            limit: WITHDRAW_RECORDS_LIMIT,
+        }),
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MSG_WITHDRAW_REWARDS_TYPE_URL.to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    }
+}
@@ -0,0 +1,66 @@
+// src/bin/operator.rs
+//
+// Builds and validates the owner's operational messages offline. Run with `cargo operator`.
+//
+//   operator plan <state.json> <plan.json>      diff the plan against the state and print the
+//                                               messages that carry it out
+//   operator validate <state.json> <msg.json>   check a single ExecuteMsg against the state
+//
+// The state file holds the query results described by `OnChainState`; the plan file is an
+// `OperatorPlan`. Output is JSON on stdout.
+
+use std::process::ExitCode;
+
+use cosmwasm_std::{from_json, to_json_string, StdError, StdResult};
+use serde::de::DeserializeOwned;
+
+use cosmwasm_liquid_staking::msg::ExecuteMsg;
+use cosmwasm_liquid_staking::operator::{
+    build_messages, validate_message, OnChainState, OperatorPlan,
+};
+
+const USAGE: &str = "usage:
+  operator plan <state.json> <plan.json>
+  operator validate <state.json> <msg.json>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let (command, state_path, input_path) = match args {
+        [command, state, input] => (command.as_str(), state, input),
+        _ => return Err(USAGE.to_string()),
+    };
+    let state: OnChainState = read_json(state_path).map_err(|e| e.to_string())?;
+
+    match command {
+        "plan" => {
+            let plan: OperatorPlan = read_json(input_path).map_err(|e| e.to_string())?;
+            let output = build_messages(&state, &plan).map_err(|e| e.to_string())?;
+            to_json_string(&output).map_err(|e| e.to_string())
+        }
+        "validate" => {
+            let msg: ExecuteMsg = read_json(input_path).map_err(|e| e.to_string())?;
+            validate_message(&state, &msg).map_err(|e| e.to_string())?;
+            to_json_string(&msg).map_err(|e| e.to_string())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &str) -> StdResult<T> {
+    let data = std::fs::read(path)
+        .map_err(|e| StdError::generic_err(format!("failed to read {}: {}", path, e)))?;
+    from_json(data)
+}
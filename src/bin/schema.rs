@@ -0,0 +1,18 @@
+// src/bin/schema.rs
+//
+// Writes the JSON schema of the contract's messages and query responses to `schema/`.
+// Run with `cargo schema`.
+
+use cosmwasm_schema::write_api;
+
+use cosmwasm_liquid_staking::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg,
+        sudo: SudoMsg,
+    }
+}
@@ -0,0 +1,40 @@
+// src/bin/simulator.rs
+//
+// Runs a scripted timeline against the contract and prints the state after every block as CSV.
+// Run with `cargo simulate <timeline.json>`; the timeline format is `simulator::Timeline`.
+
+use std::process::ExitCode;
+
+use cosmwasm_std::from_json;
+
+use cosmwasm_liquid_staking::simulator::{simulate, to_csv, Timeline};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [path] = args.as_slice() else {
+        eprintln!("usage: simulator <timeline.json>");
+        return ExitCode::FAILURE;
+    };
+
+    let timeline: Timeline = match std::fs::read(path)
+        .map_err(|e| format!("failed to read {}: {}", path, e))
+        .and_then(|data| from_json(data).map_err(|e| e.to_string()))
+    {
+        Ok(timeline) => timeline,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match simulate(&timeline) {
+        Ok(rows) => {
+            print!("{}", to_csv(&timeline, &rows));
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -25,23 +25,52 @@
 
 // Imports required from the CosmWasm standard library and other crates.
 use cosmwasm_std::{
-    entry_point, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Event, MessageInfo,
-    Order, Response, StdError, StdResult, Storage, Timestamp, Uint128, to_binary, Api
+    coin, entry_point, Coin, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo,
+    Order, QuerierWrapper, Reply, Response, StdError, StdResult, Storage, SubMsg, Timestamp, Uint128, Api
 };    
-use cw_storage_plus::{Item, Map};
-use std::collections::HashMap;
+use cw_storage_plus::Map;
+use semver::Version;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::error::ContractError;
+use crate::ibc::{autopilot_memo, callback_memo, parse_transfer_sequence, transfer_msg};
+use crate::advisor;
+use crate::archway::{withdraw_rewards_msg, WITHDRAW_RECORDS_LIMIT};
+use crate::overlay::StorageOverlay;
+use crate::strategy::{self, PlannedStep};
+use crate::tokenfactory;
 use crate::msg::{
-    Distribution, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RewardUpdate, RewardSummariesResponse, ContractRewardSummary
+    AdviceResponse, CronSimulationResponse, ExchangeRateResponse, SharesResponse, ClaimableResponse,
+    ContractStakeResponse, ContractsResponse, OperatorsResponse, ReceiptTokenResponse, RedeemTokensResponse,
+    RedemptionRateHistoryEntry, RedemptionRatioResponse, RewardResponse, StakeRatioResponse,
+    TotalLiquidStakeResponse, TreasuryResponse, Distribution, EpochStArchResponse, SimulationResponse, ExecuteMsg, HostZoneResponse, IbcLifecycleComplete,
+    InstantiateMsg, LiquidStakeReconciliationResponse, MigrateMsg, QueryMsg,
+    RedemptionLedgerResponse, RedemptionRateOracleQueryMsg, RedemptionRateOracleResponse, RewardUpdate,
+    RewardSummariesResponse, ContractRewardSummary, StrategyLayerMsg, StrategyResponse, SudoMsg, TaskScheduleResponse,
 };
 use crate::state::{
-    Config, ContractMetadata, DepositRecord, CONFIG, CONTRACT_METADATA, CONTRACT_REWARDS,
-    CONTRACT_STAKES, DEPOSIT_RECORDS, LAST_PROCESSING_TIMES, NEXT_DEPOSIT_RECORD_ID,
-    REDEEM_TOKEN_RATIOS, REDEEM_TOKENS, STAKE_RATIOS, TOTAL_LIQUID_STAKE,
-    REDEMPTION_RECORDS,
+    AdvisorConfig, BatchRedemption, Config, ContractMetadata, CronTask, Schedule, UnbondingBatch,
+    UnbondingStatus, DepositRecord, EpochReceiptTotals, HostZone, InFlightTransfer,
+    CostBasis, LiquidStakeProtocol, LiquidStakeReceipt, LiquidStakeVerification, RedemptionLedger, RedemptionRateRecord,
+    LiquidStakeRoute, RedemptionRateSource, RefundTarget, RraStrategy, StrategyLayer, StrategyStatus, StrategyStep,
+    TransferDeposit, ZoneAllocation, CONFIG, CONTRACT_METADATA,
+    CONTRACT_REWARDS, CONTRACT_STAKES, DEFAULT_HOST_ZONE, deposit_records, DepositStatus, TransferStatus, LEGACY_DEPOSIT_RECORDS, EPOCH_RECEIPT_TOTALS,
+    HOST_ZONES, HOST_ZONE_LIQUID_STAKE, HOST_ZONE_REDEMPTION_RATES, IN_FLIGHT_TRANSFERS,
+    LAST_PROCESSING_TIMES, LIQUID_STAKE_EPOCH, LIQUID_STAKE_RECEIPTS,
+    LIQUID_STAKE_VERIFICATION, NEXT_DEPOSIT_RECORD_ID, NEXT_PENDING_TRANSFER_ID, NEXT_RECEIPT_ID,
+    PENDING_TRANSFERS, RECEIPT_TOTALS, REDEEM_TOKEN_RATIOS, REDEEM_TOKENS, STAKE_RATIOS,
+    TOTAL_LIQUID_STAKE, REDEMPTION_RECORDS, ZONE_COMPLETED_STAKES, ZONE_STAKE_RATIOS,
+    NEXT_STRATEGY_ID, STRATEGIES, STRATEGY_STEPS, ADVISOR_CONFIG, REDEMPTION_RATE_HISTORY,
+    OPERATORS, LAST_PROCESSING_HEIGHTS, BATCH_TRANSFERS, NEXT_UNBONDING_BATCH_ID,
+    PENDING_BATCH_TRANSFERS, UNBONDING_BATCHES, REDEMPTION_LEDGER, REDEMPTION_TOKEN_RATIOS,
+    CLAIMABLE, CLAIMABLE_TOTALS, CREDITED_ZONE_STAKES, MAX_FEE_BPS, TREASURY,
+    COST_BASES, PERFORMANCE_FEES, RECEIPT_TOKEN_DENOM, RECEIPT_SHARES, POOL_ARCH, POOL_LST, SHARES, TOTAL_SHARES,
 };
 
+// Contract name and version recorded with cw2 on instantiate and migrate.
+pub const CONTRACT_NAME: &str = concat!("crates.io:", env!("CARGO_PKG_NAME"));
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // Constants for keys used to track when certain periodic tasks last ran. These keys are used
 // in the LAST_PROCESSING_TIMES map to store timestamps.
 const LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY: &str = "last_liquid_staking_dapp_rewards_time";
@@ -49,6 +78,15 @@ const LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY: &str = "last_arch_liquid_stake_i
 const LAST_REDEMPTION_RATE_QUERY_TIME_KEY: &str = "last_redemption_rate_query_time";
 const LAST_REWARDS_WITHDRAWAL_TIME_KEY: &str = "last_rewards_withdrawal_time";
 
+// Default and maximum page sizes for paginated queries.
+const DEFAULT_QUERY_LIMIT: u32 = 30;
+const MAX_QUERY_LIMIT: u32 = 100;
+
+// Reply ID for the stargate MsgTransfer that carries pending deposits to Stride.
+const IBC_TRANSFER_REPLY_ID: u64 = 1;
+// Reply ID for the stargate MsgTransfer that carries an unbonding batch to its host zone.
+const UNBONDING_TRANSFER_REPLY_ID: u64 = 2;
+
 // COMPLETED_STAKES: Tracks how much stake each contract has completed (fully processed and recognized).
 // Uses contract address as key and a Uint128 for the completed stake amount.
 pub static COMPLETED_STAKES: Map<&Addr, Uint128> = Map::new("completed_stakes");
@@ -71,22 +109,30 @@ pub fn instantiate(
     // Build the initial config from the instantiation message. The owner is set to the sender.
     let config = Config {
         owner: info.sender.clone(),
-        liquid_staking_interval: msg.liquid_staking_interval,
-        arch_liquid_stake_interval: msg.arch_liquid_stake_interval,
-        redemption_rate_query_interval: msg.redemption_rate_query_interval,
-        rewards_withdrawal_interval: msg.rewards_withdrawal_interval,
+        liquid_staking_interval: Schedule::Seconds(msg.liquid_staking_interval),
+        arch_liquid_stake_interval: Schedule::Seconds(msg.arch_liquid_stake_interval),
+        redemption_rate_query_interval: Schedule::Seconds(msg.redemption_rate_query_interval),
+        rewards_withdrawal_interval: Schedule::Seconds(msg.rewards_withdrawal_interval),
         redemption_interval_threshold: msg.redemption_interval_threshold,
+        protocol_fee_bps: 0,
+        performance_fee_bps: 0,
     };
 
     // Save the configuration to storage for persistent access.
     CONFIG.save(deps.storage, &config)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    // Initialize last processing times for various cron tasks to the current block time.
+    // Initialize last processing times and heights for various cron tasks to the current block.
     let now = env.block.time.seconds();
-    LAST_PROCESSING_TIMES.save(deps.storage, LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY, &now)?;
-    LAST_PROCESSING_TIMES.save(deps.storage, LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY, &now)?;
-    LAST_PROCESSING_TIMES.save(deps.storage, LAST_REDEMPTION_RATE_QUERY_TIME_KEY, &now)?;
-    LAST_PROCESSING_TIMES.save(deps.storage, LAST_REWARDS_WITHDRAWAL_TIME_KEY, &now)?;
+    for key in [
+        LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY,
+        LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY,
+        LAST_REDEMPTION_RATE_QUERY_TIME_KEY,
+        LAST_REWARDS_WITHDRAWAL_TIME_KEY,
+    ] {
+        LAST_PROCESSING_TIMES.save(deps.storage, key, &now)?;
+        LAST_PROCESSING_HEIGHTS.save(deps.storage, key, &env.block.height)?;
+    }
 
     // Initialize total liquid stake as zero at the start.
     TOTAL_LIQUID_STAKE.save(deps.storage, &Uint128::zero())?;
@@ -94,6 +140,10 @@ pub fn instantiate(
     // Set the next deposit record ID to start at 1, ensuring a unique ID counter for deposit records.
     NEXT_DEPOSIT_RECORD_ID.save(deps.storage, &1u64)?;
 
+    // Start the liquid stake receipt ledger at epoch zero with receipt IDs starting at 1.
+    LIQUID_STAKE_EPOCH.save(deps.storage, &0u64)?;
+    NEXT_RECEIPT_ID.save(deps.storage, &1u64)?;
+
     // Emit an event indicating that the contract has been instantiated successfully.
     let event = Event::new("instantiate")
         .add_attribute("action", "instantiate")
@@ -125,6 +175,18 @@ pub fn execute(
     match msg {
         ExecuteMsg::CronJob {} => execute_cron_job(deps, env),
 
+        ExecuteMsg::RunTask { task, force } => execute_run_task(deps, env, info, task, force),
+
+        ExecuteMsg::UpdateSchedule { task, schedule } => {
+            execute_update_schedule(deps, env, info, task, schedule)
+        }
+
+        ExecuteMsg::AddOperator { address } => execute_set_operator(deps, env, info, address, true),
+
+        ExecuteMsg::RemoveOperator { address } => {
+            execute_set_operator(deps, env, info, address, false)
+        }
+
         ExecuteMsg::SetContractMetadata {
             contract_address,
             rewards_address,
@@ -197,301 +259,1715 @@ pub fn execute(
         ExecuteMsg::SubtractFromTotalLiquidStake { amount } => {
             execute_subtract_from_total_liquid_stake(deps, env, info, amount)
         }
+
+        ExecuteMsg::AddHostZone {
+            zone_id,
+            protocol,
+            channel_id,
+            arch_denom,
+            lst_denom,
+            receiver,
+            timeout_seconds,
+            redemption_rate_source,
+            unbonding_period_seconds,
+            refund_target,
+        } => execute_add_host_zone(
+            deps,
+            env,
+            info,
+            HostZone {
+                zone_id,
+                protocol,
+                channel_id,
+                arch_denom,
+                lst_denom,
+                receiver,
+                timeout_seconds,
+                redemption_rate_source,
+                unbonding_period_seconds,
+                refund_target: refund_target.unwrap_or_default(),
+                enabled: true,
+            },
+        ),
+
+        ExecuteMsg::UpdateHostZone {
+            zone_id,
+            channel_id,
+            lst_denom,
+            receiver,
+            timeout_seconds,
+            redemption_rate_source,
+            unbonding_period_seconds,
+            refund_target,
+        } => execute_update_host_zone(deps, env, info, &zone_id, |zone| {
+            if let Some(channel_id) = channel_id {
+                zone.channel_id = channel_id;
+            }
+            if let Some(lst_denom) = lst_denom {
+                zone.lst_denom = lst_denom;
+            }
+            if let Some(receiver) = receiver {
+                zone.receiver = receiver;
+            }
+            if let Some(timeout_seconds) = timeout_seconds {
+                zone.timeout_seconds = timeout_seconds;
+            }
+            if let Some(source) = redemption_rate_source {
+                zone.redemption_rate_source = source;
+            }
+            if let Some(unbonding_period_seconds) = unbonding_period_seconds {
+                zone.unbonding_period_seconds = unbonding_period_seconds;
+            }
+            if let Some(refund_target) = refund_target {
+                zone.refund_target = refund_target;
+            }
+        }),
+
+        ExecuteMsg::DisableHostZone { zone_id } => {
+            execute_update_host_zone(deps, env, info, &zone_id, |zone| zone.enabled = false)
+        }
+
+        ExecuteMsg::EnableHostZone { zone_id } => {
+            execute_update_host_zone(deps, env, info, &zone_id, |zone| zone.enabled = true)
+        }
+
+        ExecuteMsg::SetDefaultHostZone { zone_id } => {
+            execute_set_default_host_zone(deps, env, info, zone_id)
+        }
+
+        ExecuteMsg::SetContractStrategy {
+            contract_address,
+            allocations,
+        } => execute_set_contract_strategy(deps, env, info, contract_address, allocations),
+
+        ExecuteMsg::SetAdvisorConfig {
+            caution_rate,
+            redemption_rate,
+            short_window,
+            long_window,
+            projection_seconds,
+        } => execute_set_advisor_config(
+            deps,
+            env,
+            info,
+            AdvisorConfig {
+                caution_rate,
+                redemption_rate,
+                short_window,
+                long_window,
+                projection_seconds,
+            },
+        ),
+
+        ExecuteMsg::ReportRedemptionRate {
+            zone_id,
+            redemption_rate,
+        } => execute_report_redemption_rate(deps, env, info, zone_id, redemption_rate),
+
+        ExecuteMsg::SetLiquidStakeRoute {
+            channel_id,
+            arch_denom,
+            stride_receiver,
+            timeout_seconds,
+            refund_target,
+            lst_denom,
+            unbonding_period_seconds,
+        } => execute_set_liquid_stake_route(
+            deps,
+            env,
+            info,
+            LiquidStakeRoute {
+                channel_id,
+                arch_denom,
+                stride_receiver,
+                timeout_seconds,
+                refund_target: refund_target.unwrap_or_default(),
+            },
+            lst_denom,
+            unbonding_period_seconds,
+        ),
+        ExecuteMsg::RetryFailedTransfers {} => execute_retry_failed_transfers(deps, env, info),
+        ExecuteMsg::CreateStrategy {
+            name,
+            layers,
+            target_rate,
+        } => execute_create_strategy(deps, env, info, name, layers, target_rate),
+        ExecuteMsg::StakeStrategy {
+            strategy_id,
+            amount,
+        } => execute_stake_strategy(deps, env, info, strategy_id, amount),
+        ExecuteMsg::UnwindStrategy { strategy_id } => {
+            execute_unwind_strategy(deps, env, info, strategy_id)
+        }
+        ExecuteMsg::EnableLiquidStakeVerification {
+            starch_denom,
+            tolerance_bps,
+        } => execute_enable_liquid_stake_verification(deps, env, info, starch_denom, tolerance_bps),
+        ExecuteMsg::DisableLiquidStakeVerification {} => {
+            execute_disable_liquid_stake_verification(deps, env, info)
+        }
+        ExecuteMsg::SetProtocolFee { fee_bps } => execute_set_protocol_fee(deps, env, info, fee_bps),
+        ExecuteMsg::SetPerformanceFee { fee_bps } => {
+            execute_set_performance_fee(deps, env, info, fee_bps)
+        }
+        ExecuteMsg::SetContractFee {
+            contract_address,
+            fee_bps,
+        } => execute_set_contract_fee(deps, env, info, contract_address, fee_bps),
+        ExecuteMsg::WithdrawTreasury {
+            amount,
+            denom,
+            recipient,
+        } => execute_withdraw_treasury(deps, env, info, amount, denom, recipient),
+        ExecuteMsg::CreateReceiptToken { subdenom } => {
+            execute_create_receipt_token(deps, env, info, subdenom)
+        }
+        ExecuteMsg::RedeemReceiptTokens {} => execute_redeem_receipt_tokens(deps, env, info),
+        ExecuteMsg::Claim { denom } => execute_claim(deps, env, info, denom),
     }
 }
 
-/// Execute function to update a specific contract's reward. Only the owner can do this.
-/// This ensures that only authorized users can modify reward amounts for contracts.
-fn execute_update_reward(
+/// Register a new host zone. Only the owner can do this. The first zone registered becomes the
+/// default zone.
+fn execute_add_host_zone(
     deps: DepsMut,
-    info: MessageInfo,
-    contract_address: String,
-    amount: Uint128,
     env: Env,
+    info: MessageInfo,
+    zone: HostZone,
 ) -> Result<Response, ContractError> {
-    // Authorization check against contract owner.
+    // Owner-only action.
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Validate the contract address to ensure it's a properly formed bech32 address.
-    let rewards_addr = deps.api.addr_validate(&contract_address)?;
-    add_reward_to_contract(deps.storage, &rewards_addr, amount, &env)?;
+    if zone.zone_id.is_empty() {
+        return Err(ContractError::InvalidHostZone {
+            reason: "zone_id must be set".to_string(),
+        });
+    }
+    if HOST_ZONES.has(deps.storage, &zone.zone_id) {
+        return Err(ContractError::HostZoneAlreadyExists {
+            zone_id: zone.zone_id,
+        });
+    }
+    let zone = validate_host_zone(deps.api, zone)?;
+    HOST_ZONES.save(deps.storage, &zone.zone_id, &zone)?;
 
-    // Emit an event indicating the reward was successfully updated.
-    let event = Event::new("update_reward")
-        .add_attribute("action", "execute_update_reward")
+    let is_default = DEFAULT_HOST_ZONE.may_load(deps.storage)?.is_none();
+    if is_default {
+        DEFAULT_HOST_ZONE.save(deps.storage, &zone.zone_id)?;
+    }
+
+    let event = host_zone_event("host_zone_added", &zone, &env)
         .add_attribute("sender", info.sender.to_string())
-        .add_attribute("contract_address", rewards_addr.clone())
-        .add_attribute("reward_amount", amount.to_string())
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
+        .add_attribute("is_default", is_default.to_string());
 
     Ok(Response::new()
         .add_event(event)
-        .add_attribute("method", "update_reward"))
+        .add_attribute("method", "add_host_zone"))
 }
 
-/// Update rewards for multiple contracts at once, reducing transaction overhead for the owner.
-/// The owner can set rewards for multiple contracts in a single call.
-fn execute_bulk_update_rewards(
+/// Apply `update` to a registered host zone and validate the result. Only the owner can do this.
+fn execute_update_host_zone<F>(
     deps: DepsMut,
-    info: MessageInfo,
-    updates: Vec<RewardUpdate>,
     env: Env,
-) -> Result<Response, ContractError> {
-    // Only the owner can bulk update rewards.
+    info: MessageInfo,
+    zone_id: &str,
+    update: F,
+) -> Result<Response, ContractError>
+where
+    F: FnOnce(&mut HostZone),
+{
+    // Owner-only action.
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    let mut res = Response::new();
-
-    // Process each update in the provided vector of updates.
-    for update in &updates {
-        let contract_addr = deps.api.addr_validate(&update.contract_address)?;
-        let event = add_reward_to_contract(deps.storage, &contract_addr, update.amount, &env)?;
-
-        // Emit events for each contract updated in bulk.
-        let update_event = Event::new("update_reward")
-            .add_attribute("action", "execute_bulk_update_rewards")
-            .add_attribute("sender", info.sender.to_string())
-            .add_attribute("contract_address", update.contract_address.clone())
-            .add_attribute("reward_amount", update.amount.to_string())
-            .add_attribute("block_height", env.block.height.to_string())
-            .add_attribute("timestamp", env.block.time.seconds().to_string());
-
-        // Add both the event from add_reward_to_contract and the update_event to the response.
-        res = res.add_event(event);
-        res = res.add_event(update_event);
-    }
-
-    // Indicate the method used in the response attributes.
-    res = res.add_attribute("method", "bulk_update_rewards");
-
-    Ok(res)
-}
-
-/// Adds a specified reward amount to a contract's reward balance. This is a helper function used by 
-/// execute_update_reward and execute_bulk_update_rewards to actually modify storage.
-///
-/// Arguments:
-/// - storage: state storage for reading/writing contract state
-/// - rewards_addr: address of the contract whose rewards are being updated
-/// - amount: the amount of rewards to add
-/// - env: environment for block info (for event attributes)
-fn add_reward_to_contract(
-    storage: &mut dyn Storage,
-    rewards_addr: &Addr,
-    amount: Uint128,
-    env: &Env,
-) -> Result<Event, ContractError> {
-    // Load the current reward amount; default to zero if not set.
-    let current_reward = CONTRACT_REWARDS
-        .may_load(storage, rewards_addr)?
-        .unwrap_or_default();
-    let new_reward = current_reward + amount;
-
-    // Save the updated reward amount.
-    CONTRACT_REWARDS.save(storage, rewards_addr, &new_reward)?;
-
-    // Emit an event indicating successful addition of rewards to the contract.
-    let event = Event::new("add_reward_to_contract")
-        .add_attribute("action", "add_reward_to_contract")
-        .add_attribute("contract_address", rewards_addr.to_string())
-        .add_attribute("reward_amount_added", amount.to_string())
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
-    Ok(event)
-}
-
-/// Execute a cron job to process tasks that are due based on the elapsed time since their last run.
-/// Tasks include handling liquid staking rewards, arch liquid stake intervals, and redemption rate queries.
-fn execute_cron_job(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
-    let mut res = Response::new();
-    res = res.add_attribute("method", "execute_cron_job");
-
-    let config = CONFIG.load(deps.storage)?;
-    let now = env.block.time.seconds();
-
-    // If enough time has passed since the last liquid staking DApp rewards, process them.
-    if should_process_task(
-        deps.storage,
-        LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY,
-        config.liquid_staking_interval,
-        now,
-    )? {
-        let task_res = handle_liquid_staking_dapp_rewards(deps.storage, &env)?;
-        LAST_PROCESSING_TIMES.save(
-            deps.storage,
-            LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY,
-            &now,
-        )?;
-        // Add attributes and events from the task result to the main response.
-        res = res.add_attributes(task_res.attributes);
-        res = res.add_events(task_res.events);
-        res = res.add_attribute("task", "liquid_staking_dapp_rewards");
-    }
-
-    // If enough time has passed for arch liquid stake intervals, handle that.
-    if should_process_task(
-        deps.storage,
-        LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY,
-        config.arch_liquid_stake_interval,
-        now,
-    )? {
-        let task_res = handle_arch_liquid_stake_interval(deps.storage, &env)?;
-        LAST_PROCESSING_TIMES.save(
-            deps.storage,
-            LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY,
-            &now,
-        )?;
-        res = res.add_attributes(task_res.attributes);
-        res = res.add_events(task_res.events);
-        res = res.add_attribute("task", "arch_liquid_stake_interval");
-    }
-
-    // If enough time has passed for redemption rate queries, handle that as well.
-    if should_process_task(
-        deps.storage,
-        LAST_REDEMPTION_RATE_QUERY_TIME_KEY,
-        config.redemption_rate_query_interval,
-        now,
-    )? {
-        let task_res = handle_redemption_rate_query(deps.storage, &config, env.clone())?;
-        LAST_PROCESSING_TIMES.save(
-            deps.storage,
-            LAST_REDEMPTION_RATE_QUERY_TIME_KEY,
-            &now,
-        )?;
-        res = res.add_attributes(task_res.attributes);
-        res = res.add_events(task_res.events);
-        res = res.add_attribute("task", "redemption_rate_query");
-    }
-
-    // Emit a final event summarizing the cron job execution.
-    let event = Event::new("cron_job_executed")
-        .add_attribute("action", "execute_cron_job")
-        .add_attribute("timestamp", now.to_string())
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("processed_tasks", format!("{:?}", res.attributes));
+    let mut zone = load_host_zone(deps.storage, zone_id)?;
+    update(&mut zone);
+    let zone = validate_host_zone(deps.api, zone)?;
+    HOST_ZONES.save(deps.storage, zone_id, &zone)?;
 
-    res = res.add_event(event);
+    let event = host_zone_event("host_zone_updated", &zone, &env)
+        .add_attribute("sender", info.sender.to_string());
 
-    Ok(res)
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "update_host_zone"))
 }
 
-/// Set metadata for a given contract, controlling min/max reward amounts and 
-/// associated addresses for rewards and liquidity. Only the owner can call this.
-fn execute_set_contract_metadata(
+/// Select the host zone that pending deposits are sent to. Only the owner can do this.
+fn execute_set_default_host_zone(
     deps: DepsMut,
-    info: MessageInfo,
-    contract_address: String,
-    rewards_address: String,
-    liquidity_provider_address: String,
-    minimum_reward_amount: Uint128,
-    maximum_reward_amount: Uint128,
-    redemption_address: String,
     env: Env,
+    info: MessageInfo,
+    zone_id: String,
 ) -> Result<Response, ContractError> {
-    // Authorization: only the owner can set metadata.
+    // Owner-only action.
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Validate reward amount range.
-    if maximum_reward_amount < minimum_reward_amount {
-        return Err(ContractError::InvalidRewardAmountRange {});
-    }
-
-    // Create and save the ContractMetadata struct.
-    let metadata = ContractMetadata {
-        rewards_address: rewards_address.clone(),
-        liquidity_provider_address: liquidity_provider_address.clone(),
-        minimum_reward_amount,
-        maximum_reward_amount,
-        redemption_address: redemption_address.clone(),
-    };
-
-    CONTRACT_METADATA.save(
-        deps.storage,
-        &deps.api.addr_validate(&contract_address)?,
-        &metadata,
-    )?;
+    load_host_zone(deps.storage, &zone_id)?;
+    DEFAULT_HOST_ZONE.save(deps.storage, &zone_id)?;
 
-    // Emit an event indicating successful metadata setting.
-    let event = Event::new("set_contract_metadata")
-        .add_attribute("action", "execute_set_contract_metadata")
+    let event = Event::new("default_host_zone_set")
+        .add_attribute("action", "execute_set_default_host_zone")
         .add_attribute("sender", info.sender.to_string())
-        .add_attribute("contract_address", contract_address.clone())
-        .add_attribute("rewards_address", rewards_address.clone())
-        .add_attribute("liquidity_provider_address", liquidity_provider_address.clone())
-        .add_attribute("minimum_reward_amount", minimum_reward_amount.to_string())
-        .add_attribute("maximum_reward_amount", maximum_reward_amount.to_string())
-        .add_attribute("redemption_address", redemption_address.clone())
+        .add_attribute("zone_id", zone_id)
         .add_attribute("block_height", env.block.height.to_string())
         .add_attribute("timestamp", env.block.time.seconds().to_string());
 
     Ok(Response::new()
         .add_event(event)
-        .add_attribute("method", "set_contract_metadata")
-        .add_attribute("contract", contract_address))
+        .add_attribute("method", "set_default_host_zone"))
 }
 
-/// Add stake for the sender. This increases the CONTRACT_STAKES mapping for the caller by the given amount.
-fn execute_add_stake(
+/// Set the IBC route of the default host zone, registering a "stride" zone with a reported
+/// redemption rate when there is no default zone yet. Only the owner can do this.
+fn execute_set_liquid_stake_route(
     deps: DepsMut,
-    info: MessageInfo,
-    amount: Uint128,
     env: Env,
+    info: MessageInfo,
+    route: LiquidStakeRoute,
+    lst_denom: Option<String>,
+    unbonding_period_seconds: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Update the stake in storage.
-    add_contract_stake(deps.storage, &info.sender, amount)?;
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    // Emit an event indicating the stake addition.
-    let event = Event::new("add_stake")
-        .add_attribute("action", "execute_add_stake")
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("stake_amount", amount.to_string())
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    let mut zone = match DEFAULT_HOST_ZONE.may_load(deps.storage)? {
+        Some(zone_id) => load_host_zone(deps.storage, &zone_id)?,
+        None => {
+            let (Some(lst_denom), Some(unbonding_period_seconds)) = (lst_denom.clone(), unbonding_period_seconds)
+            else {
+                return Err(ContractError::InvalidHostZone {
+                    reason: "lst_denom and unbonding_period_seconds are required to register the stride zone"
+                        .to_string(),
+                });
+            };
+            if HOST_ZONES.has(deps.storage, "stride") {
+                return Err(ContractError::HostZoneAlreadyExists {
+                    zone_id: "stride".to_string(),
+                });
+            }
+            HostZone {
+                zone_id: "stride".to_string(),
+                protocol: LiquidStakeProtocol::Stride,
+                channel_id: String::new(),
+                arch_denom: String::new(),
+                lst_denom,
+                receiver: String::new(),
+                timeout_seconds: 0,
+                redemption_rate_source: RedemptionRateSource::Reported,
+                unbonding_period_seconds,
+                refund_target: RefundTarget::default(),
+                enabled: true,
+            }
+        }
+    };
+    zone.channel_id = route.channel_id;
+    zone.arch_denom = route.arch_denom;
+    zone.receiver = route.stride_receiver;
+    zone.timeout_seconds = route.timeout_seconds;
+    zone.refund_target = route.refund_target;
+    if let Some(lst_denom) = lst_denom {
+        zone.lst_denom = lst_denom;
+    }
+    if let Some(unbonding_period_seconds) = unbonding_period_seconds {
+        zone.unbonding_period_seconds = unbonding_period_seconds;
+    }
+    let zone = validate_host_zone(deps.api, zone)?;
+    HOST_ZONES.save(deps.storage, &zone.zone_id, &zone)?;
+    DEFAULT_HOST_ZONE.save(deps.storage, &zone.zone_id)?;
+
+    let event = host_zone_event("set_liquid_stake_route", &zone, &env)
+        .add_attribute("sender", info.sender.to_string());
 
     Ok(Response::new()
         .add_event(event)
-        .add_attribute("method", "add_stake")
-        .add_attribute("contract", info.sender.to_string())
-        .add_attribute("amount", amount.to_string()))
+        .add_attribute("method", "set_liquid_stake_route"))
 }
 
-/// Reset all completed deposit records to pending for all contracts. Only the owner can do this.
-/// This might be used for testing or emergency measures.
-fn execute_reset_all_completed_deposit_records(
+/// Record the redemption rate of a host zone whose rate is reported by the owner.
+fn execute_report_redemption_rate(
     deps: DepsMut,
-    info: MessageInfo,
     env: Env,
+    info: MessageInfo,
+    zone_id: String,
+    redemption_rate: Decimal,
 ) -> Result<Response, ContractError> {
-    // Authorization check.
+    // Owner-only action.
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Perform the reset operation in storage.
-    reset_all_completed_deposit_records(deps.storage)?;
+    let zone = load_host_zone(deps.storage, &zone_id)?;
+    if zone.redemption_rate_source != RedemptionRateSource::Reported {
+        return Err(ContractError::RedemptionRateNotReported { zone_id });
+    }
 
-    // Emit an event indicating the operation.
-    let event = Event::new("reset_all_completed_deposit_records")
-        .add_attribute("action", "execute_reset_all_completed_deposit_records")
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    let event = save_redemption_rate(deps.storage, &env, &zone_id, redemption_rate)?
+        .add_attribute("sender", info.sender.to_string());
 
     Ok(Response::new()
         .add_event(event)
-        .add_attribute("method", "reset_all_completed_deposit_records"))
+        .add_attribute("method", "report_redemption_rate"))
+}
+
+/// Create an RRA strategy over registered host zones. Only the owner can do this. Layer rates
+/// start from the zones' last known redemption rates.
+fn execute_create_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    layers: Vec<StrategyLayerMsg>,
+    target_rate: Decimal,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut strategy_layers = vec![];
+    for layer in layers {
+        load_host_zone(deps.storage, &layer.zone_id)?;
+        let redemption_rate = HOST_ZONE_REDEMPTION_RATES
+            .may_load(deps.storage, &layer.zone_id)?
+            .map(|record| record.redemption_rate)
+            .unwrap_or_default();
+        strategy_layers.push(StrategyLayer {
+            zone_id: layer.zone_id,
+            input_denom: layer.input_denom,
+            output_denom: layer.output_denom,
+            position: Uint128::zero(),
+            redemption_rate,
+        });
+    }
+    strategy::validate_path(&strategy_layers).map_err(|e| ContractError::InvalidStrategyPath {
+        reason: e.to_string(),
+    })?;
+    if target_rate <= Decimal::one() {
+        return Err(ContractError::InvalidStrategyPath {
+            reason: "target_rate must be greater than 1".to_string(),
+        });
+    }
+
+    let id = NEXT_STRATEGY_ID.may_load(deps.storage)?.unwrap_or(1);
+    NEXT_STRATEGY_ID.save(deps.storage, &(id + 1))?;
+    let path = strategy_layers
+        .iter()
+        .map(|layer| layer.zone_id.clone())
+        .collect::<Vec<_>>()
+        .join(">");
+    let rra = RraStrategy {
+        id,
+        name,
+        layers: strategy_layers,
+        target_rate,
+        status: StrategyStatus::Active,
+        timestamp: env.block.time.seconds(),
+        block_height: env.block.height,
+    };
+    STRATEGIES.save(deps.storage, id, &rra)?;
+
+    let event = Event::new("strategy_created")
+        .add_attribute("action", "execute_create_strategy")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("strategy_id", id.to_string())
+        .add_attribute("name", rra.name)
+        .add_attribute("path", path)
+        .add_attribute("target_rate", target_rate.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "create_strategy"))
+}
+
+/// Record base tokens liquid staked through every layer of a strategy at the layers' current
+/// redemption rates. Only the owner can do this.
+fn execute_stake_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    strategy_id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut rra = load_active_strategy(deps.storage, strategy_id)?;
+    sync_strategy_layer_rates(deps.storage, &mut rra)?;
+    let steps = strategy::stake(&mut rra.layers, amount).map_err(|e| {
+        ContractError::InvalidStrategyPath {
+            reason: e.to_string(),
+        }
+    })?;
+    let events = save_strategy_steps(deps.storage, &env, &rra, steps)?;
+    STRATEGIES.save(deps.storage, strategy_id, &rra)?;
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_event(strategy_event("strategy_staked", &rra, &env))
+        .add_attribute("method", "stake_strategy"))
+}
+
+/// Unwind a strategy layer by layer without waiting for its target rate. Only the owner can do this.
+fn execute_unwind_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    strategy_id: u64,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut rra = load_active_strategy(deps.storage, strategy_id)?;
+    sync_strategy_layer_rates(deps.storage, &mut rra)?;
+    let res = unwind_strategy(deps.storage, &env, &mut rra)?;
+
+    Ok(res.add_attribute("method", "unwind_strategy"))
+}
+
+fn load_active_strategy(
+    storage: &dyn Storage,
+    strategy_id: u64,
+) -> Result<RraStrategy, ContractError> {
+    let rra = STRATEGIES
+        .may_load(storage, strategy_id)?
+        .ok_or(ContractError::StrategyNotFound { strategy_id })?;
+    if rra.status != StrategyStatus::Active {
+        return Err(ContractError::StrategyNotActive { strategy_id });
+    }
+    Ok(rra)
+}
+
+/// Copy each layer's host zone redemption rate onto the strategy.
+fn sync_strategy_layer_rates(
+    storage: &dyn Storage,
+    rra: &mut RraStrategy,
+) -> Result<(), ContractError> {
+    for layer in rra.layers.iter_mut() {
+        if let Some(record) = HOST_ZONE_REDEMPTION_RATES.may_load(storage, &layer.zone_id)? {
+            layer.redemption_rate = record.redemption_rate;
+        }
+    }
+    Ok(())
+}
+
+/// Run the unwind sequence of a strategy, record its steps and mark it unwound.
+fn unwind_strategy(
+    storage: &mut dyn Storage,
+    env: &Env,
+    rra: &mut RraStrategy,
+) -> Result<Response, ContractError> {
+    let compounded_rate = strategy::compounded_rate(&rra.layers);
+    let steps = strategy::unwind(&mut rra.layers);
+    let redeemed = steps.last().map(|step| step.amount_out).unwrap_or_default();
+    let events = save_strategy_steps(storage, env, rra, steps)?;
+    rra.status = StrategyStatus::Unwound;
+    STRATEGIES.save(storage, rra.id, rra)?;
+
+    let event = strategy_event("strategy_unwound", rra, env)
+        .add_attribute("unwind_rate", compounded_rate.to_string())
+        .add_attribute("redeemed_base_amount", redeemed.to_string());
+
+    Ok(Response::new().add_events(events).add_event(event))
+}
+
+/// Refresh the layer rates of every active strategy and unwind the ones whose compounded rate
+/// has reached their target. Called by the redemption rate cron task.
+fn update_strategies(storage: &mut dyn Storage, env: &Env) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    let strategies = STRATEGIES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, rra)| rra))
+        .collect::<StdResult<Vec<RraStrategy>>>()?;
+
+    for mut rra in strategies {
+        if rra.status != StrategyStatus::Active {
+            continue;
+        }
+        sync_strategy_layer_rates(storage, &mut rra)?;
+        let has_position = rra.layers.last().is_some_and(|top| !top.position.is_zero());
+        if has_position && strategy::compounded_rate(&rra.layers) >= rra.target_rate {
+            let unwind_res = unwind_strategy(storage, env, &mut rra)?;
+            res = res.add_events(unwind_res.events);
+        } else {
+            STRATEGIES.save(storage, rra.id, &rra)?;
+        }
+    }
+
+    Ok(res)
+}
+
+/// Store planned steps under the strategy with increasing ids and return an event per step.
+fn save_strategy_steps(
+    storage: &mut dyn Storage,
+    env: &Env,
+    rra: &RraStrategy,
+    steps: Vec<PlannedStep>,
+) -> Result<Vec<Event>, ContractError> {
+    let first_id = STRATEGY_STEPS
+        .prefix(rra.id)
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |id| id + 1);
+
+    let mut events = vec![];
+    for (id, planned) in (first_id..).zip(steps) {
+        let step = StrategyStep {
+            id,
+            strategy_id: rra.id,
+            action: planned.action,
+            layer: planned.layer,
+            amount_in: planned.amount_in,
+            amount_out: planned.amount_out,
+            redemption_rate: planned.redemption_rate,
+            timestamp: env.block.time.seconds(),
+            block_height: env.block.height,
+        };
+        STRATEGY_STEPS.save(storage, (rra.id, id), &step)?;
+
+        let layer = &rra.layers[step.layer as usize];
+        events.push(
+            Event::new("strategy_step")
+                .add_attribute("strategy_id", rra.id.to_string())
+                .add_attribute("step_id", step.id.to_string())
+                .add_attribute("action", format!("{:?}", step.action).to_lowercase())
+                .add_attribute("layer", step.layer.to_string())
+                .add_attribute("zone_id", layer.zone_id.clone())
+                .add_attribute("amount_in", step.amount_in.to_string())
+                .add_attribute("amount_out", step.amount_out.to_string())
+                .add_attribute("redemption_rate", step.redemption_rate.to_string())
+                .add_attribute("block_height", env.block.height.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string()),
+        );
+    }
+
+    Ok(events)
+}
+
+fn strategy_event(name: &str, rra: &RraStrategy, env: &Env) -> Event {
+    Event::new(name)
+        .add_attribute("strategy_id", rra.id.to_string())
+        .add_attribute("compounded_rate", strategy::compounded_rate(&rra.layers).to_string())
+        .add_attribute("base_value", strategy::base_value(&rra.layers).to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string())
+}
+
+fn strategy_response(rra: RraStrategy) -> StrategyResponse {
+    StrategyResponse {
+        compounded_rate: strategy::compounded_rate(&rra.layers),
+        base_value: strategy::base_value(&rra.layers),
+        strategy: rra,
+    }
+}
+
+/// Set the thresholds and windows used by the Redemption Rate Advisor. Only the owner can do this.
+fn execute_set_advisor_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    advisor_config: AdvisorConfig,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if advisor_config.caution_rate >= advisor_config.redemption_rate {
+        return Err(ContractError::InvalidAdvisorConfig {
+            reason: "caution_rate must be below redemption_rate".to_string(),
+        });
+    }
+    if advisor_config.short_window == 0 || advisor_config.short_window > advisor_config.long_window {
+        return Err(ContractError::InvalidAdvisorConfig {
+            reason: "short_window must be between 1 and long_window".to_string(),
+        });
+    }
+    ADVISOR_CONFIG.save(deps.storage, &advisor_config)?;
+
+    let event = Event::new("set_advisor_config")
+        .add_attribute("action", "execute_set_advisor_config")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("caution_rate", advisor_config.caution_rate.to_string())
+        .add_attribute("redemption_rate", advisor_config.redemption_rate.to_string())
+        .add_attribute("short_window", advisor_config.short_window.to_string())
+        .add_attribute("long_window", advisor_config.long_window.to_string())
+        .add_attribute("projection_seconds", advisor_config.projection_seconds.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_advisor_config"))
+}
+
+/// The last `count` redemption rates recorded for a host zone, oldest first.
+fn recent_redemption_rates(
+    storage: &dyn Storage,
+    zone_id: &str,
+    count: u32,
+) -> Result<Vec<(u64, Decimal)>, ContractError> {
+    let mut rates = REDEMPTION_RATE_HISTORY
+        .prefix(zone_id)
+        .range(storage, None, None, Order::Descending)
+        .take(count as usize)
+        .collect::<StdResult<Vec<(u64, Decimal)>>>()?;
+    rates.reverse();
+    Ok(rates)
+}
+
+/// Advisor inputs for a host zone: its recent rate history, current rate and projected rate.
+struct AdvisorRates {
+    config: AdvisorConfig,
+    history: Vec<(u64, Decimal)>,
+    current: Decimal,
+    projected: Decimal,
+}
+
+/// Current and projected redemption rate of a host zone under the advisor config.
+fn advisor_rates(storage: &dyn Storage, zone_id: &str) -> Result<AdvisorRates, ContractError> {
+    let advisor_config = ADVISOR_CONFIG.may_load(storage)?.unwrap_or_default();
+    let rates = recent_redemption_rates(storage, zone_id, advisor_config.long_window)?;
+    let no_rate = || ContractError::NoRedemptionRate {
+        zone_id: zone_id.to_string(),
+    };
+    let current = rates.last().map(|(_, rate)| *rate).ok_or_else(no_rate)?;
+    let projected = advisor::projected_rate(
+        &rates,
+        advisor_config.long_window,
+        advisor_config.projection_seconds,
+    )
+    .ok_or_else(no_rate)?;
+    Ok(AdvisorRates {
+        config: advisor_config,
+        history: rates,
+        current,
+        projected,
+    })
+}
+
+/// Host zone with the largest weight in a contract's strategy, or the default host zone.
+fn main_host_zone(storage: &dyn Storage, contract: &Addr) -> Result<String, ContractError> {
+    let strategy = CONTRACT_METADATA
+        .may_load(storage, contract)?
+        .map(|meta| meta.strategy)
+        .unwrap_or_default();
+    // max_by_key returns the last maximum; reverse so ties go to the first allocation.
+    match strategy.iter().rev().max_by_key(|allocation| allocation.weight) {
+        Some(allocation) => Ok(allocation.zone_id.clone()),
+        None => DEFAULT_HOST_ZONE
+            .may_load(storage)?
+            .ok_or(ContractError::NoDefaultHostZone {}),
+    }
+}
+
+/// Simulate a stake or redeem of `amount` on the default host zone at the current and projected
+/// redemption rates.
+fn simulate_on_default_zone(
+    storage: &dyn Storage,
+    amount: Uint128,
+    output: fn(Uint128, Decimal) -> Uint128,
+) -> Result<SimulationResponse, ContractError> {
+    let zone_id = DEFAULT_HOST_ZONE
+        .may_load(storage)?
+        .ok_or(ContractError::NoDefaultHostZone {})?;
+    let rates = advisor_rates(storage, &zone_id)?;
+    Ok(SimulationResponse {
+        zone_id,
+        amount_in: amount,
+        redemption_rate: rates.current,
+        amount_out: output(amount, rates.current),
+        projected_rate: rates.projected,
+        projected_amount_out: output(amount, rates.projected),
+    })
+}
+
+/// Dry-run the cron job at `at_time` and `at_height` on a storage overlay and report what it would change.
+/// With `with_distribution` the overlay also distributes liquidity before the stake ratios are read.
+fn simulate_cron_job(
+    deps: Deps,
+    mut env: Env,
+    at_time: Option<u64>,
+    at_height: Option<u64>,
+    with_distribution: bool,
+) -> Result<CronSimulationResponse, ContractError> {
+    if let Some(at_time) = at_time {
+        env.block.time = Timestamp::from_seconds(at_time);
+    }
+    if let Some(at_height) = at_height {
+        env.block.height = at_height;
+    }
+
+    let mut overlay = StorageOverlay::new(deps.storage);
+    let res = run_cron_tasks(&mut overlay, &deps.querier, &env)?;
+    let tasks = res
+        .attributes
+        .into_iter()
+        .filter(|attr| attr.key == "task")
+        .map(|attr| attr.value)
+        .collect();
+
+    // Compare deposit records before and after the run.
+    let before = all_deposit_records(deps.storage)?
+        .into_iter()
+        .map(|record| ((record.contract_address.clone(), record.id), record.status))
+        .collect::<HashMap<(Addr, u64), DepositStatus>>();
+    let mut created_deposit_records = vec![];
+    let mut dispatched_deposit_records = vec![];
+    let mut completed_deposit_records = vec![];
+    for record in all_deposit_records(&overlay)? {
+        let previous = before.get(&(record.contract_address.clone(), record.id));
+        if previous.is_none() {
+            created_deposit_records.push(record.clone());
+        }
+        if previous == Some(&record.status) {
+            continue;
+        }
+        match record.status {
+            DepositStatus::InFlight => dispatched_deposit_records.push(record),
+            DepositStatus::Completed => completed_deposit_records.push(record),
+            _ => {}
+        }
+    }
+
+    // The cron job leaves distribution to `DistributeLiquidity {}`, so only run it when asked.
+    if with_distribution {
+        distribute_liquidity(&mut overlay, &env)?;
+    }
+    let stake_ratios = get_all_stake_ratios(&overlay)?;
+
+    Ok(CronSimulationResponse {
+        at_time: env.block.time.seconds(),
+        at_height: env.block.height,
+        tasks,
+        created_deposit_records,
+        dispatched_deposit_records,
+        completed_deposit_records,
+        stake_ratios,
+    })
+}
+
+/// Every deposit record of every contract.
+fn all_deposit_records(storage: &dyn Storage) -> Result<Vec<DepositRecord>, ContractError> {
+    let records = deposit_records()
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<DepositRecord>>>()?;
+    Ok(records)
+}
+
+/// Deposit records of one contract, by id.
+fn contract_deposit_records(storage: &dyn Storage, contract: &Addr) -> Result<Vec<DepositRecord>, ContractError> {
+    let records = deposit_records()
+        .prefix(contract)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<DepositRecord>>>()?;
+    Ok(records)
+}
+
+/// Deposit records of every contract in a status, by contract and id.
+fn records_with_status(storage: &dyn Storage, status: DepositStatus) -> Result<Vec<DepositRecord>, ContractError> {
+    let records = deposit_records()
+        .idx
+        .status
+        .prefix(status.to_string())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<DepositRecord>>>()?;
+    Ok(records)
+}
+
+/// ARCH of `arch_denom` held for deposits that have not reached a host zone: pending records and
+/// failed ones waiting for a retry. Records without a zone go to the default zone.
+fn undelivered_deposits(storage: &dyn Storage, arch_denom: &str) -> Result<Uint128, ContractError> {
+    let default_zone = DEFAULT_HOST_ZONE.may_load(storage)?;
+    let mut zone_denoms: HashMap<String, String> = HashMap::new();
+    let mut total = Uint128::zero();
+    for status in [DepositStatus::Pending, DepositStatus::Failed] {
+        for record in records_with_status(storage, status)? {
+            let Some(zone_id) = record.zone_id.or_else(|| default_zone.clone()) else {
+                continue;
+            };
+            if !zone_denoms.contains_key(&zone_id) {
+                let denom = load_host_zone(storage, &zone_id)?.arch_denom;
+                zone_denoms.insert(zone_id.clone(), denom);
+            }
+            if zone_denoms[&zone_id] == arch_denom {
+                total += record.amount;
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// Check that a host zone is complete and normalise its oracle address.
+fn validate_host_zone(api: &dyn Api, mut zone: HostZone) -> Result<HostZone, ContractError> {
+    if zone.channel_id.is_empty()
+        || zone.arch_denom.is_empty()
+        || zone.lst_denom.is_empty()
+        || zone.receiver.is_empty()
+    {
+        return Err(ContractError::InvalidHostZone {
+            reason: "channel_id, arch_denom, lst_denom and receiver must be set".to_string(),
+        });
+    }
+    if zone.timeout_seconds == 0 {
+        return Err(ContractError::InvalidHostZone {
+            reason: "timeout_seconds must be greater than zero".to_string(),
+        });
+    }
+    if let RedemptionRateSource::Oracle { contract_address } = &zone.redemption_rate_source {
+        zone.redemption_rate_source = RedemptionRateSource::Oracle {
+            contract_address: api.addr_validate(contract_address.as_str())?,
+        };
+    }
+    Ok(zone)
+}
+
+fn load_host_zone(storage: &dyn Storage, zone_id: &str) -> Result<HostZone, ContractError> {
+    HOST_ZONES
+        .may_load(storage, zone_id)?
+        .ok_or_else(|| ContractError::HostZoneNotFound {
+            zone_id: zone_id.to_string(),
+        })
+}
+
+/// Event describing the full configuration of a host zone.
+fn host_zone_event(name: &str, zone: &HostZone, env: &Env) -> Event {
+    let redemption_rate_source = match &zone.redemption_rate_source {
+        RedemptionRateSource::Reported => "reported".to_string(),
+        RedemptionRateSource::Oracle { contract_address } => contract_address.to_string(),
+    };
+    Event::new(name)
+        .add_attribute("zone_id", zone.zone_id.clone())
+        .add_attribute("protocol", format!("{:?}", zone.protocol).to_lowercase())
+        .add_attribute("channel_id", zone.channel_id.clone())
+        .add_attribute("arch_denom", zone.arch_denom.clone())
+        .add_attribute("lst_denom", zone.lst_denom.clone())
+        .add_attribute("receiver", zone.receiver.clone())
+        .add_attribute("timeout_seconds", zone.timeout_seconds.to_string())
+        .add_attribute("redemption_rate_source", redemption_rate_source)
+        .add_attribute("unbonding_period_seconds", zone.unbonding_period_seconds.to_string())
+        .add_attribute("refund_target", refund_target_name(&zone.refund_target))
+        .add_attribute("enabled", zone.enabled.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string())
+}
+
+/// Store the latest redemption rate of a host zone.
+fn save_redemption_rate(
+    storage: &mut dyn Storage,
+    env: &Env,
+    zone_id: &str,
+    redemption_rate: Decimal,
+) -> Result<Event, ContractError> {
+    let record = RedemptionRateRecord {
+        redemption_rate,
+        timestamp: env.block.time.seconds(),
+        block_height: env.block.height,
+    };
+    HOST_ZONE_REDEMPTION_RATES.save(storage, zone_id, &record)?;
+    REDEMPTION_RATE_HISTORY.save(storage, (zone_id, record.timestamp), &redemption_rate)?;
+
+    Ok(Event::new("redemption_rate_updated")
+        .add_attribute("zone_id", zone_id)
+        .add_attribute("redemption_rate", redemption_rate.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string()))
+}
+
+/// Owner-only: start verifying EmitLiquidStakeEvent reports against the contract's balance
+/// of `starch_denom`. The balance at this point is the baseline for the next report.
+fn execute_enable_liquid_stake_verification(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    starch_denom: String,
+    tolerance_bps: u64,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if starch_denom.is_empty() {
+        return Err(ContractError::InvalidLiquidStakeVerification {
+            reason: "starch_denom must be set".to_string(),
+        });
+    }
+    if tolerance_bps > 10_000 {
+        return Err(ContractError::InvalidLiquidStakeVerification {
+            reason: "tolerance_bps must not exceed 10000".to_string(),
+        });
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &starch_denom)?
+        .amount;
+    let verification = LiquidStakeVerification {
+        starch_denom,
+        tolerance_bps,
+        last_balance: balance,
+    };
+    LIQUID_STAKE_VERIFICATION.save(deps.storage, &verification)?;
+
+    let event = Event::new("liquid_stake_verification_enabled")
+        .add_attribute("action", "execute_enable_liquid_stake_verification")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("starch_denom", verification.starch_denom)
+        .add_attribute("tolerance_bps", tolerance_bps.to_string())
+        .add_attribute("starch_balance", balance.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "enable_liquid_stake_verification"))
+}
+
+/// Owner-only: stop verifying EmitLiquidStakeEvent reports.
+fn execute_disable_liquid_stake_verification(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    LIQUID_STAKE_VERIFICATION.remove(deps.storage);
+
+    let event = Event::new("liquid_stake_verification_disabled")
+        .add_attribute("action", "execute_disable_liquid_stake_verification")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "disable_liquid_stake_verification"))
+}
+
+fn validate_fee_bps(fee_bps: u64) -> Result<(), ContractError> {
+    if fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee {
+            reason: format!("{fee_bps} bps exceeds {MAX_FEE_BPS} bps"),
+        });
+    }
+    Ok(())
+}
+
+/// Set the protocol fee kept from converted rewards. Only the owner can do this.
+fn execute_set_protocol_fee(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_bps: u64,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_fee_bps(fee_bps)?;
+
+    config.protocol_fee_bps = fee_bps;
+    CONFIG.save(deps.storage, &config)?;
+
+    let event = Event::new("set_protocol_fee")
+        .add_attribute("action", "execute_set_protocol_fee")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("fee_bps", fee_bps.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_protocol_fee"))
+}
+
+/// Set the performance fee kept from redemption gains. Only the owner can do this.
+fn execute_set_performance_fee(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    fee_bps: u64,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_fee_bps(fee_bps)?;
+
+    config.performance_fee_bps = fee_bps;
+    CONFIG.save(deps.storage, &config)?;
+
+    let event = Event::new("set_performance_fee")
+        .add_attribute("action", "execute_set_performance_fee")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("fee_bps", fee_bps.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_performance_fee"))
+}
+
+/// Override the protocol fee of one contract, or clear the override. Only the owner can do this.
+fn execute_set_contract_fee(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract_address: String,
+    fee_bps: Option<u64>,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(fee_bps) = fee_bps {
+        validate_fee_bps(fee_bps)?;
+    }
+
+    let contract_addr = deps.api.addr_validate(&contract_address)?;
+    let mut metadata = CONTRACT_METADATA
+        .may_load(deps.storage, &contract_addr)?
+        .ok_or_else(|| ContractError::ContractMetadataNotFound {
+            contract: contract_address.clone(),
+        })?;
+    metadata.fee_bps = fee_bps;
+    CONTRACT_METADATA.save(deps.storage, &contract_addr, &metadata)?;
+
+    let event = Event::new("set_contract_fee")
+        .add_attribute("action", "execute_set_contract_fee")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("contract_address", contract_address)
+        .add_attribute("fee_bps", fee_bps.map_or("default".to_string(), |bps| bps.to_string()))
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_contract_fee"))
+}
+
+/// Send protocol fees from the treasury to `recipient`. Only the owner can do this.
+fn execute_withdraw_treasury(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    denom: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let treasury = TREASURY
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default()
+        .checked_sub(amount)
+        .map_err(|e| ContractError::Std(StdError::Overflow { source: e }))?;
+    TREASURY.save(deps.storage, &denom, &treasury)?;
+
+    let event = Event::new("treasury_withdrawn")
+        .add_attribute("action", "execute_withdraw_treasury")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.clone())
+        .add_attribute("treasury", treasury.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        })
+        .add_event(event)
+        .add_attribute("method", "withdraw_treasury"))
+}
+
+/// Create the TokenFactory receipt token. Only the owner can do this, once.
+fn execute_create_receipt_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    subdenom: String,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(denom) = RECEIPT_TOKEN_DENOM.may_load(deps.storage)? {
+        return Err(ContractError::InvalidReceiptToken {
+            reason: format!("{denom} already exists"),
+        });
+    }
+    if subdenom.is_empty() {
+        return Err(ContractError::InvalidReceiptToken {
+            reason: "subdenom must be set".to_string(),
+        });
+    }
+
+    let denom = tokenfactory::factory_denom(&env, &subdenom);
+    RECEIPT_TOKEN_DENOM.save(deps.storage, &denom)?;
+
+    let event = Event::new("receipt_token_created")
+        .add_attribute("action", "execute_create_receipt_token")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("denom", denom)
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_message(tokenfactory::create_denom_msg(&env, &subdenom))
+        .add_event(event)
+        .add_attribute("method", "create_receipt_token"))
+}
+
+/// Mint receipt tokens for a completed deposit, if the receipt token exists. The deposit's shares
+/// move from the dApp to RECEIPT_SHARES, so they follow the tokens rather than the dApp.
+fn receipt_mint_msg(
+    storage: &mut dyn Storage,
+    env: &Env,
+    contract: &Addr,
+    amount: Uint128,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    let Some(denom) = RECEIPT_TOKEN_DENOM.may_load(storage)? else {
+        return Ok(None);
+    };
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    let shares = SHARES.may_load(storage, contract)?.unwrap_or_default();
+    SHARES.save(storage, contract, &(shares - amount))?;
+    let receipt_shares = RECEIPT_SHARES.may_load(storage)?.unwrap_or_default();
+    RECEIPT_SHARES.save(storage, &(receipt_shares + amount))?;
+    Ok(Some(tokenfactory::mint_msg(env, coin(amount.u128(), denom), contract.as_str())))
+}
+
+/// Burn the receipt tokens sent by any holder, along with the receipt shares backing them, and
+/// queue the liquid staking tokens those shares are worth, at the pool exchange rate and the
+/// redemption zone's redemption rate, as the holder's pending redeem tokens. The tokens leave
+/// the pool now, so the batch that later redeems them does not burn any more shares for them.
+fn execute_redeem_receipt_tokens(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let denom = RECEIPT_TOKEN_DENOM.may_load(deps.storage)?.ok_or_else(|| {
+        ContractError::InvalidReceiptToken {
+            reason: "no receipt token has been created".to_string(),
+        }
+    })?;
+    let amount = match info.funds.as_slice() {
+        [funds] if funds.denom == denom && !funds.amount.is_zero() => funds.amount,
+        _ => return Err(ContractError::InvalidFunds {}),
+    };
+    let zone_id = redemption_zone(deps.storage)?.zone_id;
+
+    let value = amount.mul_floor(exchange_rate(deps.storage)?);
+    let lst_amount = reported_rate(deps.storage, &zone_id)?
+        .map_or(value, |rate| value.div_floor(rate));
+
+    // One receipt share per token; the token supply never exceeds them.
+    let receipt_shares = RECEIPT_SHARES.may_load(deps.storage)?.unwrap_or_default();
+    RECEIPT_SHARES.save(deps.storage, &receipt_shares.checked_sub(amount).map_err(StdError::from)?)?;
+    let total_shares = TOTAL_SHARES.may_load(deps.storage)?.unwrap_or_default();
+    TOTAL_SHARES.save(deps.storage, &(total_shares - amount))?;
+    let pool_lst = POOL_LST.may_load(deps.storage, &zone_id)?.unwrap_or_default();
+    let pool_lst = pool_lst.checked_sub(lst_amount).map_err(|_| ContractError::InsufficientPoolTokens {
+        zone_id: zone_id.clone(),
+        available: pool_lst,
+        requested: lst_amount,
+    })?;
+    POOL_LST.save(deps.storage, &zone_id, &pool_lst)?;
+
+    REDEMPTION_LEDGER.update(deps.storage, &info.sender, |ledger| {
+        let mut ledger = ledger.unwrap_or_default();
+        ledger.pending += lst_amount;
+        ledger.burned += lst_amount;
+        Ok::<_, StdError>(ledger)
+    })?;
+
+    let event = Event::new("receipt_tokens_redeemed")
+        .add_attribute("holder", info.sender.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.clone())
+        .add_attribute("value", value.to_string())
+        .add_attribute("redeem_tokens_amount", lst_amount.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_message(tokenfactory::burn_msg(&env, coin(amount.u128(), denom)))
+        .add_event(event)
+        .add_attribute("method", "redeem_receipt_tokens"))
+}
+
+/// Send the sender everything credited to it in `denom`, both as a payee and, for a registered
+/// contract, the payouts credited to its configured payout addresses.
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let balances = claimable_balances(deps.storage, &info.sender)?;
+    let mut amount = Uint128::zero();
+    for ((payee, contract, _), balance) in balances.into_iter().filter(|((_, _, d), _)| *d == denom) {
+        CLAIMABLE.remove(deps.storage, (&payee, &contract, &denom));
+        amount += balance;
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim { denom });
+    }
+    adjust_verified_balance(deps.storage, &denom, |balance| balance.saturating_sub(amount))?;
+    CLAIMABLE_TOTALS.update(deps.storage, &denom, |owed| {
+        Ok::<_, StdError>(owed.unwrap_or_default().saturating_sub(amount))
+    })?;
+
+    let event = Event::new("claim")
+        .add_attribute("recipient", info.sender.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom.clone())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), &denom)],
+        })
+        .add_event(event)
+        .add_attribute("method", "claim")
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// CLAIMABLE key (payee, contract, denom) and balance.
+type ClaimableBalance = ((Addr, Addr, String), Uint128);
+
+/// Claimable balances `address` can claim: those credited to it as a payee and, if it is a
+/// registered contract, those credited to its payout addresses.
+fn claimable_balances(storage: &dyn Storage, address: &Addr) -> Result<Vec<ClaimableBalance>, ContractError> {
+    let mut balances = CLAIMABLE
+        .sub_prefix(address)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|((contract, denom), amount)| ((address.clone(), contract, denom), amount)))
+        .collect::<StdResult<Vec<_>>>()?;
+    if let Some(metadata) = CONTRACT_METADATA.may_load(storage, address)? {
+        let mut payees = vec![metadata.redemption_address, metadata.liquidity_provider_address];
+        payees.dedup();
+        for payee in payees.into_iter().filter(|payee| payee != address) {
+            let payouts = CLAIMABLE
+                .prefix((&payee, address))
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+            balances.extend(
+                payouts
+                    .into_iter()
+                    .map(|(denom, amount)| ((payee.clone(), address.clone(), denom), amount)),
+            );
+        }
+    }
+    Ok(balances)
+}
+
+/// Add `amount` of `denom` owed to `contract` to the claimable balance `payee` holds for it.
+/// The payee and, if it is registered, the contract can claim it.
+fn credit_claimable(
+    storage: &mut dyn Storage,
+    env: &Env,
+    contract: &Addr,
+    payee: &Addr,
+    denom: &str,
+    amount: Uint128,
+    source: &str,
+) -> Result<Event, ContractError> {
+    let claimable = CLAIMABLE.may_load(storage, (payee, contract, denom))?.unwrap_or_default() + amount;
+    CLAIMABLE.save(storage, (payee, contract, denom), &claimable)?;
+    let owed = CLAIMABLE_TOTALS.may_load(storage, denom)?.unwrap_or_default() + amount;
+    CLAIMABLE_TOTALS.save(storage, denom, &owed)?;
+
+    Ok(Event::new("claimable_credited")
+        .add_attribute("contract_address", contract.to_string())
+        .add_attribute("payee", payee.to_string())
+        .add_attribute("source", source)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("denom", denom)
+        .add_attribute("claimable", claimable.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string()))
+}
+
+/// Execute function to update a specific contract's reward. Only the owner can do this.
+/// This ensures that only authorized users can modify reward amounts for contracts.
+fn execute_update_reward(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract_address: String,
+    amount: Uint128,
+    env: Env,
+) -> Result<Response, ContractError> {
+    // Authorization check against contract owner.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Validate the contract address to ensure it's a properly formed bech32 address.
+    let rewards_addr = deps.api.addr_validate(&contract_address)?;
+    add_reward_to_contract(deps.storage, &rewards_addr, amount, &env)?;
+
+    // Emit an event indicating the reward was successfully updated.
+    let event = Event::new("update_reward")
+        .add_attribute("action", "execute_update_reward")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("contract_address", rewards_addr.clone())
+        .add_attribute("reward_amount", amount.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "update_reward"))
+}
+
+/// Update rewards for multiple contracts at once, reducing transaction overhead for the owner.
+/// The owner can set rewards for multiple contracts in a single call.
+fn execute_bulk_update_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+    updates: Vec<RewardUpdate>,
+    env: Env,
+) -> Result<Response, ContractError> {
+    // Only the owner can bulk update rewards.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut res = Response::new();
+
+    // Process each update in the provided vector of updates.
+    for update in &updates {
+        let contract_addr = deps.api.addr_validate(&update.contract_address)?;
+        let event = add_reward_to_contract(deps.storage, &contract_addr, update.amount, &env)?;
+
+        // Emit events for each contract updated in bulk.
+        let update_event = Event::new("update_reward")
+            .add_attribute("action", "execute_bulk_update_rewards")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("contract_address", update.contract_address.clone())
+            .add_attribute("reward_amount", update.amount.to_string())
+            .add_attribute("block_height", env.block.height.to_string())
+            .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+        // Add both the event from add_reward_to_contract and the update_event to the response.
+        res = res.add_event(event);
+        res = res.add_event(update_event);
+    }
+
+    // Indicate the method used in the response attributes.
+    res = res.add_attribute("method", "bulk_update_rewards");
+
+    Ok(res)
+}
+
+/// Adds a specified reward amount to a contract's reward balance. This is a helper function used by 
+/// execute_update_reward and execute_bulk_update_rewards to actually modify storage.
+///
+/// Arguments:
+/// - storage: state storage for reading/writing contract state
+/// - rewards_addr: address of the contract whose rewards are being updated
+/// - amount: the amount of rewards to add
+/// - env: environment for block info (for event attributes)
+fn add_reward_to_contract(
+    storage: &mut dyn Storage,
+    rewards_addr: &Addr,
+    amount: Uint128,
+    env: &Env,
+) -> Result<Event, ContractError> {
+    // Load the current reward amount; default to zero if not set.
+    let current_reward = CONTRACT_REWARDS
+        .may_load(storage, rewards_addr)?
+        .unwrap_or_default();
+    let new_reward = current_reward + amount;
+
+    // Save the updated reward amount.
+    CONTRACT_REWARDS.save(storage, rewards_addr, &new_reward)?;
+
+    // Emit an event indicating successful addition of rewards to the contract.
+    let event = Event::new("add_reward_to_contract")
+        .add_attribute("action", "add_reward_to_contract")
+        .add_attribute("contract_address", rewards_addr.to_string())
+        .add_attribute("reward_amount_added", amount.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    Ok(event)
+}
+
+/// Execute a cron job to process tasks that are due based on the elapsed time since their last run.
+/// Tasks include handling liquid staking rewards, arch liquid stake intervals, and redemption rate queries.
+fn execute_cron_job(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    run_cron_tasks(deps.storage, &deps.querier, &env)
+}
+
+/// Run every periodic task whose interval has elapsed. Shared by `execute_cron_job` and the
+/// `SimulateCronJob` query, which runs it against a storage overlay. Each task that fires adds a
+/// "task" attribute to the response.
+fn run_cron_tasks(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    res = res.add_attribute("method", "execute_cron_job");
+
+    let config = CONFIG.load(storage)?;
+    let now = env.block.time.seconds();
+
+    // Run each task whose interval has elapsed.
+    for task in [
+        CronTask::LiquidStakingDappRewards,
+        CronTask::ArchLiquidStakeInterval,
+        CronTask::RedemptionRateQuery,
+        CronTask::RewardsWithdrawal,
+    ] {
+        if should_process_task(storage, task_key(&task), &task_schedule(&config, &task), env)? {
+            let task_res = run_task(storage, querier, env, &task)?;
+            // Add messages, attributes and events from the task result to the main response.
+            res = res.add_submessages(task_res.messages);
+            res = res.add_attributes(task_res.attributes);
+            res = res.add_events(task_res.events);
+        }
+    }
+
+    // Pay out unbonding batches that have completed.
+    let batch_res = process_unbonding_batches(storage, querier, env)?;
+    res = res.add_submessages(batch_res.messages);
+    res = res.add_events(batch_res.events);
+
+    // Emit a final event summarizing the cron job execution.
+    let event = Event::new("cron_job_executed")
+        .add_attribute("action", "execute_cron_job")
+        .add_attribute("timestamp", now.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("processed_tasks", format!("{:?}", res.attributes));
+
+    res = res.add_event(event);
+
+    Ok(res)
+}
+
+/// Set metadata for a given contract, controlling min/max reward amounts and 
+/// associated addresses for rewards and liquidity. Only the owner can call this.
+#[allow(clippy::too_many_arguments)]
+fn execute_set_contract_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract_address: String,
+    rewards_address: String,
+    liquidity_provider_address: String,
+    minimum_reward_amount: Uint128,
+    maximum_reward_amount: Uint128,
+    redemption_address: String,
+    env: Env,
+) -> Result<Response, ContractError> {
+    // Authorization: only the owner can set metadata.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Validate reward amount range.
+    if maximum_reward_amount < minimum_reward_amount {
+        return Err(ContractError::InvalidRewardAmountRange {});
+    }
+
+    // Create and save the ContractMetadata struct, keeping any strategy and fee already set.
+    let contract_addr = deps.api.addr_validate(&contract_address)?;
+    let (strategy, fee_bps) = CONTRACT_METADATA
+        .may_load(deps.storage, &contract_addr)?
+        .map(|meta| (meta.strategy, meta.fee_bps))
+        .unwrap_or_default();
+    let metadata = ContractMetadata {
+        rewards_address: deps.api.addr_validate(&rewards_address)?,
+        liquidity_provider_address: deps.api.addr_validate(&liquidity_provider_address)?,
+        minimum_reward_amount,
+        maximum_reward_amount,
+        redemption_address: deps.api.addr_validate(&redemption_address)?,
+        strategy,
+        fee_bps,
+    };
+
+    CONTRACT_METADATA.save(deps.storage, &contract_addr, &metadata)?;
+
+    // Emit an event indicating successful metadata setting.
+    let event = Event::new("set_contract_metadata")
+        .add_attribute("action", "execute_set_contract_metadata")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("contract_address", contract_address.clone())
+        .add_attribute("rewards_address", rewards_address.clone())
+        .add_attribute("liquidity_provider_address", liquidity_provider_address.clone())
+        .add_attribute("minimum_reward_amount", minimum_reward_amount.to_string())
+        .add_attribute("maximum_reward_amount", maximum_reward_amount.to_string())
+        .add_attribute("redemption_address", redemption_address.clone())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_contract_metadata")
+        .add_attribute("contract", contract_address))
+}
+
+/// Set how a contract's rewards are split across host zones. Only the owner can do this.
+/// Every zone must be registered, weights must be positive and a zone may appear only once.
+fn execute_set_contract_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract_address: String,
+    allocations: Vec<ZoneAllocation>,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let contract_addr = deps.api.addr_validate(&contract_address)?;
+    let mut metadata = CONTRACT_METADATA
+        .may_load(deps.storage, &contract_addr)?
+        .ok_or_else(|| ContractError::ContractMetadataNotFound {
+            contract: contract_address.clone(),
+        })?;
+
+    for (i, allocation) in allocations.iter().enumerate() {
+        load_host_zone(deps.storage, &allocation.zone_id)?;
+        if allocation.weight == 0 {
+            return Err(ContractError::InvalidStrategy {
+                reason: format!("weight of {} must be greater than zero", allocation.zone_id),
+            });
+        }
+        if allocations[..i].iter().any(|a| a.zone_id == allocation.zone_id) {
+            return Err(ContractError::InvalidStrategy {
+                reason: format!("{} is allocated more than once", allocation.zone_id),
+            });
+        }
+    }
+
+    let strategy = allocations
+        .iter()
+        .map(|a| format!("{}:{}", a.zone_id, a.weight))
+        .collect::<Vec<_>>()
+        .join(",");
+    metadata.strategy = allocations;
+    CONTRACT_METADATA.save(deps.storage, &contract_addr, &metadata)?;
+
+    let event = Event::new("set_contract_strategy")
+        .add_attribute("action", "execute_set_contract_strategy")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("contract_address", contract_address)
+        .add_attribute("strategy", strategy)
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "set_contract_strategy"))
+}
+
+/// Add stake for the sender. This increases the CONTRACT_STAKES mapping for the caller by the given amount.
+fn execute_add_stake(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    env: Env,
+) -> Result<Response, ContractError> {
+    // Update the stake in storage.
+    add_contract_stake(deps.storage, &info.sender, amount)?;
+
+    // Emit an event indicating the stake addition.
+    let event = Event::new("add_stake")
+        .add_attribute("action", "execute_add_stake")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("stake_amount", amount.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "add_stake")
+        .add_attribute("contract", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Reset all completed deposit records to pending for all contracts. Only the owner can do this.
+/// This might be used for testing or emergency measures.
+fn execute_reset_all_completed_deposit_records(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+) -> Result<Response, ContractError> {
+    // Authorization check.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Perform the reset operation in storage.
+    reset_all_completed_deposit_records(deps.storage)?;
+
+    // Emit an event indicating the operation.
+    let event = Event::new("reset_all_completed_deposit_records")
+        .add_attribute("action", "execute_reset_all_completed_deposit_records")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "reset_all_completed_deposit_records"))
 }
 
 /// Reset all redemption ratios to a clean state. Only the owner can perform this action.
@@ -561,6 +2037,215 @@ fn execute_reset_stake_ratios(
         .add_attribute("method", "reset_stake_ratios"))
 }
 
+/// Run a single periodic task on demand. Without `force` the task's interval must have elapsed,
+/// exactly as in the cron job. Only the owner or an operator can do this.
+fn execute_run_task(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    task: CronTask,
+    force: bool,
+) -> Result<Response, ContractError> {
+    // Owner or operator action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner && !OPERATORS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let key = task_key(&task);
+    let schedule = task_schedule(&config, &task);
+    if !force && !should_process_task(deps.storage, key, &schedule, &env)? {
+        let (last_time, last_height) = last_processed(deps.storage, key)?;
+        return Err(ContractError::TaskNotDue {
+            task: task.name().to_string(),
+            schedule: schedule.to_string(),
+            next_due: schedule.next_due(last_time, last_height),
+        });
+    }
+
+    let task_res = run_task(deps.storage, &deps.querier, &env, &task)?;
+
+    let event = Event::new("run_task")
+        .add_attribute("action", "execute_run_task")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("task", task.name())
+        .add_attribute("force", force.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(task_res
+        .add_event(event)
+        .add_attribute("method", "run_task"))
+}
+
+/// Run a periodic task and record the current time as its last processing time.
+fn run_task(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    task: &CronTask,
+) -> Result<Response, ContractError> {
+    let task_res = match task {
+        CronTask::LiquidStakingDappRewards => handle_liquid_staking_dapp_rewards(storage, env)?,
+        CronTask::ArchLiquidStakeInterval => handle_arch_liquid_stake_interval(storage, env)?,
+        CronTask::RedemptionRateQuery => handle_redemption_rate_query(storage, querier, env)?,
+        CronTask::RewardsWithdrawal => handle_rewards_withdrawal(env),
+    };
+    LAST_PROCESSING_TIMES.save(storage, task_key(task), &env.block.time.seconds())?;
+    LAST_PROCESSING_HEIGHTS.save(storage, task_key(task), &env.block.height)?;
+
+    let schedule = task_schedule(&CONFIG.load(storage)?, task);
+    let event = Event::new("cron_task_processed")
+        .add_attribute("task", task.name())
+        .add_attribute("schedule", schedule.to_string())
+        .add_attribute("next_due", schedule.next_due(env.block.time.seconds(), env.block.height).to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(task_res
+        .add_event(event)
+        .add_attribute("task", task.name()))
+}
+
+/// Time and height at which a task was last processed. Tasks last processed before heights were
+/// tracked report height zero.
+fn last_processed(storage: &dyn Storage, key: &str) -> Result<(u64, u64), ContractError> {
+    let last_time = LAST_PROCESSING_TIMES.load(storage, key)?;
+    let last_height = LAST_PROCESSING_HEIGHTS.may_load(storage, key)?.unwrap_or_default();
+    Ok((last_time, last_height))
+}
+
+/// Last and next run of a task at the current block.
+fn task_schedule_response(
+    storage: &dyn Storage,
+    env: &Env,
+    config: &Config,
+    task: CronTask,
+) -> Result<TaskScheduleResponse, ContractError> {
+    let key = task_key(&task);
+    let schedule = task_schedule(config, &task);
+    let (last_run_time, last_run_height) = last_processed(storage, key)?;
+    let next_due = schedule.next_due(last_run_time, last_run_height);
+    let (next_due_time, next_due_height) = match schedule {
+        Schedule::Seconds(_) => (Some(next_due), None),
+        Schedule::Blocks(_) => (None, Some(next_due)),
+    };
+
+    Ok(TaskScheduleResponse {
+        task,
+        key: key.to_string(),
+        schedule,
+        last_run_time,
+        last_run_height,
+        next_due_time,
+        next_due_height,
+        overdue: schedule.is_due(
+            last_run_time,
+            last_run_height,
+            env.block.time.seconds(),
+            env.block.height,
+        ),
+    })
+}
+
+/// LAST_PROCESSING_TIMES key of a task.
+fn task_key(task: &CronTask) -> &'static str {
+    match task {
+        CronTask::LiquidStakingDappRewards => LAST_LIQUID_STAKING_DAPP_REWARDS_TIME_KEY,
+        CronTask::ArchLiquidStakeInterval => LAST_ARCH_LIQUID_STAKE_INTERVAL_TIME_KEY,
+        CronTask::RedemptionRateQuery => LAST_REDEMPTION_RATE_QUERY_TIME_KEY,
+        CronTask::RewardsWithdrawal => LAST_REWARDS_WITHDRAWAL_TIME_KEY,
+    }
+}
+
+/// Configured schedule of a task.
+fn task_schedule(config: &Config, task: &CronTask) -> Schedule {
+    match task {
+        CronTask::LiquidStakingDappRewards => config.liquid_staking_interval,
+        CronTask::ArchLiquidStakeInterval => config.arch_liquid_stake_interval,
+        CronTask::RedemptionRateQuery => config.redemption_rate_query_interval,
+        CronTask::RewardsWithdrawal => config.rewards_withdrawal_interval,
+    }
+}
+
+/// Change how often a periodic task runs, in seconds or in blocks. Only the owner can do this.
+fn execute_update_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    task: CronTask,
+    schedule: Schedule,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let previous = task_schedule(&config, &task);
+    match task {
+        CronTask::LiquidStakingDappRewards => config.liquid_staking_interval = schedule,
+        CronTask::ArchLiquidStakeInterval => config.arch_liquid_stake_interval = schedule,
+        CronTask::RedemptionRateQuery => config.redemption_rate_query_interval = schedule,
+        CronTask::RewardsWithdrawal => config.rewards_withdrawal_interval = schedule,
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    // A task last processed before heights were tracked starts counting blocks from now.
+    let key = task_key(&task);
+    if !LAST_PROCESSING_HEIGHTS.has(deps.storage, key) {
+        LAST_PROCESSING_HEIGHTS.save(deps.storage, key, &env.block.height)?;
+    }
+
+    let event = Event::new("update_schedule")
+        .add_attribute("action", "execute_update_schedule")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("task", task.name())
+        .add_attribute("previous_schedule", previous.to_string())
+        .add_attribute("schedule", schedule.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "update_schedule"))
+}
+
+/// Add or remove an operator. Only the owner can do this.
+fn execute_set_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let operator = deps.api.addr_validate(&address)?;
+    let name = if enabled {
+        OPERATORS.save(deps.storage, &operator, &Empty {})?;
+        "add_operator"
+    } else {
+        OPERATORS.remove(deps.storage, &operator);
+        "remove_operator"
+    };
+
+    let event = Event::new(name)
+        .add_attribute("action", format!("execute_{}", name))
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("operator", operator.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", name))
+}
+
 /// Adds a given stake amount to the CONTRACT_STAKES map for a specific contract address.
 /// This is a fundamental operation called by functions that need to track added stakes.
 fn add_contract_stake(
@@ -576,16 +2261,29 @@ fn add_contract_stake(
     Ok(())
 }
 
-/// Checks if a given task should be processed now by comparing the current time with the last processed time
-/// and ensuring the specified interval has elapsed.
+/// Checks if a given task should be processed now by comparing the current block time or height
+/// with the last processed time or height and ensuring the task's schedule has elapsed.
 fn should_process_task(
     storage: &dyn Storage,
     key: &str,
-    interval_in_seconds: u64,
-    current_time: u64,
+    schedule: &Schedule,
+    env: &Env,
 ) -> Result<bool, ContractError> {
-    let last_time = LAST_PROCESSING_TIMES.load(storage, key)?;
-    Ok(current_time >= last_time + interval_in_seconds)
+    let (last_time, last_height) = last_processed(storage, key)?;
+    Ok(schedule.is_due(last_time, last_height, env.block.time.seconds(), env.block.height))
+}
+
+/// Withdraw this contract's accumulated Archway rewards to its rewards address.
+fn handle_rewards_withdrawal(env: &Env) -> Response {
+    let event = Event::new("handle_rewards_withdrawal")
+        .add_attribute("rewards_address", env.contract.address.to_string())
+        .add_attribute("records_limit", WITHDRAW_RECORDS_LIMIT.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Response::new()
+        .add_message(withdraw_rewards_msg(env))
+        .add_event(event)
 }
 
 /// Retrieves all contracts that have associated metadata stored in CONTRACT_METADATA.
@@ -607,13 +2305,14 @@ fn handle_liquid_staking_dapp_rewards(
     let mut res = Response::new();
 
     let reward_map = get_cumulative_reward_amount(storage)?;
+    let protocol_fee_bps = CONFIG.load(storage)?.protocol_fee_bps;
 
     // Process each contract: check its metadata, determine final reward amount, and create deposit records.
     let contracts = get_all_contracts(storage)?;
     for contract in contracts {
         let metadata = CONTRACT_METADATA.may_load(storage, &contract)?;
         if let Some(meta) = metadata {
-            let rewards_addr = Addr::unchecked(&meta.rewards_address);
+            let rewards_addr = meta.rewards_address.clone();
             let raw_amount = reward_map
                 .get(&contract)
                 .cloned()
@@ -626,80 +2325,386 @@ fn handle_liquid_staking_dapp_rewards(
                 raw_amount
             };
 
-            // Only proceed if the amount meets the minimum reward criteria.
-            if amount >= meta.minimum_reward_amount {
-                // Create a deposit record indicating a pending stake due to these rewards.
-                let record = create_contract_liquid_stake_deposit_record(
-                    storage,
-                    &contract,
-                    amount,
-                    &rewards_addr,
-                    env,
-                );
-
-                // Append this record to the DEPOSIT_RECORDS for the contract.
-                let mut records = DEPOSIT_RECORDS
-                    .may_load(storage, &contract)?
-                    .unwrap_or_default();
-                records.push(record.clone());
-                DEPOSIT_RECORDS.save(storage, &contract, &records)?;
+            // Only proceed if the amount meets the minimum reward criteria.
+            if amount >= meta.minimum_reward_amount {
+                // Create a pending deposit record for each host zone share of these rewards. The
+                // protocol fee on each share is kept in the treasury, in the ARCH denom of the
+                // share's host zone, and the rest is liquid staked. Without a host zone there is
+                // no denom to keep it in and no fee is charged. A 100% fee leaves nothing to deposit.
+                let fee_bps = meta.fee_bps.unwrap_or(protocol_fee_bps);
+                let mut deposited = Uint128::zero();
+                let shares = split_by_strategy(amount, &meta.strategy)
+                    .into_iter()
+                    .filter(|(_, share)| !share.is_zero());
+                for (zone_id, share) in shares {
+                    let fee_denom = zone_arch_denom(storage, zone_id.as_deref())?;
+                    let fee = match fee_denom {
+                        Some(_) => share.multiply_ratio(fee_bps, MAX_FEE_BPS),
+                        None => Uint128::zero(),
+                    };
+                    let share = share - fee;
+                    if let Some(fee_denom) = fee_denom.filter(|_| !fee.is_zero()) {
+                        let treasury = TREASURY.may_load(storage, &fee_denom)?.unwrap_or_default() + fee;
+                        TREASURY.save(storage, &fee_denom, &treasury)?;
+
+                        let fee_event = Event::new("protocol_fee")
+                            .add_attribute("contract_address", contract.to_string())
+                            .add_attribute("zone_id", zone_id.clone().unwrap_or_default())
+                            .add_attribute("fee_bps", fee_bps.to_string())
+                            .add_attribute("fee_amount", fee.to_string())
+                            .add_attribute("denom", fee_denom)
+                            .add_attribute("deposit_amount", share.to_string())
+                            .add_attribute("treasury", treasury.to_string())
+                            .add_attribute("block_height", env.block.height.to_string())
+                            .add_attribute("timestamp", env.block.time.seconds().to_string());
+                        res = res.add_event(fee_event);
+                    }
+                    if share.is_zero() {
+                        continue;
+                    }
+                    deposited += share;
+
+                    let mut record = create_contract_liquid_stake_deposit_record(
+                        storage,
+                        &contract,
+                        share,
+                        &rewards_addr,
+                        zone_id,
+                        env,
+                    );
+                    record.protocol_fee = fee;
+                    deposit_records().save(storage, (&contract, record.id), &record)?;
+
+                    // Emit an event indicating the processing of liquid staking rewards for this contract.
+                    let event = Event::new("handle_liquid_staking_dapp_rewards")
+                        .add_attribute("contract_address", contract.to_string())
+                        .add_attribute("pending_deposit_record_amount", share.to_string())
+                        .add_attribute("reward_address", rewards_addr.to_string())
+                        .add_attribute("deposit_record_id", record.id.to_string())
+                        .add_attribute("deposit_record_status", record.status.to_string())
+                        .add_attribute("zone_id", record.zone_id.unwrap_or_default())
+                        .add_attribute("block_height", env.block.height.to_string())
+                        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+                    res = res.add_event(event);
+                }
+
+                // Increase the contract's stake and reset its CONTRACT_REWARDS to zero since rewards are now accounted for.
+                add_contract_stake(storage, &contract, deposited)?;
+                CONTRACT_REWARDS.save(storage, &contract, &Uint128::zero())?;
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// ARCH denom of a host zone, or of the default zone for None. None without host zones.
+fn zone_arch_denom(storage: &dyn Storage, zone_id: Option<&str>) -> Result<Option<String>, ContractError> {
+    let zone_id = match zone_id {
+        Some(zone_id) => Some(zone_id.to_string()),
+        None => DEFAULT_HOST_ZONE.may_load(storage)?,
+    };
+    match zone_id {
+        Some(zone_id) => Ok(Some(load_host_zone(storage, &zone_id)?.arch_denom)),
+        None => Ok(None),
+    }
+}
+
+/// Split an amount across a strategy's host zones by weight. The last zone receives the rounding
+/// remainder and zones whose share rounds to zero are skipped. An empty strategy keeps the whole
+/// amount on the default host zone (None).
+fn split_by_strategy(amount: Uint128, strategy: &[ZoneAllocation]) -> Vec<(Option<String>, Uint128)> {
+    let total_weight: u64 = strategy.iter().map(|a| a.weight).sum();
+    if total_weight == 0 {
+        return vec![(None, amount)];
+    }
+
+    let mut remaining = amount;
+    let mut shares = vec![];
+    for (i, allocation) in strategy.iter().enumerate() {
+        let share = if i == strategy.len() - 1 {
+            remaining
+        } else {
+            amount.multiply_ratio(allocation.weight, total_weight)
+        };
+        remaining -= share;
+        if !share.is_zero() {
+            shares.push((Some(allocation.zone_id.clone()), share));
+        }
+    }
+    shares
+}
+
+/// Handle the arch liquid stake interval triggered by cron jobs. Without host zones it aggregates
+/// pending deposits into completed stakes and updates the total liquid stake. Once host zones are
+/// registered, pending deposits are transferred to their host zone (the default zone unless the
+/// contract's strategy says otherwise) and only complete once the transfer is acknowledged; they
+/// stay pending while their zone is disabled.
+fn handle_arch_liquid_stake_interval(
+    storage: &mut dyn Storage,
+    env: &Env,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+
+    // Each run of this interval starts a new liquid stake epoch for the receipt ledger.
+    let epoch = LIQUID_STAKE_EPOCH.may_load(storage)?.unwrap_or_default() + 1;
+    LIQUID_STAKE_EPOCH.save(storage, &epoch)?;
+
+    if let Some(default_zone) = DEFAULT_HOST_ZONE.may_load(storage)? {
+        // Send pending deposits to each enabled zone; completion happens in the IBC ack handler.
+        let zones = HOST_ZONES
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, zone)| zone))
+            .collect::<StdResult<Vec<HostZone>>>()?;
+        for zone in zones.into_iter().filter(|zone| zone.enabled) {
+            let is_default = zone.zone_id == default_zone;
+            let transfer_res = dispatch_liquid_stake_transfer(storage, env, &zone, is_default)?;
+            res = res.add_submessages(transfer_res.messages);
+            res = res.add_events(transfer_res.events);
+            res = res.add_attributes(transfer_res.attributes);
+        }
+    } else {
+        // Update total liquid stake by processing pending deposit records.
+        let total_stake_res = get_total_liquid_stake(storage, env)?;
+        res = res.add_submessages(total_stake_res.messages);
+        res = res.add_events(total_stake_res.events);
+        res = res.add_attributes(total_stake_res.attributes);
+    }
+
+    // Emit an event indicating the handling of arch liquid stake interval.
+    let event = Event::new("handle_arch_liquid_stake_interval")
+        .add_attribute("epoch", epoch.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    res = res.add_event(event);
+
+    Ok(res)
+}
+
+/// Move the pending deposit records allocated to a host zone to "in_flight" and transfer their
+/// combined amount to the zone to be liquid staked. Records without a zone go to the default
+/// zone. The packet sequence is attached to the transfer and its records in `reply` once the
+/// transfer has been accepted by the chain.
+fn dispatch_liquid_stake_transfer(
+    storage: &mut dyn Storage,
+    env: &Env,
+    zone: &HostZone,
+    is_default: bool,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    let mut total_amount = Uint128::zero();
+    let mut deposits = vec![];
+
+    for mut record in records_with_status(storage, DepositStatus::Pending)? {
+        let for_zone = match &record.zone_id {
+            Some(zone_id) => *zone_id == zone.zone_id,
+            None => is_default,
+        };
+        if !for_zone {
+            continue;
+        }
+        record.status = DepositStatus::InFlight;
+        record.zone_id = Some(zone.zone_id.clone());
+        record.ibc_channel = Some(zone.channel_id.clone());
+        record.ibc_sequence = None;
+        total_amount += record.amount;
+        deposits.push(TransferDeposit {
+            contract_address: record.contract_address.clone(),
+            deposit_record_id: record.id,
+            amount: record.amount,
+        });
+        deposit_records().save(storage, (&record.contract_address, record.id), &record)?;
+    }
+
+    // Nothing pending, nothing to send.
+    if total_amount.is_zero() {
+        return Ok(res);
+    }
+
+    let deposit_record_count = deposits.len();
+    let transfer = InFlightTransfer {
+        zone_id: zone.zone_id.clone(),
+        channel_id: zone.channel_id.clone(),
+        sequence: 0,
+        amount: total_amount,
+        denom: zone.arch_denom.clone(),
+        deposits,
+        status: TransferStatus::InFlight,
+        attempts: 1,
+        failure_reason: None,
+        timestamp: env.block.time.seconds(),
+        block_height: env.block.height,
+    };
+    res = res.add_submessage(send_liquid_stake_transfer(storage, env, zone, transfer)?);
+
+    // Emit an event describing the dispatched transfer.
+    let event = Event::new("liquid_stake_transfer_dispatched")
+        .add_attribute("zone_id", zone.zone_id.clone())
+        .add_attribute("channel_id", zone.channel_id.clone())
+        .add_attribute("receiver", zone.receiver.clone())
+        .add_attribute("amount", total_amount.to_string())
+        .add_attribute("denom", zone.arch_denom.clone())
+        .add_attribute("deposit_record_count", deposit_record_count.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    res = res.add_event(event);
+
+    Ok(res)
+}
+
+/// Queue a transfer until its sequence is known and build the MsgTransfer that sends it. Stride
+/// transfers carry an autopilot LiquidStake memo; other protocols liquid stake on receipt.
+fn send_liquid_stake_transfer(
+    storage: &mut dyn Storage,
+    env: &Env,
+    zone: &HostZone,
+    transfer: InFlightTransfer,
+) -> Result<SubMsg, ContractError> {
+    let pending_id = NEXT_PENDING_TRANSFER_ID.may_load(storage)?.unwrap_or_default();
+    NEXT_PENDING_TRANSFER_ID.save(storage, &(pending_id + 1))?;
+
+    let memo = match zone.protocol {
+        LiquidStakeProtocol::Stride => autopilot_memo(env, &zone.receiver, "LiquidStake")?,
+        _ => callback_memo(env)?,
+    };
+    let msg = transfer_msg(
+        env,
+        &zone.channel_id,
+        &zone.receiver,
+        coin(transfer.amount.u128(), &transfer.denom),
+        zone.timeout_seconds,
+        memo,
+    );
+    PENDING_TRANSFERS.save(storage, pending_id, &transfer)?;
+
+    Ok(SubMsg::reply_on_success(msg, IBC_TRANSFER_REPLY_ID))
+}
+
+/// Re-send every failed liquid stake transfer to its host zone, over the zone's current channel.
+/// Transfers to disabled zones are skipped with an event. Only the owner can do this.
+fn execute_retry_failed_transfers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    // Owner-only action.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
-                // Increase the contract's stake and reset its CONTRACT_REWARDS to zero since rewards are now accounted for.
-                add_contract_stake(storage, &contract, amount)?;
-                CONTRACT_REWARDS.save(storage, &contract, &Uint128::zero())?;
+    let failed: Vec<InFlightTransfer> = IN_FLIGHT_TRANSFERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, transfer)| transfer))
+        .filter(|item| item.as_ref().map_or(true, |transfer| transfer.status == TransferStatus::Failed))
+        .collect::<StdResult<Vec<_>>>()?;
 
-                // Emit an event indicating the processing of liquid staking rewards for this contract.
-                let event = Event::new("handle_liquid_staking_dapp_rewards")
-                    .add_attribute("contract_address", contract.to_string())
-                    .add_attribute("pending_deposit_record_amount", amount.to_string())
-                    .add_attribute("reward_address", rewards_addr.to_string())
-                    .add_attribute("deposit_record_id", record.id.to_string())
-                    .add_attribute("deposit_record_status", record.status.clone())
-                    .add_attribute("block_height", env.block.height.to_string())
-                    .add_attribute("timestamp", env.block.time.seconds().to_string());
+    if failed.is_empty() {
+        return Err(ContractError::NoFailedTransfers {});
+    }
 
-                res = res.add_event(event);
-            }
+    let mut res = Response::new();
+    for transfer in failed {
+        let zone = load_host_zone(deps.storage, &transfer.zone_id)?;
+        // Transfers to a disabled zone stay failed until it is enabled again.
+        if !zone.enabled {
+            let event = Event::new("liquid_stake_transfer_retry_skipped")
+                .add_attribute("zone_id", zone.zone_id.clone())
+                .add_attribute("channel_id", transfer.channel_id.clone())
+                .add_attribute("sequence", transfer.sequence.to_string())
+                .add_attribute("amount", transfer.amount.to_string())
+                .add_attribute("reason", "host zone disabled")
+                .add_attribute("block_height", env.block.height.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string());
+            res = res.add_event(event);
+            continue;
         }
+        IN_FLIGHT_TRANSFERS.remove(deps.storage, (&transfer.channel_id, transfer.sequence));
+
+        // Put the records back in flight on the zone's (possibly updated) channel.
+        update_transfer_deposit_records(deps.storage, &transfer.deposits, |_, _, record| {
+            record.status = DepositStatus::InFlight;
+            record.ibc_channel = Some(zone.channel_id.clone());
+            record.ibc_sequence = None;
+            Ok(None)
+        })?;
+
+        // Emit an event linking the retry to the failed packet.
+        let event = Event::new("liquid_stake_transfer_retried")
+            .add_attribute("zone_id", zone.zone_id.clone())
+            .add_attribute("previous_channel_id", transfer.channel_id.clone())
+            .add_attribute("previous_sequence", transfer.sequence.to_string())
+            .add_attribute("amount", transfer.amount.to_string())
+            .add_attribute("attempt", (transfer.attempts + 1).to_string())
+            .add_attribute("block_height", env.block.height.to_string())
+            .add_attribute("timestamp", env.block.time.seconds().to_string());
+        res = res.add_event(event);
+
+        let retry = InFlightTransfer {
+            channel_id: zone.channel_id.clone(),
+            sequence: 0,
+            status: TransferStatus::InFlight,
+            attempts: transfer.attempts + 1,
+            failure_reason: None,
+            timestamp: env.block.time.seconds(),
+            block_height: env.block.height,
+            ..transfer
+        };
+        res = res.add_submessage(send_liquid_stake_transfer(deps.storage, &env, &zone, retry)?);
     }
 
-    Ok(res)
+    Ok(res.add_attribute("method", "retry_failed_transfers"))
 }
 
-/// Handle the arch liquid stake interval triggered by cron jobs. It aggregates pending deposits into 
-/// completed stakes and updates the total liquid stake.
-fn handle_arch_liquid_stake_interval(
+/// Refresh the redemption rate of every enabled host zone that reads it from an oracle contract,
+/// then update the RRA strategies with the new rates. A failing oracle is reported in an event and
+/// does not stop the other zones or cron tasks.
+fn handle_redemption_rate_query(
     storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
     env: &Env,
 ) -> Result<Response, ContractError> {
     let mut res = Response::new();
 
-    // Update total liquid stake by processing pending deposit records.
-    let total_stake_res = get_total_liquid_stake(storage, env)?;
-    res = res.add_events(total_stake_res.events);
-    res = res.add_attributes(total_stake_res.attributes);
-
-    // Emit an event indicating the handling of arch liquid stake interval.
-    let event = Event::new("handle_arch_liquid_stake_interval")
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    let zones = HOST_ZONES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, zone)| zone))
+        .collect::<StdResult<Vec<HostZone>>>()?;
+    for zone in zones.into_iter().filter(|zone| zone.enabled) {
+        let RedemptionRateSource::Oracle { contract_address } = &zone.redemption_rate_source else {
+            continue;
+        };
+
+        let query = RedemptionRateOracleQueryMsg::RedemptionRate {
+            denom: zone.lst_denom.clone(),
+        };
+        match querier.query_wasm_smart::<RedemptionRateOracleResponse>(contract_address, &query) {
+            Ok(response) => {
+                let event =
+                    save_redemption_rate(storage, env, &zone.zone_id, response.redemption_rate)?;
+                res = res.add_event(event);
+            }
+            Err(err) => {
+                let event = Event::new("redemption_rate_query_failed")
+                    .add_attribute("zone_id", zone.zone_id.clone())
+                    .add_attribute("oracle", contract_address.to_string())
+                    .add_attribute("error", err.to_string())
+                    .add_attribute("block_height", env.block.height.to_string())
+                    .add_attribute("timestamp", env.block.time.seconds().to_string());
+                res = res.add_event(event);
+            }
+        }
+    }
 
-    res = res.add_event(event);
+    let strategies_res = update_strategies(storage, env)?;
+    res = res.add_events(strategies_res.events);
 
     Ok(res)
 }
 
-/// Handle redemption rate queries if implemented. Currently stubbed out, but in a production
-/// environment, this would fetch redemption rates and log redemptions based on thresholds on chain, redemption rate at which tokens were redeemed
-fn handle_redemption_rate_query(
-    _storage: &mut dyn Storage,
-    _config: &Config,
-    _env: Env,
-) -> Result<Response, ContractError> {
-    // Stub: For now, do nothing and return an empty response.
-    Ok(Response::new())
-}
-
 /// Compute cumulative reward amounts across all contracts from CONTRACT_REWARDS.
 fn get_cumulative_reward_amount(
     storage: &dyn Storage,
@@ -732,6 +2737,7 @@ fn create_contract_liquid_stake_deposit_record(
     contract_addr: &Addr,
     amount: Uint128,
     _reward_address: &Addr,
+    zone_id: Option<String>,
     env: &Env,
 ) -> DepositRecord {
     // Increment and retrieve the next deposit record ID.
@@ -743,47 +2749,51 @@ fn create_contract_liquid_stake_deposit_record(
         id: next_id,
         contract_address: contract_addr.clone(),
         amount,
-        status: "pending".to_string(),
+        status: DepositStatus::Pending,
         timestamp: env.block.time.seconds(),
         block_height: env.block.height,
+        ibc_channel: None,
+        ibc_sequence: None,
+        zone_id,
+        entry_redemption_rate: None,
+        shares: Uint128::zero(),
+        protocol_fee: Uint128::zero(),
     }
 }
 
-/// Distribute liquidity tokens among contracts based on their proportion of completed stakes.
-/// Contracts with higher completed stakes receive a larger share of liquidity tokens.
+/// Distribute liquidity tokens among contracts based on their pool shares. Shares are minted at
+/// the exchange rate when a deposit completes, so earlier deposits are worth more once the
+/// redemption rate has grown. The liquid staking tokens of stake completed on a host zone since
+/// the last distribution are then paid out of the pool to the contracts' liquidity provider
+/// addresses, along with the shares they are worth.
 fn distribute_liquidity(
     storage: &mut dyn Storage,
     env: &Env,
 ) -> Result<Response, ContractError> {
     let mut res = Response::new();
 
-    // Get the total liquid stake that is recognized.
-    let total_liquid_stake = TOTAL_LIQUID_STAKE.load(storage)?;
-    let liquidity_amount = total_liquid_stake.u128();
-
     let contracts = get_all_contracts(storage)?;
-    let mut cumulative_stakes = HashMap::new();
-    let mut total_stake = Uint128::zero();
+    let mut contract_shares = HashMap::new();
+    let total_shares = TOTAL_SHARES.may_load(storage)?.unwrap_or_default();
+    let rate = exchange_rate(storage)?;
 
-    // Compute the total completed stake across all contracts from COMPLETED_STAKES.
     for contract in &contracts {
-        let contract_stake = COMPLETED_STAKES
-            .may_load(storage, contract)?
-            .unwrap_or_default();
-        cumulative_stakes.insert(contract.clone(), contract_stake);
-        total_stake += contract_stake;
+        let shares = SHARES.may_load(storage, contract)?.unwrap_or_default();
+        contract_shares.insert(contract.clone(), shares);
     }
 
-    // If no stake is present, nothing to distribute.
-    if total_stake.is_zero() {
+    // If no shares are present, nothing to distribute.
+    if total_shares.is_zero() {
         return Ok(res);
     }
 
-    // Distribute liquidity proportionally to each contract based on stake ratio.
-    for (contract_addr, contract_stake) in cumulative_stakes {
-        let stake_proportion = Decimal::from_ratio(contract_stake.u128(), total_stake.u128());
-        let liquidity_tokens_amount =
-            Uint128::from((stake_proportion * Uint128::from(liquidity_amount)).u128());
+    update_zone_stake_ratios(storage)?;
+
+    // Each contract's stake ratio is its share of the pool and its liquidity is what its shares
+    // are worth at the exchange rate.
+    for (contract_addr, shares) in contract_shares {
+        let stake_proportion = Decimal::from_ratio(shares, total_shares);
+        let liquidity_tokens_amount = shares.mul_floor(rate);
 
         // Save this ratio in STAKE_RATIOS for future reference.
         STAKE_RATIOS.save(storage, &contract_addr, &stake_proportion)?;
@@ -799,9 +2809,96 @@ fn distribute_liquidity(
         res = res.add_event(distribute_event);
     }
 
+    res = res.add_events(credit_zone_liquidity(storage, env)?);
+
     Ok(res)
 }
 
+/// Credit each contract's liquidity provider address the liquid staking tokens of its completed
+/// stake on every host zone that has not been paid out yet, at the zone's last reported rate or
+/// one to one before any rate is reported. The tokens leave the pool and the contract's shares
+/// worth them are burned, so a payout is capped at what the contract's shares are still worth,
+/// e.g. after a redemption; positions held as receipt tokens are not paid out.
+fn credit_zone_liquidity(storage: &mut dyn Storage, env: &Env) -> Result<Vec<Event>, ContractError> {
+    let zone_stakes = ZONE_COMPLETED_STAKES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<((String, Addr), Uint128)>>>()?;
+
+    let mut events = vec![];
+    for ((zone_id, contract), stake) in zone_stakes {
+        let credited = CREDITED_ZONE_STAKES
+            .may_load(storage, (&zone_id, &contract))?
+            .unwrap_or_default();
+        let new_stake = stake.saturating_sub(credited);
+        if new_stake.is_zero() {
+            continue;
+        }
+        // Zones migrated without a liquid staking denom are paid out once it is set.
+        let Some(zone) = HOST_ZONES.may_load(storage, &zone_id)? else {
+            continue;
+        };
+        if zone.lst_denom.is_empty() {
+            continue;
+        }
+        let Some(metadata) = CONTRACT_METADATA.may_load(storage, &contract)? else {
+            continue;
+        };
+        CREDITED_ZONE_STAKES.save(storage, (&zone_id, &contract), &stake)?;
+
+        let rate = reported_rate(storage, &zone_id)?;
+        let pool_lst = POOL_LST.may_load(storage, &zone_id)?.unwrap_or_default();
+        let mut lst_amount = rate.map_or(new_stake, |rate| new_stake.div_floor(rate)).min(pool_lst);
+        let mut value = rate.map_or(lst_amount, |rate| lst_amount.mul_floor(rate));
+        let shares = SHARES.may_load(storage, &contract)?.unwrap_or_default();
+        let available = shares.mul_floor(exchange_rate(storage)?);
+        if value > available {
+            lst_amount = lst_amount.multiply_ratio(available, value);
+            value = rate.map_or(lst_amount, |rate| lst_amount.mul_floor(rate));
+        }
+        if lst_amount.is_zero() {
+            continue;
+        }
+
+        burn_shares(storage, &contract, value)?;
+        POOL_LST.save(storage, &zone_id, &(pool_lst - lst_amount))?;
+        redeem_cost_basis(storage, &zone_id, &contract, lst_amount)?;
+        events.push(credit_claimable(
+            storage,
+            env,
+            &contract,
+            &metadata.liquidity_provider_address,
+            &zone.lst_denom,
+            lst_amount,
+            "liquidity",
+        )?);
+    }
+
+    Ok(events)
+}
+
+/// Recompute each contract's share of the completed stake within every host zone.
+fn update_zone_stake_ratios(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let zone_stakes = ZONE_COMPLETED_STAKES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<((String, Addr), Uint128)>>>()?;
+
+    let mut zone_totals: HashMap<String, Uint128> = HashMap::new();
+    for ((zone_id, _), stake) in &zone_stakes {
+        *zone_totals.entry(zone_id.clone()).or_default() += *stake;
+    }
+
+    for ((zone_id, contract), stake) in zone_stakes {
+        let total = zone_totals[&zone_id];
+        if total.is_zero() {
+            continue;
+        }
+        let ratio = Decimal::from_ratio(stake, total);
+        ZONE_STAKE_RATIOS.save(storage, (&zone_id, &contract), &ratio)?;
+    }
+
+    Ok(())
+}
+
 /// Entry point to trigger liquidity distribution by the owner. Calls the `distribute_liquidity` function
 /// and emits a summary event.
 fn execute_distribute_liquidity(
@@ -857,12 +2954,30 @@ fn execute_set_redeem_tokens(
         });
     }
 
-    // Update the redemption record for the contract by adding the specified amount.
-    let current_amount = REDEMPTION_RECORDS
+    // With host zones the pending tokens are redeemed out of the pool, so together they may not be
+    // worth more than the contract's shares.
+    let mut ledger = REDEMPTION_LEDGER
         .may_load(deps.storage, &validated_contract_address)?
         .unwrap_or_default();
-    let new_amount = current_amount + amount;
-    REDEMPTION_RECORDS.save(deps.storage, &validated_contract_address, &new_amount)?;
+    if DEFAULT_HOST_ZONE.exists(deps.storage) {
+        let zone_id = redemption_zone(deps.storage)?.zone_id;
+        let lst_amount = ledger.pending - ledger.burned + amount;
+        let value = reported_rate(deps.storage, &zone_id)?
+            .map_or(lst_amount, |rate| lst_amount.mul_floor(rate));
+        let shares = SHARES.may_load(deps.storage, &validated_contract_address)?.unwrap_or_default();
+        let exchange_rate = exchange_rate(deps.storage)?;
+        if value > shares.mul_floor(exchange_rate) {
+            return Err(ContractError::InsufficientShares {
+                contract_address: contract_address.clone(),
+                available: shares,
+                requested: value.div_ceil(exchange_rate),
+            });
+        }
+    }
+
+    // Add the amount to the contract's pending redeem tokens.
+    ledger.pending += amount;
+    REDEMPTION_LEDGER.save(deps.storage, &validated_contract_address, &ledger)?;
 
     // Emit an event indicating redeem tokens have been set.
     let event = Event::new("set_redeem_tokens")
@@ -880,8 +2995,8 @@ fn execute_set_redeem_tokens(
         .add_attribute("amount", amount.to_string()))
 }
 
-/// Distribute redeem tokens across all contracts that have pending redemption records. Only the owner can do this.
-/// After computing redemption ratios, it resets the redemption records and emits distribution events.
+/// Distribute redeem tokens across all contracts that have pending redeem tokens. Only the owner can do this.
+/// After computing redemption ratios, it moves the pending amounts to distributed and emits distribution events.
 fn execute_distribute_redeem_tokens(
     deps: DepsMut,
     env: Env,
@@ -895,18 +3010,17 @@ fn execute_distribute_redeem_tokens(
 
     let mut res = Response::new();
 
-    // Gather all contracts and check their redemption records.
-    let contracts = get_all_contracts(deps.storage)?;
+    // Gather the pending redeem tokens of contracts and of receipt token holders.
+    let ledgers = REDEMPTION_LEDGER
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, RedemptionLedger)>>>()?;
     let mut total_redeem_tokens = Uint128::zero();
-    let mut redemption_records = HashMap::new();
+    let mut redemption_records = vec![];
 
-    for contract_addr in contracts.iter() {
-        let amount = REDEMPTION_RECORDS
-            .may_load(deps.storage, contract_addr)?
-            .unwrap_or_default();
-        if !amount.is_zero() {
-            redemption_records.insert(contract_addr.clone(), amount);
-            total_redeem_tokens += amount;
+    for (contract_addr, ledger) in ledgers {
+        if !ledger.pending.is_zero() {
+            total_redeem_tokens += ledger.pending;
+            redemption_records.push((contract_addr, ledger.pending));
         }
     }
 
@@ -930,8 +3044,21 @@ fn execute_distribute_redeem_tokens(
 
         res = res.add_event(event);
 
-        // Reset the redemption record for this contract now that we've distributed tokens.
-        REDEMPTION_RECORDS.save(deps.storage, contract_addr, &Uint128::zero())?;
+        // The pending tokens of this contract are now distributed.
+        REDEMPTION_LEDGER.update(deps.storage, contract_addr, |ledger| {
+            let mut ledger = ledger.unwrap_or_default();
+            ledger.pending -= *amount;
+            ledger.distributed += *amount;
+            Ok::<_, StdError>(ledger)
+        })?;
+    }
+
+    // With host zones, redeem the liquid staking tokens on a zone that supports redemption.
+    if DEFAULT_HOST_ZONE.exists(deps.storage) {
+        let zone = redemption_zone(deps.storage)?;
+        let batch_res = start_unbonding_batch(deps.storage, &env, &zone, redemption_records)?;
+        res = res.add_submessages(batch_res.messages);
+        res = res.add_events(batch_res.events);
     }
 
     // Summarize the redemption token distribution with a final event.
@@ -945,6 +3072,356 @@ fn execute_distribute_redeem_tokens(
     Ok(res)
 }
 
+/// Host zone redeem tokens are valued on and redeemed from: the default zone if it is an enabled
+/// Stride zone, otherwise the enabled Stride zone holding the most liquid staking tokens in the pool.
+fn redemption_zone(storage: &dyn Storage) -> Result<HostZone, ContractError> {
+    let default_zone_id = DEFAULT_HOST_ZONE
+        .may_load(storage)?
+        .ok_or(ContractError::NoDefaultHostZone {})?;
+    let default_zone = load_host_zone(storage, &default_zone_id)?;
+    if default_zone.protocol == LiquidStakeProtocol::Stride && default_zone.enabled {
+        return Ok(default_zone);
+    }
+
+    let mut best: Option<(Uint128, HostZone)> = None;
+    for item in HOST_ZONES.range(storage, None, None, Order::Ascending) {
+        let (zone_id, zone) = item?;
+        if zone.protocol != LiquidStakeProtocol::Stride || !zone.enabled {
+            continue;
+        }
+        let pool_lst = POOL_LST.may_load(storage, &zone_id)?.unwrap_or_default();
+        if best.as_ref().is_none_or(|(most, _)| pool_lst > *most) {
+            best = Some((pool_lst, zone));
+        }
+    }
+    match best {
+        Some((_, zone)) => Ok(zone),
+        None if default_zone.protocol == LiquidStakeProtocol::Stride => {
+            Err(ContractError::HostZoneDisabled { zone_id: default_zone_id })
+        }
+        None => Err(ContractError::InvalidHostZone {
+            reason: "no enabled host zone supports autopilot redemption".to_string(),
+        }),
+    }
+}
+
+/// Create an unbonding batch for the given (contract, liquid staking token amount) redemptions and
+/// send the tokens to the host zone with an autopilot RedeemStake memo. Stride unbonds them and,
+/// after the unbonding period, returns the ARCH to this contract. Each contract's ARCH share is
+/// fixed at the current redemption rate, and so is the performance fee on its gain over the
+/// redeemed part of its cost basis.
+fn start_unbonding_batch(
+    storage: &mut dyn Storage,
+    env: &Env,
+    zone: &HostZone,
+    redemptions: Vec<(Addr, Uint128)>,
+) -> Result<Response, ContractError> {
+    if zone.protocol != LiquidStakeProtocol::Stride {
+        return Err(ContractError::InvalidHostZone {
+            reason: format!("{} does not support autopilot redemption", zone.zone_id),
+        });
+    }
+    let redemption_rate = HOST_ZONE_REDEMPTION_RATES
+        .may_load(storage, &zone.zone_id)?
+        .ok_or_else(|| ContractError::RedemptionRateNotReported {
+            zone_id: zone.zone_id.clone(),
+        })?
+        .redemption_rate;
+
+    let performance_fee_bps = CONFIG.load(storage)?.performance_fee_bps;
+    let lst_amount: Uint128 = redemptions.iter().map(|(_, amount)| *amount).sum();
+    let expected_arch = lst_amount.mul_floor(redemption_rate);
+    let mut batch_redemptions = vec![];
+    for (contract_address, amount) in redemptions {
+        let arch_amount = expected_arch.multiply_ratio(amount, lst_amount);
+        let cost_basis = redeem_cost_basis(storage, &zone.zone_id, &contract_address, amount)?;
+        // Tokens without a recorded entry rate are taken to have cost what they redeem for.
+        let uncovered = (amount - cost_basis.lst_amount).mul_floor(redemption_rate);
+        let gain = arch_amount.saturating_sub(cost_basis.arch_cost + uncovered);
+        // Tokens of redeemed receipt tokens already left the pool; the rest leave it now along
+        // with the shares they were worth.
+        let mut ledger = REDEMPTION_LEDGER.may_load(storage, &contract_address)?.unwrap_or_default();
+        let burned = ledger.burned.min(amount);
+        ledger.burned -= burned;
+        REDEMPTION_LEDGER.save(storage, &contract_address, &ledger)?;
+        let shares = burn_shares(storage, &contract_address, (amount - burned).mul_floor(redemption_rate))?;
+        let pool_lst = POOL_LST.may_load(storage, &zone.zone_id)?.unwrap_or_default();
+        let pool_lst = pool_lst.checked_sub(amount - burned).map_err(|_| ContractError::InsufficientPoolTokens {
+            zone_id: zone.zone_id.clone(),
+            available: pool_lst,
+            requested: amount - burned,
+        })?;
+        POOL_LST.save(storage, &zone.zone_id, &pool_lst)?;
+        batch_redemptions.push(BatchRedemption {
+            contract_address,
+            lst_amount: amount,
+            arch_amount,
+            cost_basis,
+            performance_fee: gain.multiply_ratio(performance_fee_bps, MAX_FEE_BPS),
+            shares,
+            burned,
+        });
+    }
+    let redemptions = batch_redemptions;
+
+    let id = NEXT_UNBONDING_BATCH_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_UNBONDING_BATCH_ID.save(storage, &(id + 1))?;
+    let batch = UnbondingBatch {
+        id,
+        zone_id: zone.zone_id.clone(),
+        lst_denom: zone.lst_denom.clone(),
+        lst_amount,
+        redemption_rate,
+        expected_arch,
+        redemptions,
+        status: UnbondingStatus::Transferring,
+        channel_id: zone.channel_id.clone(),
+        sequence: None,
+        completion_time: None,
+        failure_reason: None,
+        timestamp: env.block.time.seconds(),
+        block_height: env.block.height,
+    };
+    UNBONDING_BATCHES.save(storage, id, &batch)?;
+    PENDING_BATCH_TRANSFERS.save(storage, id, &Empty {})?;
+    adjust_verified_balance(storage, &zone.lst_denom, |balance| balance.saturating_sub(lst_amount))?;
+
+    let msg = transfer_msg(
+        env,
+        &zone.channel_id,
+        &zone.receiver,
+        coin(lst_amount.u128(), &zone.lst_denom),
+        zone.timeout_seconds,
+        autopilot_memo(env, &zone.receiver, "RedeemStake")?,
+    );
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(msg, UNBONDING_TRANSFER_REPLY_ID))
+        .add_event(unbonding_batch_event("unbonding_batch_created", &batch, env)))
+}
+
+/// Take up to `lst_amount` tokens out of a contract's cost basis on a zone, at its average cost,
+/// and return the part taken.
+fn redeem_cost_basis(
+    storage: &mut dyn Storage,
+    zone_id: &str,
+    contract: &Addr,
+    lst_amount: Uint128,
+) -> Result<CostBasis, ContractError> {
+    let Some(mut basis) = COST_BASES.may_load(storage, (zone_id, contract))? else {
+        return Ok(CostBasis::default());
+    };
+    let lst_taken = lst_amount.min(basis.lst_amount);
+    let taken = CostBasis {
+        lst_amount: lst_taken,
+        arch_cost: basis.arch_cost.multiply_ratio(lst_taken, basis.lst_amount.max(Uint128::one())),
+    };
+    basis.lst_amount -= taken.lst_amount;
+    basis.arch_cost -= taken.arch_cost;
+    COST_BASES.save(storage, (zone_id, contract), &basis)?;
+    Ok(taken)
+}
+
+/// Pay out unbonding batches whose unbonding period has passed. Each contract's ARCH share is
+/// credited to its claimable balance, less its performance fee, once this contract holds enough
+/// ARCH for the whole batch on top of what it already owes, the treasury and the deposits still
+/// waiting to be sent to a host zone; until then the batch stays unbonding and is checked again on
+/// the next run.
+fn process_unbonding_batches(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    let now = env.block.time.seconds();
+
+    let matured = UNBONDING_BATCHES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, batch)| batch))
+        .filter(|batch| {
+            batch.as_ref().map_or(true, |batch| {
+                batch.status == UnbondingStatus::Unbonding
+                    && batch.completion_time.is_some_and(|time| time <= now)
+            })
+        })
+        .collect::<StdResult<Vec<UnbondingBatch>>>()?;
+
+    let mut balances: HashMap<String, Uint128> = HashMap::new();
+    for mut batch in matured {
+        let arch_denom = load_host_zone(storage, &batch.zone_id)?.arch_denom;
+        let balance = match balances.get(&arch_denom) {
+            Some(balance) => *balance,
+            None => {
+                let owed = CLAIMABLE_TOTALS.may_load(storage, &arch_denom)?.unwrap_or_default()
+                    + TREASURY.may_load(storage, &arch_denom)?.unwrap_or_default()
+                    + undelivered_deposits(storage, &arch_denom)?;
+                querier
+                    .query_balance(&env.contract.address, &arch_denom)?
+                    .amount
+                    .saturating_sub(owed)
+            }
+        };
+        if balance < batch.expected_arch {
+            res = res.add_event(
+                unbonding_batch_event("unbonding_batch_awaiting_funds", &batch, env)
+                    .add_attribute("balance", balance.to_string()),
+            );
+            balances.insert(arch_denom, balance);
+            continue;
+        }
+
+        let paid: Uint128 = batch.redemptions.iter().map(|r| r.arch_amount).sum();
+        balances.insert(arch_denom.clone(), balance - paid);
+        for redemption in batch.redemptions.iter().filter(|r| !r.arch_amount.is_zero()) {
+            // Receipt token holders that are not registered contracts are paid themselves.
+            let redemption_address = CONTRACT_METADATA
+                .may_load(storage, &redemption.contract_address)?
+                .map_or_else(|| redemption.contract_address.clone(), |metadata| metadata.redemption_address);
+            let payout = redemption.arch_amount - redemption.performance_fee;
+            res = res.add_event(credit_claimable(
+                storage,
+                env,
+                &redemption.contract_address,
+                &redemption_address,
+                &arch_denom,
+                payout,
+                "redemption",
+            )?);
+            if !redemption.performance_fee.is_zero() {
+                res = res.add_event(charge_performance_fee(storage, env, batch.id, &arch_denom, redemption)?);
+            }
+
+            let payout_event = Event::new("redemption_payout")
+                .add_attribute("batch_id", batch.id.to_string())
+                .add_attribute("contract_address", redemption.contract_address.to_string())
+                .add_attribute("redemption_address", redemption_address)
+                .add_attribute("amount", payout.to_string())
+                .add_attribute("denom", arch_denom.clone())
+                .add_attribute("block_height", env.block.height.to_string())
+                .add_attribute("timestamp", now.to_string());
+            res = res.add_event(payout_event);
+        }
+        for redemption in &batch.redemptions {
+            REDEMPTION_LEDGER.update(storage, &redemption.contract_address, |ledger| {
+                let mut ledger = ledger.unwrap_or_default();
+                ledger.distributed = ledger.distributed.saturating_sub(redemption.lst_amount);
+                ledger.claimed += redemption.lst_amount;
+                Ok::<_, StdError>(ledger)
+            })?;
+        }
+
+        batch.status = UnbondingStatus::Completed;
+        UNBONDING_BATCHES.save(storage, batch.id, &batch)?;
+        res = res.add_event(unbonding_batch_event("unbonding_batch_completed", &batch, env));
+    }
+
+    Ok(res)
+}
+
+/// Move a redemption's performance fee to the treasury.
+fn charge_performance_fee(
+    storage: &mut dyn Storage,
+    env: &Env,
+    batch_id: u64,
+    arch_denom: &str,
+    redemption: &BatchRedemption,
+) -> Result<Event, ContractError> {
+    let fee = redemption.performance_fee;
+    let treasury = TREASURY.may_load(storage, arch_denom)?.unwrap_or_default() + fee;
+    TREASURY.save(storage, arch_denom, &treasury)?;
+    let contract_fees = PERFORMANCE_FEES
+        .may_load(storage, &redemption.contract_address)?
+        .unwrap_or_default()
+        + fee;
+    PERFORMANCE_FEES.save(storage, &redemption.contract_address, &contract_fees)?;
+
+    Ok(Event::new("performance_fee")
+        .add_attribute("batch_id", batch_id.to_string())
+        .add_attribute("contract_address", redemption.contract_address.to_string())
+        .add_attribute("arch_amount", redemption.arch_amount.to_string())
+        .add_attribute("arch_cost", redemption.cost_basis.arch_cost.to_string())
+        .add_attribute("fee_amount", fee.to_string())
+        .add_attribute("denom", arch_denom)
+        .add_attribute("treasury", treasury.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string()))
+}
+
+/// Handle the ack, error ack or timeout of an unbonding batch transfer. On success the batch
+/// starts unbonding; on failure its amounts go back to the contracts' pending redeem tokens, cost
+/// bases and pool shares, so the next DistributeRedeemTokens sends them again.
+fn complete_batch_transfer(
+    storage: &mut dyn Storage,
+    env: &Env,
+    batch_id: u64,
+    failure: Option<&str>,
+) -> Result<Response, ContractError> {
+    let mut batch = UNBONDING_BATCHES
+        .may_load(storage, batch_id)?
+        .ok_or(ContractError::UnbondingBatchNotFound { batch_id })?;
+    if let Some(sequence) = batch.sequence {
+        BATCH_TRANSFERS.remove(storage, (&batch.channel_id, sequence));
+    }
+
+    let name = match failure {
+        None => {
+            let unbonding_period = load_host_zone(storage, &batch.zone_id)?.unbonding_period_seconds;
+            batch.status = UnbondingStatus::Unbonding;
+            batch.completion_time = Some(env.block.time.seconds() + unbonding_period);
+            "unbonding_batch_unbonding"
+        }
+        Some(reason) => {
+            for redemption in &batch.redemptions {
+                REDEMPTION_LEDGER.update(storage, &redemption.contract_address, |ledger| {
+                    let mut ledger = ledger.unwrap_or_default();
+                    ledger.distributed = ledger.distributed.saturating_sub(redemption.lst_amount);
+                    ledger.pending += redemption.lst_amount;
+                    ledger.burned += redemption.burned;
+                    Ok::<_, StdError>(ledger)
+                })?;
+                let key = (batch.zone_id.as_str(), &redemption.contract_address);
+                let mut basis = COST_BASES.may_load(storage, key)?.unwrap_or_default();
+                basis.lst_amount += redemption.cost_basis.lst_amount;
+                basis.arch_cost += redemption.cost_basis.arch_cost;
+                COST_BASES.save(storage, key, &basis)?;
+
+                if !redemption.shares.is_zero() {
+                    let total_shares = TOTAL_SHARES.may_load(storage)?.unwrap_or_default();
+                    TOTAL_SHARES.save(storage, &(total_shares + redemption.shares))?;
+                    let shares = SHARES.may_load(storage, &redemption.contract_address)?.unwrap_or_default();
+                    SHARES.save(storage, &redemption.contract_address, &(shares + redemption.shares))?;
+                }
+                let pool_lst = POOL_LST.may_load(storage, &batch.zone_id)?.unwrap_or_default();
+                POOL_LST.save(storage, &batch.zone_id, &(pool_lst + redemption.lst_amount - redemption.burned))?;
+            }
+            // The transfer module refunds the tokens along with the failure.
+            adjust_verified_balance(storage, &batch.lst_denom, |balance| balance + batch.lst_amount)?;
+            batch.status = UnbondingStatus::Failed;
+            batch.failure_reason = Some(reason.to_string());
+            "unbonding_batch_failed"
+        }
+    };
+    UNBONDING_BATCHES.save(storage, batch_id, &batch)?;
+
+    Ok(Response::new().add_event(unbonding_batch_event(name, &batch, env)))
+}
+
+fn unbonding_batch_event(name: &str, batch: &UnbondingBatch, env: &Env) -> Event {
+    Event::new(name)
+        .add_attribute("batch_id", batch.id.to_string())
+        .add_attribute("zone_id", batch.zone_id.clone())
+        .add_attribute("lst_amount", batch.lst_amount.to_string())
+        .add_attribute("lst_denom", batch.lst_denom.clone())
+        .add_attribute("expected_arch", batch.expected_arch.to_string())
+        .add_attribute("redemption_rate", batch.redemption_rate.to_string())
+        .add_attribute(
+            "completion_time",
+            batch.completion_time.map(|t| t.to_string()).unwrap_or_default(),
+        )
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string())
+}
+
 /// Update total liquid stake by converting pending deposit records into completed ones. This may be triggered
 /// by certain intervals to recognize stakes as completed and update COMPLETED_STAKES and TOTAL_LIQUID_STAKE.
 fn get_total_liquid_stake(
@@ -958,52 +3435,17 @@ fn get_total_liquid_stake(
         .may_load(storage)?
         .unwrap_or_default();
 
-    let contracts = get_all_contracts(storage)?;
-
-    // For each contract, check deposit records and finalize those that are still pending.
-    for contract in contracts {
-        let mut deposit_records = DEPOSIT_RECORDS
-            .may_load(storage, &contract)?
-            .unwrap_or_default();
-        let mut updated_records = vec![];
-
-        for mut record in deposit_records {
-            if record.status == "pending" {
-                // Convert from pending to completed and update the total liquid stake counter.
-                total_liquid_stake += record.amount;
-                record.status = "completed".to_string();
-
-                // Update COMPLETED_STAKES to reflect that these stakes are now completed.
-                let current_completed_stake = COMPLETED_STAKES
-                    .may_load(storage, &contract)?
-                    .unwrap_or_default();
-                let new_completed_stake = current_completed_stake + record.amount;
-                COMPLETED_STAKES.save(storage, &contract, &new_completed_stake)?;
-
-                // Reduce the CONTRACT_STAKES by the completed amount.
-                let current_contract_stake = CONTRACT_STAKES
-                    .may_load(storage, &contract)?
-                    .unwrap_or_default();
-                let new_contract_stake = current_contract_stake
-                    .checked_sub(record.amount)
-                    .map_err(|e| ContractError::Std(StdError::Overflow { source: e }))?;
-                CONTRACT_STAKES.save(storage, &contract, &new_contract_stake)?;
-
-                // Emit an event per deposit record updated.
-                let deposit_event = Event::new("deposit_record_updated")
-                    .add_attribute("contract_address", contract.to_string())
-                    .add_attribute("deposit_record_id", record.id.to_string())
-                    .add_attribute("completed_deposit_record_amount", record.amount.to_string())
-                    .add_attribute("deposit_record_status", record.status.clone())
-                    .add_attribute("timestamp", env.block.time.seconds().to_string())
-                    .add_attribute("block_height", env.block.height.to_string());
-
-                res = res.add_event(deposit_event);
-            }
-            updated_records.push(record);
+    // Finalize the deposit records that are still pending.
+    for mut record in records_with_status(storage, DepositStatus::Pending)? {
+        // Convert from pending to completed and update the total liquid stake counter.
+        let contract = record.contract_address.clone();
+        total_liquid_stake += record.amount;
+        let deposit_event = complete_deposit_record(storage, &contract, &mut record, env)?;
+        res = res.add_event(deposit_event);
+        if let Some(msg) = receipt_mint_msg(storage, env, &contract, record.shares)? {
+            res = res.add_message(msg);
         }
-
-        DEPOSIT_RECORDS.save(storage, &contract, &updated_records)?;
+        deposit_records().save(storage, (&contract, record.id), &record)?;
     }
 
     // Save the updated total liquid stake after processing all pending records.
@@ -1020,41 +3462,193 @@ fn get_total_liquid_stake(
     Ok(res)
 }
 
-/// Reset all completed deposit records back to pending 
-fn reset_all_completed_deposit_records(storage: &mut dyn Storage) -> Result<(), ContractError> {
-    let contracts = get_all_contracts(storage)?;
+/// Last redemption rate reported for a host zone, if any.
+fn reported_rate(storage: &dyn Storage, zone_id: &str) -> Result<Option<Decimal>, ContractError> {
+    Ok(HOST_ZONE_REDEMPTION_RATES
+        .may_load(storage, zone_id)?
+        .map(|record| record.redemption_rate)
+        .filter(|rate| !rate.is_zero()))
+}
 
-    for contract in contracts {
-        let deposit_records = DEPOSIT_RECORDS
-            .may_load(storage, &contract)?
+/// ARCH value of the liquid stake pool: the stake completed without a host zone plus each zone's
+/// liquid staking tokens at its last reported redemption rate, one to one before any report.
+fn pool_value(storage: &dyn Storage) -> Result<Uint128, ContractError> {
+    let mut value = POOL_ARCH.may_load(storage)?.unwrap_or_default();
+    let pool_lst = POOL_LST
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+    for (zone_id, lst_amount) in pool_lst {
+        value += reported_rate(storage, &zone_id)?.map_or(lst_amount, |rate| lst_amount.mul_floor(rate));
+    }
+    Ok(value)
+}
+
+/// ARCH value of one pool share, one while there are no shares.
+fn exchange_rate(storage: &dyn Storage) -> Result<Decimal, ContractError> {
+    let total_shares = TOTAL_SHARES.may_load(storage)?.unwrap_or_default();
+    let value = pool_value(storage)?;
+    if total_shares.is_zero() || value.is_zero() {
+        return Ok(Decimal::one());
+    }
+    Ok(Decimal::from_ratio(value, total_shares))
+}
+
+/// Mint a contract the shares `value` ARCH buys at the exchange rate. Call before adding the
+/// value to the pool. Fails if the pool holds value but no shares, since whoever minted first
+/// would own that value.
+fn mint_shares(storage: &mut dyn Storage, contract: &Addr, value: Uint128) -> Result<Uint128, ContractError> {
+    let total_shares = TOTAL_SHARES.may_load(storage)?.unwrap_or_default();
+    let pool = pool_value(storage)?;
+    if total_shares.is_zero() && !pool.is_zero() {
+        return Err(ContractError::UnsharedPoolValue { pool_value: pool });
+    }
+    let shares = if pool.is_zero() {
+        value
+    } else {
+        value.multiply_ratio(total_shares, pool)
+    };
+    TOTAL_SHARES.save(storage, &(total_shares + shares))?;
+    let contract_shares = SHARES.may_load(storage, contract)?.unwrap_or_default();
+    SHARES.save(storage, contract, &(contract_shares + shares))?;
+    Ok(shares)
+}
+
+/// Burn the contract shares worth `value` ARCH at the exchange rate. Fails if the contract holds
+/// fewer shares. Call before removing the value from the pool.
+fn burn_shares(storage: &mut dyn Storage, contract: &Addr, value: Uint128) -> Result<Uint128, ContractError> {
+    let total_shares = TOTAL_SHARES.may_load(storage)?.unwrap_or_default();
+    let pool = pool_value(storage)?;
+    let contract_shares = SHARES.may_load(storage, contract)?.unwrap_or_default();
+    let shares = if pool.is_zero() {
+        Uint128::zero()
+    } else {
+        value.multiply_ratio(total_shares, pool)
+    };
+    if shares > contract_shares {
+        return Err(ContractError::InsufficientShares {
+            contract_address: contract.to_string(),
+            available: contract_shares,
+            requested: shares,
+        });
+    }
+    TOTAL_SHARES.save(storage, &(total_shares - shares))?;
+    SHARES.save(storage, contract, &(contract_shares - shares))?;
+    Ok(shares)
+}
+
+/// Mark a deposit record as completed and move its amount from CONTRACT_STAKES into
+/// COMPLETED_STAKES. The caller is responsible for adding the amount to TOTAL_LIQUID_STAKE.
+fn complete_deposit_record(
+    storage: &mut dyn Storage,
+    contract: &Addr,
+    record: &mut DepositRecord,
+    env: &Env,
+) -> Result<Event, ContractError> {
+    record.status = DepositStatus::Completed;
+
+    // Update COMPLETED_STAKES to reflect that these stakes are now completed.
+    let current_completed_stake = COMPLETED_STAKES
+        .may_load(storage, contract)?
+        .unwrap_or_default();
+    let new_completed_stake = current_completed_stake + record.amount;
+    COMPLETED_STAKES.save(storage, contract, &new_completed_stake)?;
+
+    // Mint pool shares for the deposit at the exchange rate before it joins the pool.
+    record.shares = mint_shares(storage, contract, record.amount)?;
+
+    // Track the completed stake per host zone as well, with the liquid staking tokens it was
+    // minted into at the zone's redemption rate as the contract's cost basis.
+    if let Some(zone_id) = &record.zone_id {
+        let zone_stake = ZONE_COMPLETED_STAKES
+            .may_load(storage, (zone_id, contract))?
             .unwrap_or_default();
+        ZONE_COMPLETED_STAKES.save(storage, (zone_id, contract), &(zone_stake + record.amount))?;
+        // With a receipt token the position is held as receipt tokens, so its liquid staking
+        // tokens are not paid out as liquidity as well.
+        if RECEIPT_TOKEN_DENOM.exists(storage) {
+            let credited = CREDITED_ZONE_STAKES
+                .may_load(storage, (zone_id, contract))?
+                .unwrap_or_default();
+            CREDITED_ZONE_STAKES.save(storage, (zone_id, contract), &(credited + record.amount))?;
+        }
 
-        // Only keep records that are not completed.
-        let pending_records: Vec<DepositRecord> = deposit_records
-            .into_iter()
-            .filter(|record| record.status != "completed")
-            .collect();
+        record.entry_redemption_rate = reported_rate(storage, zone_id)?;
+        let lst_amount = record
+            .entry_redemption_rate
+            .map_or(record.amount, |rate| record.amount.div_floor(rate));
+        if record.entry_redemption_rate.is_some() {
+            let mut basis = COST_BASES.may_load(storage, (zone_id, contract))?.unwrap_or_default();
+            basis.lst_amount += lst_amount;
+            basis.arch_cost += record.amount;
+            COST_BASES.save(storage, (zone_id, contract), &basis)?;
+        }
+        let pool_lst = POOL_LST.may_load(storage, zone_id)?.unwrap_or_default();
+        POOL_LST.save(storage, zone_id, &(pool_lst + lst_amount))?;
+    } else {
+        let pool_arch = POOL_ARCH.may_load(storage)?.unwrap_or_default();
+        POOL_ARCH.save(storage, &(pool_arch + record.amount))?;
+    }
 
-        DEPOSIT_RECORDS.save(storage, &contract, &pending_records)?;
+    // Reduce the CONTRACT_STAKES by the completed amount.
+    let current_contract_stake = CONTRACT_STAKES
+        .may_load(storage, contract)?
+        .unwrap_or_default();
+    let new_contract_stake = current_contract_stake
+        .checked_sub(record.amount)
+        .map_err(|e| ContractError::Std(StdError::Overflow { source: e }))?;
+    CONTRACT_STAKES.save(storage, contract, &new_contract_stake)?;
+
+    // Emit an event per deposit record updated.
+    let deposit_event = Event::new("deposit_record_updated")
+        .add_attribute("contract_address", contract.to_string())
+        .add_attribute("deposit_record_id", record.id.to_string())
+        .add_attribute("completed_deposit_record_amount", record.amount.to_string())
+        .add_attribute("shares", record.shares.to_string())
+        .add_attribute("deposit_record_status", record.status.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string())
+        .add_attribute("block_height", env.block.height.to_string());
+
+    Ok(deposit_event)
+}
 
-       
-        let _event = Event::new("reset_completed_deposit_records")
-            .add_attribute("contract_address", contract.to_string())
-            .add_attribute("remaining_records", pending_records.len().to_string());
+/// Reset all completed deposit records back to pending 
+fn reset_all_completed_deposit_records(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    // Only keep records that are not completed.
+    for record in records_with_status(storage, DepositStatus::Completed)? {
+        deposit_records().remove(storage, (&record.contract_address, record.id))?;
     }
 
     Ok(())
 }
 
-/// A helper query to get the total currently recognized liquid stake without triggering any updates.
+/// A helper query to get the total currently recognized liquid stake without triggering any
+/// updates, along with the deposit records still pending and those completed.
 fn get_total_liquid_stake_query(
     deps: Deps,
-) -> Result<Uint128, ContractError> {
-    let total_completed_stake = TOTAL_LIQUID_STAKE
+    env: &Env,
+) -> Result<TotalLiquidStakeResponse, ContractError> {
+    let total = TOTAL_LIQUID_STAKE
         .may_load(deps.storage)?
         .unwrap_or_default();
 
-    Ok(total_completed_stake)
+    let mut pending = Uint128::zero();
+    let mut completed = Uint128::zero();
+    for record in all_deposit_records(deps.storage)? {
+        match record.status {
+            DepositStatus::Pending | DepositStatus::InFlight | DepositStatus::Failed => {
+                pending += record.amount
+            }
+            DepositStatus::Completed => completed += record.amount,
+            DepositStatus::Refunded => {}
+        }
+    }
+
+    Ok(TotalLiquidStakeResponse {
+        total,
+        pending,
+        completed,
+        as_of_height: env.block.height,
+    })
 }
 
 /// Allows the owner to subtract a specified amount from the TOTAL_LIQUID_STAKE
@@ -1092,8 +3686,9 @@ fn execute_subtract_from_total_liquid_stake(
         .add_attribute("method", "subtract_from_total_liquid_stake"))
 }
 
-/// Emit a custom event associated with liquid staking activities. Only the owner can do this.
-/// This is used for external integrations or logging important stake-related milestones.
+/// Record the outcome of a liquid stake in the receipt ledger and emit a custom event for it.
+/// Only the owner can do this. `total_liquid_stake` is the ARCH that was liquid staked and
+/// `stuarch_obtained` the stARCH received for it.
 fn emit_liquid_stake_event(
     deps: DepsMut,
     env: Env,
@@ -1108,18 +3703,204 @@ fn emit_liquid_stake_event(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Emit an event describing the liquid stake event and associated data.
-    let event = Event::new("liquid_stake_event")
-        .add_attribute("action", "liquid_stake_event")
-        .add_attribute("total_liquid_stake", total_liquid_stake.to_string())
-        .add_attribute("stuarch_obtained", stuarch_obtained.to_string())
-        .add_attribute("tx_hash", tx_hash)
-        .add_attribute("block_height", env.block.height.to_string())
-        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    // When verification is enabled, the report must match what the contract actually holds.
+    let verified_balance = match LIQUID_STAKE_VERIFICATION.may_load(deps.storage)? {
+        Some(mut verification) => {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &verification.starch_denom)?
+                .amount;
+            verify_liquid_stake_report(
+                deps.storage,
+                &verification,
+                balance,
+                total_liquid_stake,
+                stuarch_obtained,
+            )?;
+            verification.last_balance = balance;
+            LIQUID_STAKE_VERIFICATION.save(deps.storage, &verification)?;
+            Some(balance)
+        }
+        None => None,
+    };
+
+    let receipt = record_liquid_stake_receipt(
+        deps.storage,
+        &env,
+        total_liquid_stake,
+        stuarch_obtained,
+        tx_hash,
+    )?;
+    let reconciliation = get_liquid_stake_reconciliation(deps.storage)?;
+
+    // Emit an event describing the liquid stake event and associated data.
+    let event = Event::new("liquid_stake_event")
+        .add_attribute("action", "liquid_stake_event")
+        .add_attribute("total_liquid_stake", total_liquid_stake.to_string())
+        .add_attribute("stuarch_obtained", stuarch_obtained.to_string())
+        .add_attribute("tx_hash", receipt.tx_hash)
+        .add_attribute("receipt_id", receipt.id.to_string())
+        .add_attribute("epoch", receipt.epoch.to_string())
+        .add_attribute("redemption_rate", receipt.redemption_rate.to_string())
+        .add_attribute("unreceipted_arch", reconciliation.unreceipted_arch.to_string())
+        .add_attribute("over_receipted_arch", reconciliation.over_receipted_arch.to_string())
+        .add_attribute("verified", verified_balance.is_some().to_string())
+        .add_attribute(
+            "starch_balance",
+            verified_balance.map(|b| b.to_string()).unwrap_or_default(),
+        )
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "emit_liquid_stake_event"))
+}
+
+/// Check a liquid stake report against on-chain state, within the configured tolerance.
+/// The stARCH received since the last verified report must equal `stuarch_obtained`, and
+/// `total_liquid_stake` must not exceed the ARCH in TOTAL_LIQUID_STAKE that has no receipt yet.
+fn verify_liquid_stake_report(
+    storage: &dyn Storage,
+    verification: &LiquidStakeVerification,
+    starch_balance: Uint128,
+    total_liquid_stake: Uint128,
+    stuarch_obtained: Uint128,
+) -> Result<(), ContractError> {
+    let tolerance =
+        |reported: Uint128| reported.multiply_ratio(verification.tolerance_bps, 10_000u128);
+
+    let starch_received = starch_balance.saturating_sub(verification.last_balance);
+    if starch_received.abs_diff(stuarch_obtained) > tolerance(stuarch_obtained) {
+        return Err(ContractError::LiquidStakeVerificationFailed {
+            field: "stuarch_obtained".to_string(),
+            reported: stuarch_obtained,
+            observed: starch_received,
+        });
+    }
+
+    let unreceipted_arch = get_liquid_stake_reconciliation(storage)?.unreceipted_arch;
+    if total_liquid_stake > unreceipted_arch + tolerance(total_liquid_stake) {
+        return Err(ContractError::LiquidStakeVerificationFailed {
+            field: "total_liquid_stake".to_string(),
+            reported: total_liquid_stake,
+            observed: unreceipted_arch,
+        });
+    }
+
+    Ok(())
+}
+
+/// Keep the verified stARCH balance in step with stARCH the contract sends out, or gets back from
+/// a failed transfer, so that only liquid stakes count as stARCH received.
+fn adjust_verified_balance(
+    storage: &mut dyn Storage,
+    denom: &str,
+    adjust: impl FnOnce(Uint128) -> Uint128,
+) -> Result<(), ContractError> {
+    let Some(mut verification) = LIQUID_STAKE_VERIFICATION.may_load(storage)? else {
+        return Ok(());
+    };
+    if verification.starch_denom != denom {
+        return Ok(());
+    }
+    verification.last_balance = adjust(verification.last_balance);
+    LIQUID_STAKE_VERIFICATION.save(storage, &verification)?;
+    Ok(())
+}
+
+/// Append a receipt to LIQUID_STAKE_RECEIPTS under the current epoch and update the epoch and
+/// running totals.
+fn record_liquid_stake_receipt(
+    storage: &mut dyn Storage,
+    env: &Env,
+    arch_in: Uint128,
+    starch_out: Uint128,
+    tx_hash: String,
+) -> Result<LiquidStakeReceipt, ContractError> {
+    let id = NEXT_RECEIPT_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_RECEIPT_ID.save(storage, &(id + 1))?;
+    let epoch = LIQUID_STAKE_EPOCH.may_load(storage)?.unwrap_or_default();
+
+    let receipt = LiquidStakeReceipt {
+        id,
+        epoch,
+        arch_in,
+        starch_out,
+        redemption_rate: Decimal::checked_from_ratio(arch_in, starch_out).unwrap_or_default(),
+        tx_hash,
+        timestamp: env.block.time.seconds(),
+        block_height: env.block.height,
+    };
+    LIQUID_STAKE_RECEIPTS.save(storage, id, &receipt)?;
+
+    // Fold the receipt into its epoch and into the running totals.
+    let add = |totals: Option<EpochReceiptTotals>| {
+        let mut totals = totals.unwrap_or_default();
+        totals.arch_in += arch_in;
+        totals.starch_out += starch_out;
+        totals.receipt_count += 1;
+        totals
+    };
+    let epoch_totals = add(EPOCH_RECEIPT_TOTALS.may_load(storage, epoch)?);
+    EPOCH_RECEIPT_TOTALS.save(storage, epoch, &epoch_totals)?;
+    let running_totals = add(RECEIPT_TOTALS.may_load(storage)?);
+    RECEIPT_TOTALS.save(storage, &running_totals)?;
+
+    Ok(receipt)
+}
 
-    Ok(Response::new()
-        .add_event(event)
-        .add_attribute("method", "emit_liquid_stake_event"))
+/// Compare the receipt ledger with TOTAL_LIQUID_STAKE.
+fn get_liquid_stake_reconciliation(
+    storage: &dyn Storage,
+) -> Result<LiquidStakeReconciliationResponse, ContractError> {
+    let total_liquid_stake = TOTAL_LIQUID_STAKE.may_load(storage)?.unwrap_or_default();
+    let totals = RECEIPT_TOTALS.may_load(storage)?.unwrap_or_default();
+
+    let average_redemption_rate = if totals.starch_out.is_zero() {
+        None
+    } else {
+        Some(Decimal::from_ratio(totals.arch_in, totals.starch_out))
+    };
+
+    Ok(LiquidStakeReconciliationResponse {
+        current_epoch: LIQUID_STAKE_EPOCH.may_load(storage)?.unwrap_or_default(),
+        total_liquid_stake,
+        receipted_arch: totals.arch_in,
+        receipted_starch: totals.starch_out,
+        unreceipted_arch: total_liquid_stake.saturating_sub(totals.arch_in),
+        over_receipted_arch: totals.arch_in.saturating_sub(total_liquid_stake),
+        average_redemption_rate,
+    })
+}
+
+/// Receipt totals for each epoch that has receipts, with running totals up to that epoch.
+fn get_cumulative_starch(
+    storage: &dyn Storage,
+    up_to_epoch: Option<u64>,
+) -> Result<Vec<EpochStArchResponse>, ContractError> {
+    let mut cumulative_arch_in = Uint128::zero();
+    let mut cumulative_starch_out = Uint128::zero();
+    let mut epochs = vec![];
+
+    for item in EPOCH_RECEIPT_TOTALS.range(storage, None, None, Order::Ascending) {
+        let (epoch, totals) = item?;
+        if up_to_epoch.is_some_and(|max| epoch > max) {
+            break;
+        }
+        cumulative_arch_in += totals.arch_in;
+        cumulative_starch_out += totals.starch_out;
+        epochs.push(EpochStArchResponse {
+            epoch,
+            arch_in: totals.arch_in,
+            starch_out: totals.starch_out,
+            receipt_count: totals.receipt_count,
+            cumulative_arch_in,
+            cumulative_starch_out,
+        });
+    }
+
+    Ok(epochs)
 }
 
 /// Emit an event representing the distribution of liquidity to multiple addresses. Only the owner can do this.
@@ -1175,6 +3956,16 @@ fn reset_stake_ratios(storage: &mut dyn Storage) -> Result<(), ContractError> {
         COMPLETED_STAKES.save(storage, &key, &Uint128::zero())?;
     }
 
+    // Clear the per-zone ratios and completed stakes the same way.
+    let zone_keys = ZONE_COMPLETED_STAKES
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Addr)>>>()?;
+    for (zone_id, contract) in zone_keys {
+        ZONE_STAKE_RATIOS.remove(storage, (&zone_id, &contract));
+        ZONE_COMPLETED_STAKES.save(storage, (&zone_id, &contract), &Uint128::zero())?;
+        CREDITED_ZONE_STAKES.remove(storage, (&zone_id, &contract));
+    }
+
     Ok(())
 }
 
@@ -1192,7 +3983,6 @@ fn get_contract_stake(
 /// pending rewards, pending deposits, and completed deposits at a glance.
 fn get_reward_summaries(
     storage: &dyn Storage,
-    api: &dyn Api,
 ) -> Result<RewardSummariesResponse, ContractError> {
     let contracts = get_all_contracts(storage)?;
     let mut contract_summaries = Vec::new();
@@ -1200,13 +3990,13 @@ fn get_reward_summaries(
     let mut total_pending_rewards = Uint128::zero();
     let mut total_deposit_pending = Uint128::zero();
     let mut total_deposit_completed = Uint128::zero();
+    let mut total_performance_fees = Uint128::zero();
 
     for contract_addr in contracts {
         let contract_address = contract_addr.to_string();
 
-        // Retrieve contract metadata to confirm its existence and get associated addresses.
-        let metadata = CONTRACT_METADATA.load(storage, &contract_addr)?;
-        let _rewards_addr = api.addr_validate(&metadata.rewards_address)?;
+        // Retrieve contract metadata to confirm its existence.
+        CONTRACT_METADATA.load(storage, &contract_addr)?;
 
         // Get the pending rewards from CONTRACT_REWARDS for this contract.
         let pending_rewards = CONTRACT_REWARDS
@@ -1216,29 +4006,31 @@ fn get_reward_summaries(
         total_pending_rewards += pending_rewards;
 
         // Retrieve deposit records and categorize them into pending and completed totals.
-        let deposit_records = DEPOSIT_RECORDS
-            .may_load(storage, &contract_addr)?
-            .unwrap_or_default();
-
         let mut deposit_pending = Uint128::zero();
         let mut deposit_completed = Uint128::zero();
 
-        for record in deposit_records {
-            if record.status == "pending" {
-                deposit_pending += record.amount;
-            } else if record.status == "completed" {
-                deposit_completed += record.amount;
+        for record in contract_deposit_records(storage, &contract_addr)? {
+            match record.status {
+                DepositStatus::Pending => deposit_pending += record.amount,
+                DepositStatus::Completed => deposit_completed += record.amount,
+                _ => {}
             }
         }
 
         total_deposit_pending += deposit_pending;
         total_deposit_completed += deposit_completed;
 
+        let performance_fees = PERFORMANCE_FEES
+            .may_load(storage, &contract_addr)?
+            .unwrap_or_default();
+        total_performance_fees += performance_fees;
+
         contract_summaries.push(ContractRewardSummary {
             contract_address,
             pending_rewards,
             deposit_pending,
             deposit_completed,
+            performance_fees,
         });
     }
 
@@ -1247,37 +4039,35 @@ fn get_reward_summaries(
         total_pending_rewards,
         total_deposit_pending,
         total_deposit_completed,
+        total_performance_fees,
     })
 }
 
 /// The `query` entry point handles read-only queries. Each query variant retrieves specific pieces 
 /// of information about the contract state (e.g., config, total stake, records, metadata, etc.).
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&CONFIG.load(deps.storage)?)
             .map_err(ContractError::from),
 
         QueryMsg::GetTotalLiquidStakeQuery {} => {
-            let total_stake = get_total_liquid_stake_query(deps)?;
-            // Use to_json_binary for encoding responses if you prefer JSON format consistently.
+            let total_stake = get_total_liquid_stake_query(deps, &env)?;
             to_json_binary(&total_stake).map_err(ContractError::from)
         }
 
         QueryMsg::GetDepositRecords { contract } => {
             let addr = deps.api.addr_validate(&contract)?;
-            let records = DEPOSIT_RECORDS
-                .may_load(deps.storage, &addr)?
-                .unwrap_or_default();
+            let records = contract_deposit_records(deps.storage, &addr)?;
             to_json_binary(&records).map_err(ContractError::from)
         }
 
         QueryMsg::GetStakeRatio { contract } => {
             let addr = deps.api.addr_validate(&contract)?;
-            let stake_ratio = STAKE_RATIOS
+            let ratio = STAKE_RATIOS
                 .may_load(deps.storage, &addr)?
                 .unwrap_or_default();
-            to_json_binary(&stake_ratio.to_string()).map_err(ContractError::from)
+            to_json_binary(&StakeRatioResponse { contract, ratio }).map_err(ContractError::from)
         }
 
         QueryMsg::GetAllStakeRatios {} => {
@@ -1293,35 +4083,101 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
 
         QueryMsg::GetContractStake { contract } => {
             let addr = deps.api.addr_validate(&contract)?;
-            let stake = CONTRACT_STAKES
-                .may_load(deps.storage, &addr)?
-                .unwrap_or_default();
-            to_json_binary(&stake).map_err(ContractError::from)
+            let stake = get_contract_stake(deps.storage, &addr)?;
+            to_json_binary(&ContractStakeResponse { contract, stake }).map_err(ContractError::from)
         }
 
         QueryMsg::GetReward { rewards_address } => {
             let addr = deps.api.addr_validate(&rewards_address)?;
-            let reward = CONTRACT_REWARDS
+            let amount = CONTRACT_REWARDS
                 .may_load(deps.storage, &addr)?
                 .unwrap_or_default();
-            to_json_binary(&reward).map_err(ContractError::from)
+            to_json_binary(&RewardResponse { rewards_address, amount }).map_err(ContractError::from)
         }
 
         QueryMsg::GetRedeemTokens { contract } => {
             let addr = deps.api.addr_validate(&contract)?;
-            let tokens = REDEEM_TOKENS
+            let amount = REDEMPTION_LEDGER
+                .may_load(deps.storage, &addr)?
+                .unwrap_or_default()
+                .pending;
+            to_json_binary(&RedeemTokensResponse { contract, amount }).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetExchangeRate {} => {
+            let response = ExchangeRateResponse {
+                exchange_rate: exchange_rate(deps.storage)?,
+                total_shares: TOTAL_SHARES.may_load(deps.storage)?.unwrap_or_default(),
+                pool_value: pool_value(deps.storage)?,
+            };
+            to_json_binary(&response).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetShares { contract } => {
+            let addr = deps.api.addr_validate(&contract)?;
+            let shares = SHARES.may_load(deps.storage, &addr)?.unwrap_or_default();
+            let response = SharesResponse {
+                contract,
+                shares,
+                value: shares.mul_floor(exchange_rate(deps.storage)?),
+            };
+            to_json_binary(&response).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetReceiptToken {} => {
+            let denom = RECEIPT_TOKEN_DENOM.may_load(deps.storage)?;
+            to_json_binary(&ReceiptTokenResponse { denom }).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetTreasury {} => {
+            let coins = TREASURY
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+                .collect::<StdResult<Vec<Coin>>>()?;
+            to_json_binary(&TreasuryResponse { coins }).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetClaimable { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+            for ((_, _, denom), amount) in claimable_balances(deps.storage, &addr)? {
+                *totals.entry(denom).or_default() += amount;
+            }
+            let coins = totals
+                .into_iter()
+                .map(|(denom, amount)| coin(amount.u128(), denom))
+                .collect();
+            to_json_binary(&ClaimableResponse { address, coins }).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetRedemptionLedger { contract } => {
+            let addr = deps.api.addr_validate(&contract)?;
+            let ledger = REDEMPTION_LEDGER
                 .may_load(deps.storage, &addr)?
                 .unwrap_or_default();
-            to_json_binary(&tokens).map_err(ContractError::from)
+            to_json_binary(&redemption_ledger_response(addr, ledger)).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetRedemptionLedgers { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+            let start_after = start_after
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?;
+            let start = start_after.as_ref().map(cw_storage_plus::Bound::exclusive);
+            let ledgers = REDEMPTION_LEDGER
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(addr, ledger)| redemption_ledger_response(addr, ledger)))
+                .collect::<StdResult<Vec<RedemptionLedgerResponse>>>()?;
+            to_json_binary(&ledgers).map_err(ContractError::from)
         }
 
         QueryMsg::GetAllContracts {} => {
-            let contracts = get_all_contracts(deps.storage)?;
-            let contract_list: Vec<String> = contracts
+            let contracts = get_all_contracts(deps.storage)?
                 .into_iter()
                 .map(|c| c.to_string())
                 .collect();
-            to_json_binary(&contract_list).map_err(ContractError::from)
+            to_json_binary(&ContractsResponse { contracts }).map_err(ContractError::from)
         }
 
         QueryMsg::GetAllRedemptionRatios {} => {
@@ -1330,46 +4186,811 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         }
 
         QueryMsg::GetRewardSummaries {} => {
-            let reward_summaries = get_reward_summaries(deps.storage, deps.api)?;
+            let reward_summaries = get_reward_summaries(deps.storage)?;
             to_json_binary(&reward_summaries).map_err(ContractError::from)
         }
+
+        QueryMsg::GetHostZone { zone_id } => {
+            let zone = load_host_zone(deps.storage, &zone_id)?;
+            to_json_binary(&host_zone_response(deps.storage, zone)?).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetStrategy { strategy_id } => {
+            let rra = STRATEGIES
+                .may_load(deps.storage, strategy_id)?
+                .ok_or(ContractError::StrategyNotFound { strategy_id })?;
+            to_json_binary(&strategy_response(rra)).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetStrategies {} => {
+            let strategies = STRATEGIES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, rra)| strategy_response(rra)))
+                .collect::<StdResult<Vec<StrategyResponse>>>()?;
+            to_json_binary(&strategies).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetStrategySteps {
+            strategy_id,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+            let start = start_after.map(cw_storage_plus::Bound::exclusive);
+            let steps = STRATEGY_STEPS
+                .prefix(strategy_id)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, step)| step))
+                .collect::<StdResult<Vec<StrategyStep>>>()?;
+            to_json_binary(&steps).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetRedemptionRateHistory {
+            zone_id,
+            start_after,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+            let start = start_after.map(cw_storage_plus::Bound::exclusive);
+            let rates = REDEMPTION_RATE_HISTORY
+                .prefix(&zone_id)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| {
+                    item.map(|(timestamp, redemption_rate)| RedemptionRateHistoryEntry {
+                        timestamp,
+                        redemption_rate,
+                    })
+                })
+                .collect::<StdResult<Vec<RedemptionRateHistoryEntry>>>()?;
+            to_json_binary(&rates).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetAdvisorConfig {} => {
+            let advisor_config = ADVISOR_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+            to_json_binary(&advisor_config).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetAdvice { contract } => {
+            let contract_addr = deps.api.addr_validate(&contract)?;
+            let zone_id = main_host_zone(deps.storage, &contract_addr)?;
+            let rates = advisor_rates(deps.storage, &zone_id)?;
+            let phase = advisor::phase(&rates.config, rates.projected);
+            to_json_binary(&AdviceResponse {
+                contract,
+                zone_id,
+                current_rate: rates.current,
+                short_moving_average: advisor::moving_average(&rates.history, rates.config.short_window)
+                    .unwrap_or(rates.current),
+                long_moving_average: advisor::moving_average(&rates.history, rates.config.long_window)
+                    .unwrap_or(rates.current),
+                projected_rate: rates.projected,
+                action: advisor::recommended_action(&phase).to_string(),
+                phase,
+            })
+            .map_err(ContractError::from)
+        }
+
+        QueryMsg::SimulateStake { amount } => {
+            let simulation = simulate_on_default_zone(deps.storage, amount, advisor::stake_output)?;
+            to_json_binary(&simulation).map_err(ContractError::from)
+        }
+
+        QueryMsg::SimulateRedeem { amount } => {
+            let simulation = simulate_on_default_zone(deps.storage, amount, advisor::redeem_output)?;
+            to_json_binary(&simulation).map_err(ContractError::from)
+        }
+
+        QueryMsg::SimulateCronJob { at_time, at_height, distribute_liquidity } => {
+            let simulation = simulate_cron_job(
+                deps,
+                env,
+                at_time,
+                at_height,
+                distribute_liquidity.unwrap_or(false),
+            )?;
+            to_json_binary(&simulation).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetOperators {} => {
+            let operators = OPERATORS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|addr| addr.to_string()))
+                .collect::<StdResult<Vec<String>>>()?;
+            to_json_binary(&OperatorsResponse { operators }).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetSchedule {} => {
+            let config = CONFIG.load(deps.storage)?;
+            let schedules = [
+                CronTask::LiquidStakingDappRewards,
+                CronTask::ArchLiquidStakeInterval,
+                CronTask::RedemptionRateQuery,
+                CronTask::RewardsWithdrawal,
+            ]
+            .into_iter()
+            .map(|task| task_schedule_response(deps.storage, &env, &config, task))
+            .collect::<Result<Vec<TaskScheduleResponse>, ContractError>>()?;
+            to_json_binary(&schedules).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetUnbondingBatch { batch_id } => {
+            let batch = UNBONDING_BATCHES
+                .may_load(deps.storage, batch_id)?
+                .ok_or(ContractError::UnbondingBatchNotFound { batch_id })?;
+            to_json_binary(&batch).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetUnbondingBatches { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+            let start = start_after.map(cw_storage_plus::Bound::exclusive);
+            let batches = UNBONDING_BATCHES
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, batch)| batch))
+                .collect::<StdResult<Vec<UnbondingBatch>>>()?;
+            to_json_binary(&batches).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetZoneStakeRatios { zone_id } => {
+            let ratios = ZONE_STAKE_RATIOS
+                .prefix(&zone_id)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    item.map(|(contract, ratio)| StakeRatioResponse {
+                        contract: contract.to_string(),
+                        ratio,
+                    })
+                })
+                .collect::<StdResult<Vec<StakeRatioResponse>>>()?;
+            to_json_binary(&ratios).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetHostZones {} => {
+            let zones = HOST_ZONES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, zone)| zone))
+                .collect::<StdResult<Vec<HostZone>>>()?;
+            let responses = zones
+                .into_iter()
+                .map(|zone| host_zone_response(deps.storage, zone))
+                .collect::<Result<Vec<_>, ContractError>>()?;
+            to_json_binary(&responses).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetLiquidStakeRoute {} => {
+            let route = match DEFAULT_HOST_ZONE.may_load(deps.storage)? {
+                Some(zone_id) => {
+                    let zone = load_host_zone(deps.storage, &zone_id)?;
+                    Some(LiquidStakeRoute {
+                        channel_id: zone.channel_id,
+                        arch_denom: zone.arch_denom,
+                        stride_receiver: zone.receiver,
+                        timeout_seconds: zone.timeout_seconds,
+                        refund_target: zone.refund_target,
+                    })
+                }
+                None => None,
+            };
+            to_json_binary(&route).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetLiquidStakeVerification {} => {
+            let verification = LIQUID_STAKE_VERIFICATION.may_load(deps.storage)?;
+            to_json_binary(&verification).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetLiquidStakeReceipts { start_after, limit } => {
+            let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+            let start = start_after.map(cw_storage_plus::Bound::exclusive);
+            let receipts = LIQUID_STAKE_RECEIPTS
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, receipt)| receipt))
+                .collect::<StdResult<Vec<LiquidStakeReceipt>>>()?;
+            to_json_binary(&receipts).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetStArchByEpoch { epoch } => {
+            let epochs = get_cumulative_starch(deps.storage, Some(epoch))?;
+            let totals = EPOCH_RECEIPT_TOTALS
+                .may_load(deps.storage, epoch)?
+                .unwrap_or_default();
+            let (cumulative_arch_in, cumulative_starch_out) = epochs
+                .last()
+                .map(|e| (e.cumulative_arch_in, e.cumulative_starch_out))
+                .unwrap_or_default();
+            to_json_binary(&EpochStArchResponse {
+                epoch,
+                arch_in: totals.arch_in,
+                starch_out: totals.starch_out,
+                receipt_count: totals.receipt_count,
+                cumulative_arch_in,
+                cumulative_starch_out,
+            })
+            .map_err(ContractError::from)
+        }
+
+        QueryMsg::GetCumulativeStArch {} => {
+            let epochs = get_cumulative_starch(deps.storage, None)?;
+            to_json_binary(&epochs).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetLiquidStakeReconciliation {} => {
+            let reconciliation = get_liquid_stake_reconciliation(deps.storage)?;
+            to_json_binary(&reconciliation).map_err(ContractError::from)
+        }
+
+        QueryMsg::GetInFlightTransfers {} => {
+            let transfers = IN_FLIGHT_TRANSFERS
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, transfer)| transfer))
+                .collect::<StdResult<Vec<InFlightTransfer>>>()?;
+            to_json_binary(&transfers).map_err(ContractError::from)
+        }
     }
 }
 
-/// Retrieve all stake ratios stored in STAKE_RATIOS, returning them as a vector of (contract, ratio) strings.
-fn get_all_stake_ratios(storage: &dyn Storage) -> Result<Vec<(String, String)>, ContractError> {
+/// Retrieve all stake ratios stored in STAKE_RATIOS in contract order.
+fn get_all_stake_ratios(storage: &dyn Storage) -> Result<Vec<StakeRatioResponse>, ContractError> {
     let ratios = STAKE_RATIOS
         .range(storage, None, None, Order::Ascending)
         .map(|item| {
             let (addr, ratio) = item?;
-            Ok((addr.to_string(), ratio.to_string()))
+            Ok(StakeRatioResponse {
+                contract: addr.to_string(),
+                ratio,
+            })
         })
-        .collect::<StdResult<Vec<(String, String)>>>()?;
+        .collect::<StdResult<Vec<StakeRatioResponse>>>()?;
     Ok(ratios)
 }
 
-/// Retrieve all redemption token ratios from REDEEM_TOKEN_RATIOS in ascending order, returning them as (contract, ratio) pairs.
+fn redemption_ledger_response(contract: Addr, ledger: RedemptionLedger) -> RedemptionLedgerResponse {
+    RedemptionLedgerResponse {
+        contract: contract.to_string(),
+        pending: ledger.pending,
+        distributed: ledger.distributed,
+        claimed: ledger.claimed,
+    }
+}
+
+/// Retrieve all redemption token ratios from REDEEM_TOKEN_RATIOS in contract order.
 fn get_all_redeem_token_ratios(
     storage: &dyn Storage,
-) -> Result<Vec<(String, String)>, ContractError> {
+) -> Result<Vec<RedemptionRatioResponse>, ContractError> {
     let ratios = REDEEM_TOKEN_RATIOS
         .range(storage, None, None, Order::Ascending)
         .map(|item| {
             let (addr, ratio) = item?;
-            Ok((addr.to_string(), ratio.to_string()))
+            Ok(RedemptionRatioResponse {
+                contract: addr.to_string(),
+                ratio,
+            })
         })
-        .collect::<StdResult<Vec<(String, String)>>>()?;
+        .collect::<StdResult<Vec<RedemptionRatioResponse>>>()?;
     Ok(ratios)
 }
 
-/// The `migrate` entry point is invoked to migrate the contract to a new code version. 
-/// In this contract, migrate does nothing and just returns a default (no-op) response.
+type Migration = fn(DepsMut) -> Result<(), ContractError>;
+
+// State migrations from the 0.1.0 layout, each tagged with the version whose state layout it
+// produces. Contracts deployed before cw2 versioning count as version 0.0.0.
+const MIGRATIONS: &[(&str, Migration)] = &[
+    ("0.2.0", |deps| migrate_deposit_records(deps.storage)),
+    ("0.2.0", migrate_metadata_addresses),
+    ("0.2.0", |deps| migrate_redemption_ledger(deps.storage)),
+    ("0.2.0", |deps| migrate_shares(deps.storage)),
+];
+
+/// The `migrate` entry point is invoked to migrate the contract to a new code version.
+/// It refuses to migrate from another contract or from a newer version, then runs the state
+/// migrations of every version after the stored one and records the new version.
 #[entry_point]
 pub fn migrate(
-    _deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     _msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
-    Ok(Response::default())
+    let stored = cw2::CONTRACT.may_load(deps.storage)?;
+    if let Some(info) = &stored {
+        if info.contract != CONTRACT_NAME {
+            return Err(ContractError::InvalidMigration {
+                reason: format!("cannot migrate from contract {}", info.contract),
+            });
+        }
+    }
+    let from_version = stored.map_or_else(|| "0.0.0".to_string(), |info| info.version);
+    let from = parse_version(&from_version)?;
+    let to = parse_version(CONTRACT_VERSION)?;
+    if from > to {
+        return Err(ContractError::InvalidMigration {
+            reason: format!("cannot downgrade from {} to {}", from, to),
+        });
+    }
+
+    let mut applied = 0;
+    for (version, migration) in MIGRATIONS {
+        if parse_version(version)? > from {
+            migration(deps.branch())?;
+            applied += 1;
+        }
+    }
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let event = Event::new("migrate")
+        .add_attribute("from_version", from.to_string())
+        .add_attribute("to_version", to.to_string())
+        .add_attribute("migrations_applied", applied.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("method", "migrate"))
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|err| ContractError::InvalidMigration {
+        reason: format!("invalid version {}: {}", version, err),
+    })
+}
+
+/// Move the per-contract Vec of deposit records into the indexed deposit record map, turning
+/// each free-form status into a DepositStatus.
+fn migrate_deposit_records(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let legacy = LEGACY_DEPOSIT_RECORDS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (contract, records) in legacy {
+        for record in records {
+            let status = legacy_deposit_status(&record.status)?;
+            let migrated = DepositRecord {
+                id: record.id,
+                contract_address: record.contract_address,
+                amount: record.amount,
+                status,
+                timestamp: record.timestamp,
+                block_height: record.block_height,
+                ibc_channel: None,
+                ibc_sequence: None,
+                zone_id: None,
+                entry_redemption_rate: None,
+                shares: Uint128::zero(),
+                protocol_fee: Uint128::zero(),
+            };
+            deposit_records().save(storage, (&contract, migrated.id), &migrated)?;
+        }
+        LEGACY_DEPOSIT_RECORDS.remove(storage, &contract);
+    }
+    Ok(())
+}
+
+/// Statuses were written as "pending" or "completed"; tolerate other casing and whitespace.
+fn legacy_deposit_status(status: &str) -> Result<DepositStatus, ContractError> {
+    match status.trim().to_ascii_lowercase().as_str() {
+        "pending" => Ok(DepositStatus::Pending),
+        "completed" => Ok(DepositStatus::Completed),
+        _ => Err(ContractError::InvalidMigration {
+            reason: format!("unknown deposit record status {}", status),
+        }),
+    }
+}
+
+/// Contract metadata addresses were stored as unchecked strings; validate and normalise them.
+fn migrate_metadata_addresses(deps: DepsMut) -> Result<(), ContractError> {
+    let metadata = CONTRACT_METADATA
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (contract, mut meta) in metadata {
+        meta.rewards_address = deps.api.addr_validate(meta.rewards_address.as_str())?;
+        meta.liquidity_provider_address = deps.api.addr_validate(meta.liquidity_provider_address.as_str())?;
+        meta.redemption_address = deps.api.addr_validate(meta.redemption_address.as_str())?;
+        CONTRACT_METADATA.save(deps.storage, &contract, &meta)?;
+    }
+    Ok(())
+}
+
+/// Seed the pool with the liquid stake recorded before share accounting, all of it ARCH since
+/// 0.1.0 had no host zones. Shares for the pool value are then split between contracts in
+/// proportion to their completed stake, so the pool starts at an exchange rate of one.
+/// After `ResetStakeRatios` no contract has completed stake; the owner then holds the shares until
+/// the pool is attributed, so that the next deposit cannot take over the existing pool.
+fn migrate_shares(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if TOTAL_SHARES.may_load(storage)?.is_some() {
+        return Ok(());
+    }
+
+    let pool = TOTAL_LIQUID_STAKE.may_load(storage)?.unwrap_or_default();
+    POOL_ARCH.save(storage, &pool)?;
+
+    let completed = COMPLETED_STAKES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let completed_total = completed.iter().fold(Uint128::zero(), |total, (_, amount)| total + amount);
+    let mut total_shares = Uint128::zero();
+    if !completed_total.is_zero() {
+        for (addr, amount) in completed {
+            let shares = pool.multiply_ratio(amount, completed_total);
+            SHARES.save(storage, &addr, &shares)?;
+            total_shares += shares;
+        }
+    } else if !pool.is_zero() {
+        let owner = CONFIG.load(storage)?.owner;
+        SHARES.save(storage, &owner, &pool)?;
+        total_shares = pool;
+    }
+    TOTAL_SHARES.save(storage, &total_shares)?;
+
+    Ok(())
+}
+
+/// Merge the redeem token maps into REDEMPTION_LEDGER. Amounts in REDEMPTION_RECORDS (and any left
+/// in the never written REDEEM_TOKENS) are pending.
+fn migrate_redemption_ledger(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let mut ledgers: BTreeMap<Addr, RedemptionLedger> = BTreeMap::new();
+
+    for map in [REDEMPTION_RECORDS, REDEEM_TOKENS] {
+        let amounts = map
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (addr, amount) in amounts {
+            ledgers.entry(addr.clone()).or_default().pending += amount;
+            map.remove(storage, &addr);
+        }
+    }
+
+    for (addr, merged) in ledgers {
+        REDEMPTION_LEDGER.update(storage, &addr, |ledger| {
+            let mut ledger = ledger.unwrap_or_default();
+            ledger.pending += merged.pending;
+            ledger.distributed += merged.distributed;
+            ledger.claimed += merged.claimed;
+            Ok::<_, StdError>(ledger)
+        })?;
+    }
+
+    REDEMPTION_TOKEN_RATIOS.clear(storage);
+
+    Ok(())
+}
+
+/// The `reply` entry point receives the result of a stargate MsgTransfer dispatched by this
+/// contract. Replies arrive in dispatch order, so the oldest queued transfer is the one that was
+/// just sent; it is stored under its (channel, sequence) so the ack/timeout reported later can be
+/// matched back to its deposit records.
+#[entry_point]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        IBC_TRANSFER_REPLY_ID => {
+            let data = msg
+                .result
+                .into_result()
+                .map_err(StdError::generic_err)?
+                .data
+                .ok_or(ContractError::MissingTransferSequence {})?;
+            let sequence = parse_transfer_sequence(&data)?;
+
+            let (pending_id, mut transfer) = PENDING_TRANSFERS
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose()?
+                .ok_or(ContractError::MissingTransferSequence {})?;
+            PENDING_TRANSFERS.remove(deps.storage, pending_id);
+
+            transfer.sequence = sequence;
+            IN_FLIGHT_TRANSFERS.save(deps.storage, (&transfer.channel_id, sequence), &transfer)?;
+
+            update_transfer_deposit_records(deps.storage, &transfer.deposits, |_, _, record| {
+                record.ibc_sequence = Some(sequence);
+                Ok(None)
+            })?;
+
+            // Emit an event linking the packet to its deposit records.
+            let event = Event::new("liquid_stake_transfer_sent")
+                .add_attribute("channel_id", transfer.channel_id)
+                .add_attribute("sequence", sequence.to_string())
+                .add_attribute("amount", transfer.amount.to_string())
+                .add_attribute("deposit_record_count", transfer.deposits.len().to_string())
+                .add_attribute("block_height", env.block.height.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+            Ok(Response::new()
+                .add_event(event)
+                .add_attribute("method", "reply_ibc_transfer"))
+        }
+        UNBONDING_TRANSFER_REPLY_ID => {
+            let data = msg
+                .result
+                .into_result()
+                .map_err(StdError::generic_err)?
+                .data
+                .ok_or(ContractError::MissingTransferSequence {})?;
+            let sequence = parse_transfer_sequence(&data)?;
+
+            let batch_id = PENDING_BATCH_TRANSFERS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose()?
+                .ok_or(ContractError::MissingTransferSequence {})?;
+            PENDING_BATCH_TRANSFERS.remove(deps.storage, batch_id);
+
+            let mut batch = UNBONDING_BATCHES.load(deps.storage, batch_id)?;
+            batch.sequence = Some(sequence);
+            UNBONDING_BATCHES.save(deps.storage, batch_id, &batch)?;
+            BATCH_TRANSFERS.save(deps.storage, (&batch.channel_id, sequence), &batch_id)?;
+
+            Ok(Response::new()
+                .add_event(
+                    unbonding_batch_event("unbonding_batch_sent", &batch, &env)
+                        .add_attribute("channel_id", batch.channel_id.clone())
+                        .add_attribute("sequence", sequence.to_string()),
+                )
+                .add_attribute("method", "reply_unbonding_transfer"))
+        }
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+/// The `sudo` entry point is called by the chain. The ibc-hooks middleware uses it to report
+/// whether a transfer sent by this contract was acknowledged, failed or timed out.
+#[entry_point]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            ack,
+            success,
+        }) => ibc_packet_ack(deps, env, channel, sequence, ack, success),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout { channel, sequence }) => {
+            ibc_packet_timeout(deps, env, channel, sequence)
+        }
+    }
+}
+
+/// Handle the acknowledgement of a liquid stake transfer. On success the deposit records carried
+/// by the packet become completed and are added to TOTAL_LIQUID_STAKE; an error ack is handled
+/// like a timeout.
+fn ibc_packet_ack(
+    deps: DepsMut,
+    env: Env,
+    channel: String,
+    sequence: u64,
+    ack: String,
+    success: bool,
+) -> Result<Response, ContractError> {
+    if let Some(batch_id) = BATCH_TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        let failure = (!success).then_some(ack.as_str());
+        let batch_res = complete_batch_transfer(deps.storage, &env, batch_id, failure)?;
+        return Ok(batch_res.add_attribute("method", "ibc_packet_ack"));
+    }
+
+    if !success {
+        let fail_res = fail_transfer(deps.storage, &env, &channel, sequence, &ack)?;
+        return Ok(fail_res.add_attribute("method", "ibc_packet_ack"));
+    }
+
+    let mut res = Response::new();
+    let transfer = match IN_FLIGHT_TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        Some(transfer) if transfer.status == TransferStatus::InFlight => transfer,
+        _ => return Ok(unknown_transfer_response(&env, &channel, sequence, "ibc_packet_ack")),
+    };
+    IN_FLIGHT_TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    let mut total_liquid_stake = TOTAL_LIQUID_STAKE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut completed_amount = Uint128::zero();
+    let mut receipt_mints = vec![];
+
+    let events = update_transfer_deposit_records(
+        deps.storage,
+        &transfer.deposits,
+        |storage, contract, record| {
+            completed_amount += record.amount;
+            // Records sent before they carried a zone are attributed to the transfer's zone.
+            if record.zone_id.is_none() {
+                record.zone_id = Some(transfer.zone_id.clone());
+            }
+            let event = complete_deposit_record(storage, contract, record, &env)?;
+            receipt_mints.extend(receipt_mint_msg(storage, &env, contract, record.shares)?);
+            Ok(Some(event))
+        },
+    )?;
+    res = res.add_events(events).add_messages(receipt_mints);
+
+    total_liquid_stake += completed_amount;
+    TOTAL_LIQUID_STAKE.save(deps.storage, &total_liquid_stake)?;
+
+    let zone_liquid_stake = HOST_ZONE_LIQUID_STAKE
+        .may_load(deps.storage, &transfer.zone_id)?
+        .unwrap_or_default()
+        + completed_amount;
+    HOST_ZONE_LIQUID_STAKE.save(deps.storage, &transfer.zone_id, &zone_liquid_stake)?;
+
+    // Emit an event summarizing the acknowledged transfer.
+    let event = Event::new("liquid_stake_transfer_acknowledged")
+        .add_attribute("zone_id", transfer.zone_id)
+        .add_attribute("zone_liquid_stake", zone_liquid_stake.to_string())
+        .add_attribute("channel_id", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("completed_amount", completed_amount.to_string())
+        .add_attribute("total_liquid_stake", total_liquid_stake.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(res.add_event(event).add_attribute("method", "ibc_packet_ack"))
+}
+
+/// Handle the timeout of a liquid stake transfer.
+fn ibc_packet_timeout(
+    deps: DepsMut,
+    env: Env,
+    channel: String,
+    sequence: u64,
+) -> Result<Response, ContractError> {
+    if let Some(batch_id) = BATCH_TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        let batch_res = complete_batch_transfer(deps.storage, &env, batch_id, Some("timeout"))?;
+        return Ok(batch_res.add_attribute("method", "ibc_packet_timeout"));
+    }
+
+    let fail_res = fail_transfer(deps.storage, &env, &channel, sequence, "timeout")?;
+    Ok(fail_res.add_attribute("method", "ibc_packet_timeout"))
+}
+
+/// Record a failed liquid stake transfer and refund its amounts according to its host zone's
+/// refund target. With `ContractStakes` the records become "failed", their amounts stay in
+/// CONTRACT_STAKES and the transfer is kept for RetryFailedTransfers. With `PendingRewards` the
+/// records become "refunded", their amounts move from CONTRACT_STAKES back into CONTRACT_REWARDS,
+/// as do the protocol fees charged on them from the treasury, and the transfer is dropped.
+fn fail_transfer(
+    storage: &mut dyn Storage,
+    env: &Env,
+    channel: &str,
+    sequence: u64,
+    reason: &str,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new();
+    let mut transfer = match IN_FLIGHT_TRANSFERS.may_load(storage, (channel, sequence))? {
+        Some(transfer) if transfer.status == TransferStatus::InFlight => transfer,
+        _ => return Ok(unknown_transfer_response(env, channel, sequence, "fail_transfer")),
+    };
+
+    let refund_target = HOST_ZONES
+        .may_load(storage, &transfer.zone_id)?
+        .map(|zone| zone.refund_target)
+        .unwrap_or_default();
+    let record_status = match refund_target {
+        RefundTarget::ContractStakes => DepositStatus::Failed,
+        RefundTarget::PendingRewards => DepositStatus::Refunded,
+    };
+
+    let events = update_transfer_deposit_records(
+        storage,
+        &transfer.deposits,
+        |storage, contract, record| {
+            record.status = record_status;
+
+            let mut fee_refund = Uint128::zero();
+            if refund_target == RefundTarget::PendingRewards {
+                let current_stake = get_contract_stake(storage, contract)?;
+                let new_stake = current_stake
+                    .checked_sub(record.amount)
+                    .map_err(|e| ContractError::Std(StdError::Overflow { source: e }))?;
+                CONTRACT_STAKES.save(storage, contract, &new_stake)?;
+
+                // The protocol fee comes back from the treasury too, so the rewards are charged
+                // once when they are deposited again. Fees already withdrawn stay withdrawn.
+                if let Some(fee_denom) = zone_arch_denom(storage, record.zone_id.as_deref())? {
+                    let treasury = TREASURY.may_load(storage, &fee_denom)?.unwrap_or_default();
+                    fee_refund = record.protocol_fee.min(treasury);
+                    TREASURY.save(storage, &fee_denom, &(treasury - fee_refund))?;
+                }
+                record.protocol_fee -= fee_refund;
+
+                let current_reward = CONTRACT_REWARDS
+                    .may_load(storage, contract)?
+                    .unwrap_or_default();
+                CONTRACT_REWARDS.save(storage, contract, &(current_reward + record.amount + fee_refund))?;
+            }
+
+            // Emit an event per deposit record refunded.
+            let refund_event = Event::new("transfer_refund")
+                .add_attribute("contract_address", contract.to_string())
+                .add_attribute("deposit_record_id", record.id.to_string())
+                .add_attribute("refund_amount", record.amount.to_string())
+                .add_attribute("fee_refund", fee_refund.to_string())
+                .add_attribute("refund_target", refund_target_name(&refund_target))
+                .add_attribute("deposit_record_status", record.status.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string())
+                .add_attribute("block_height", env.block.height.to_string());
+            Ok(Some(refund_event))
+        },
+    )?;
+    res = res.add_events(events);
+
+    match refund_target {
+        RefundTarget::ContractStakes => {
+            transfer.status = TransferStatus::Failed;
+            transfer.failure_reason = Some(reason.to_string());
+            IN_FLIGHT_TRANSFERS.save(storage, (channel, sequence), &transfer)?;
+        }
+        RefundTarget::PendingRewards => {
+            IN_FLIGHT_TRANSFERS.remove(storage, (channel, sequence));
+        }
+    }
+
+    // Emit an event summarizing the failed transfer.
+    let event = Event::new("liquid_stake_transfer_failed")
+        .add_attribute("zone_id", transfer.zone_id.clone())
+        .add_attribute("channel_id", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("failed_amount", transfer.amount.to_string())
+        .add_attribute("attempts", transfer.attempts.to_string())
+        .add_attribute("refund_target", refund_target_name(&refund_target))
+        .add_attribute("reason", reason)
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+
+    Ok(res.add_event(event))
 }
 
+/// Response for an ack or timeout that does not match an in-flight transfer of this contract.
+fn unknown_transfer_response(env: &Env, channel: &str, sequence: u64, action: &str) -> Response {
+    let event = Event::new("unknown_transfer_lifecycle")
+        .add_attribute("action", action)
+        .add_attribute("channel_id", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("block_height", env.block.height.to_string())
+        .add_attribute("timestamp", env.block.time.seconds().to_string());
+    Response::new().add_event(event)
+}
+
+/// Apply `update` to every deposit record carried by a transfer and save it. Events returned by
+/// `update` are collected in order.
+fn update_transfer_deposit_records<F>(
+    storage: &mut dyn Storage,
+    deposits: &[TransferDeposit],
+    mut update: F,
+) -> Result<Vec<Event>, ContractError>
+where
+    F: FnMut(&mut dyn Storage, &Addr, &mut DepositRecord) -> Result<Option<Event>, ContractError>,
+{
+    let mut events = vec![];
+    for deposit in deposits {
+        let key = (&deposit.contract_address, deposit.deposit_record_id);
+        let Some(mut record) = deposit_records().may_load(storage, key)? else {
+            continue;
+        };
+        if let Some(event) = update(storage, &deposit.contract_address, &mut record)? {
+            events.push(event);
+        }
+        deposit_records().save(storage, key, &record)?;
+    }
+
+    Ok(events)
+}
+
+fn host_zone_response(
+    storage: &dyn Storage,
+    zone: HostZone,
+) -> Result<HostZoneResponse, ContractError> {
+    let is_default = DEFAULT_HOST_ZONE.may_load(storage)?.as_deref() == Some(zone.zone_id.as_str());
+    Ok(HostZoneResponse {
+        is_default,
+        liquid_stake: HOST_ZONE_LIQUID_STAKE
+            .may_load(storage, &zone.zone_id)?
+            .unwrap_or_default(),
+        redemption_rate: HOST_ZONE_REDEMPTION_RATES.may_load(storage, &zone.zone_id)?,
+        zone,
+    })
+}
+
+fn refund_target_name(target: &RefundTarget) -> &'static str {
+    match target {
+        RefundTarget::ContractStakes => "contract_stakes",
+        RefundTarget::PendingRewards => "pending_rewards",
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -18,6 +18,15 @@ pub enum ContractError {
     #[error("No redemption records found")]
     NoRedemptionRecords {},
 
+    #[error("Nothing to claim in {denom}")]
+    NothingToClaim { denom: String },
+
+    #[error("Invalid fee: {reason}")]
+    InvalidFee { reason: String },
+
+    #[error("Invalid receipt token: {reason}")]
+    InvalidReceiptToken { reason: String },
+
     #[error("Unsupported query")]
     UnsupportedQuery {},
 
@@ -29,5 +38,96 @@ pub enum ContractError {
 
     #[error("Serialization error")]
     SerializationError {},
+
+    #[error("Task {task} runs every {schedule} and is not due until {next_due}")]
+    TaskNotDue {
+        task: String,
+        schedule: String,
+        next_due: u64,
+    },
+
+    #[error("Invalid host zone: {reason}")]
+    InvalidHostZone { reason: String },
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("IBC transfer reply did not contain a packet sequence")]
+    MissingTransferSequence {},
+
+    #[error("Host zone not found: {zone_id}")]
+    HostZoneNotFound { zone_id: String },
+
+    #[error("Host zone already exists: {zone_id}")]
+    HostZoneAlreadyExists { zone_id: String },
+
+    #[error("Unbonding batch not found: {batch_id}")]
+    UnbondingBatchNotFound { batch_id: u64 },
+
+    #[error("Host zone is disabled: {zone_id}")]
+    HostZoneDisabled { zone_id: String },
+
+    #[error("Invalid strategy: {reason}")]
+    InvalidStrategy { reason: String },
+
+    #[error("No redemption rate recorded for host zone {zone_id}")]
+    NoRedemptionRate { zone_id: String },
+
+    #[error("No default host zone configured")]
+    NoDefaultHostZone {},
+
+    #[error("Invalid advisor config: {reason}")]
+    InvalidAdvisorConfig { reason: String },
+
+    #[error("Invalid strategy path: {reason}")]
+    InvalidStrategyPath { reason: String },
+
+    #[error("Strategy not found: {strategy_id}")]
+    StrategyNotFound { strategy_id: u64 },
+
+    #[error("Strategy {strategy_id} is not active")]
+    StrategyNotActive { strategy_id: u64 },
+
+    #[error("Contract metadata not found: {contract}")]
+    ContractMetadataNotFound { contract: String },
+
+    #[error("Redemption rate of host zone {zone_id} is not reported by the owner")]
+    RedemptionRateNotReported { zone_id: String },
+
+    #[error("No failed transfers to retry")]
+    NoFailedTransfers {},
+
+    #[error("Invalid migration: {reason}")]
+    InvalidMigration { reason: String },
+
+    #[error("Invalid liquid stake verification: {reason}")]
+    InvalidLiquidStakeVerification { reason: String },
+
+    #[error("Invalid timeline: {reason}")]
+    InvalidTimeline { reason: String },
+
+    #[error("Liquid stake report does not match on-chain state: reported {reported} {field}, observed {observed}")]
+    LiquidStakeVerificationFailed {
+        field: String,
+        reported: Uint128,
+        observed: Uint128,
+    },
+
+    #[error("Pool holds {pool_value} ARCH without any shares to price new shares against")]
+    UnsharedPoolValue { pool_value: Uint128 },
+
+    #[error("Contract {contract_address} holds {available} shares, {requested} requested")]
+    InsufficientShares {
+        contract_address: String,
+        available: Uint128,
+        requested: Uint128,
+    },
+
+    #[error("Host zone {zone_id} pool holds {available} liquid staking tokens, {requested} requested")]
+    InsufficientPoolTokens {
+        zone_id: String,
+        available: Uint128,
+        requested: Uint128,
+    },
 }
 
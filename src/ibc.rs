@@ -0,0 +1,152 @@
+// src/ibc.rs
+//
+// IBC transfer support for the liquid staking pipeline. The contract moves pending deposits to
+// a host zone with an ICS-20 transfer whose memo carries up to two instructions:
+// - `autopilot` (Stride only): Stride liquid stakes the received ARCH and sends the stARCH back
+//   to this contract.
+// - `ibc_callback`: the ibc-hooks middleware on Archway reports the ack/timeout of the packet back
+//   to this contract through `sudo`.
+//
+// Both instructions only exist in the memo: without `autopilot` Stride credits the ARCH to the
+// receiver and nothing is liquid staked, and without `ibc_callback` the contract never hears about
+// the ack or timeout, so the deposit records would stay in flight. `IbcMsg::Transfer` in
+// cosmwasm-std 1.x has no memo field, which is why the transfer is sent as a stargate
+// `MsgTransfer` encoded with prost instead. The chain must therefore accept stargate messages
+// from contracts.
+
+use cosmwasm_std::{to_json_string, Binary, Coin, CosmosMsg, Env, StdError, StdResult};
+use prost::Message;
+use serde::Serialize;
+
+pub const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+pub const TRANSFER_PORT: &str = "transfer";
+
+/// Protobuf encoding of `ibc.applications.transfer.v1.MsgTransfer`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: String,
+    #[prost(string, tag = "2")]
+    pub source_channel: String,
+    #[prost(message, optional, tag = "3")]
+    pub token: Option<ProtoCoin>,
+    #[prost(string, tag = "4")]
+    pub sender: String,
+    #[prost(string, tag = "5")]
+    pub receiver: String,
+    #[prost(message, optional, tag = "6")]
+    pub timeout_height: Option<ProtoHeight>,
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: String,
+}
+
+/// Protobuf encoding of `ibc.applications.transfer.v1.MsgTransferResponse`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgTransferResponse {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+}
+
+/// Protobuf encoding of `cosmos.base.v1beta1.Coin`.
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// Protobuf encoding of `ibc.core.client.v1.Height`.
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoHeight {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+#[derive(Serialize)]
+struct TransferMemo {
+    autopilot: AutopilotMemo,
+    ibc_callback: String,
+}
+
+#[derive(Serialize)]
+struct CallbackMemo {
+    ibc_callback: String,
+}
+
+#[derive(Serialize)]
+struct AutopilotMemo {
+    receiver: String,
+    stakeibc: StakeibcMemo,
+}
+
+#[derive(Serialize)]
+struct StakeibcMemo {
+    action: String,
+    ibc_receiver: String,
+}
+
+/// Build the memo that asks Stride autopilot to run `action` (e.g. "LiquidStake") and return the
+/// result to this contract, and asks ibc-hooks to report the packet lifecycle to this contract.
+pub fn autopilot_memo(env: &Env, stride_receiver: &str, action: &str) -> StdResult<String> {
+    let contract = env.contract.address.to_string();
+    to_json_string(&TransferMemo {
+        autopilot: AutopilotMemo {
+            receiver: stride_receiver.to_string(),
+            stakeibc: StakeibcMemo {
+                action: action.to_string(),
+                ibc_receiver: contract.clone(),
+            },
+        },
+        ibc_callback: contract,
+    })
+}
+
+/// Build a memo that only asks ibc-hooks to report the packet lifecycle to this contract. Used for
+/// host zones that liquid stake whatever reaches their deposit receiver.
+pub fn callback_memo(env: &Env) -> StdResult<String> {
+    to_json_string(&CallbackMemo {
+        ibc_callback: env.contract.address.to_string(),
+    })
+}
+
+/// Build a stargate `MsgTransfer` sent by this contract over `channel_id`, timing out
+/// `timeout_seconds` after the current block time.
+pub fn transfer_msg(
+    env: &Env,
+    channel_id: &str,
+    receiver: &str,
+    amount: Coin,
+    timeout_seconds: u64,
+    memo: String,
+) -> CosmosMsg {
+    let msg = MsgTransfer {
+        source_port: TRANSFER_PORT.to_string(),
+        source_channel: channel_id.to_string(),
+        token: Some(ProtoCoin {
+            denom: amount.denom,
+            amount: amount.amount.to_string(),
+        }),
+        sender: env.contract.address.to_string(),
+        receiver: receiver.to_string(),
+        timeout_height: None,
+        timeout_timestamp: env.block.time.plus_seconds(timeout_seconds).nanos(),
+        memo,
+    };
+
+    CosmosMsg::Stargate {
+        type_url: MSG_TRANSFER_TYPE_URL.to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    }
+}
+
+/// Decode the packet sequence from the data returned by a `MsgTransfer`.
+pub fn parse_transfer_sequence(data: &Binary) -> StdResult<u64> {
+    MsgTransferResponse::decode(data.as_slice())
+        .map(|res| res.sequence)
+        .map_err(|e| StdError::parse_err("MsgTransferResponse", e.to_string()))
+}
@@ -1,9 +1,21 @@
 // src/lib.rs
 
+pub mod advisor;
+pub mod archway;
 pub mod contract;
 pub mod error;
+pub mod ibc;
 pub mod msg;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod operator;
+pub mod overlay;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod simulator;
 pub mod state;
+pub mod strategy;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
+pub mod tokenfactory;
 
 
 pub use crate::error::ContractError;
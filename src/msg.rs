@@ -1,9 +1,15 @@
 // src/msg.rs
 
-use cosmwasm_std::{Uint128};
+use cosmwasm_std::{Coin, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use crate::state::{
+    AdvisorConfig, AdvisorPhase, Config, ContractMetadata, CronTask, DepositRecord, HostZone, InFlightTransfer,
+    LiquidStakeProtocol, LiquidStakeReceipt, LiquidStakeRoute, LiquidStakeVerification, RedemptionRateRecord, RedemptionRateSource,
+    RefundTarget, RraStrategy, Schedule, StrategyStep, UnbondingBatch, ZoneAllocation,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -14,7 +20,7 @@ pub struct InstantiateMsg {
     pub redemption_interval_threshold: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
 pub struct MigrateMsg {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -23,6 +29,8 @@ pub struct ContractRewardSummary {
     pub pending_rewards: Uint128,
     pub deposit_pending: Uint128,
     pub deposit_completed: Uint128,
+    /// Performance fees charged on the contract's redemption gains
+    pub performance_fees: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -62,6 +70,9 @@ pub enum ExecuteMsg {
     EmitDistributeLiquidityEvent {
         distributions: Vec<Distribution>,
     },
+    /// Computes redemption ratios from the redemption records. With a default host zone it also
+    /// sends the recorded liquid staking tokens to be redeemed as one unbonding batch, whose ARCH
+    /// is credited to the contracts' claimable balances once it is back.
     DistributeRedeemTokens {},
     ResetRedemptionRatios {},
     SetRedeemTokens {
@@ -72,23 +83,296 @@ pub enum ExecuteMsg {
         amount: Uint128,
     },
     CronJob {},
+    /// Runs a single periodic task now. Unless `force` is set the task's interval must have
+    /// elapsed. Only the owner or an operator can do this.
+    RunTask { task: CronTask, force: bool },
+    /// Sets how often a periodic task runs, in seconds or in blocks
+    UpdateSchedule { task: CronTask, schedule: Schedule },
+    /// Allows an address to run tasks with `RunTask`
+    AddOperator { address: String },
+    /// Revokes an operator
+    RemoveOperator { address: String },
+    /// Registers a liquid staking host zone. The first zone added becomes the default zone, to
+    /// which the arch liquid stake interval transfers pending deposits instead of completing
+    /// them immediately.
+    AddHostZone {
+        zone_id: String,
+        protocol: LiquidStakeProtocol,
+        channel_id: String,
+        arch_denom: String,
+        lst_denom: String,
+        receiver: String,
+        timeout_seconds: u64,
+        redemption_rate_source: RedemptionRateSource,
+        unbonding_period_seconds: u64,
+        refund_target: Option<RefundTarget>,
+    },
+    /// Updates the given fields of a registered host zone
+    UpdateHostZone {
+        zone_id: String,
+        channel_id: Option<String>,
+        lst_denom: Option<String>,
+        receiver: Option<String>,
+        timeout_seconds: Option<u64>,
+        redemption_rate_source: Option<RedemptionRateSource>,
+        unbonding_period_seconds: Option<u64>,
+        refund_target: Option<RefundTarget>,
+    },
+    /// Stops sending new transfers to a host zone
+    DisableHostZone { zone_id: String },
+    /// Resumes sending transfers to a disabled host zone
+    EnableHostZone { zone_id: String },
+    /// Selects the host zone pending deposits are sent to
+    SetDefaultHostZone { zone_id: String },
+    /// Sets how a contract's rewards are split across host zones. An empty allocation sends
+    /// them to the default host zone.
+    SetContractStrategy {
+        contract_address: String,
+        allocations: Vec<ZoneAllocation>,
+    },
+    /// Sets the Redemption Rate Advisor thresholds and windows
+    SetAdvisorConfig {
+        caution_rate: Decimal,
+        redemption_rate: Decimal,
+        short_window: u32,
+        long_window: u32,
+        projection_seconds: u64,
+    },
+    /// Records the redemption rate of a host zone whose rate source is `Reported`
+    ReportRedemptionRate {
+        zone_id: String,
+        redemption_rate: Decimal,
+    },
+    /// Sets the IBC route of the default host zone. Without a default zone a "stride" zone is
+    /// registered, which needs `lst_denom` and `unbonding_period_seconds`. Kept for clients
+    /// written before host zones; prefer AddHostZone and UpdateHostZone.
+    SetLiquidStakeRoute {
+        channel_id: String,
+        arch_denom: String,
+        stride_receiver: String,
+        timeout_seconds: u64,
+        refund_target: Option<RefundTarget>,
+        #[serde(default)]
+        lst_denom: Option<String>,
+        #[serde(default)]
+        unbonding_period_seconds: Option<u64>,
+    },
+    /// Re-sends every failed liquid stake transfer to its host zone. Transfers to disabled host
+    /// zones are skipped and stay failed
+    RetryFailedTransfers {},
+    /// Creates an RRA strategy over an ordered path of host zone layers. It is unwound once the
+    /// compounded redemption rate reaches `target_rate`.
+    CreateStrategy {
+        name: String,
+        layers: Vec<StrategyLayerMsg>,
+        target_rate: Decimal,
+    },
+    /// Records `amount` base tokens liquid staked through every layer of a strategy
+    StakeStrategy { strategy_id: u64, amount: Uint128 },
+    /// Unwinds a strategy layer by layer regardless of its target rate
+    UnwindStrategy { strategy_id: u64 },
+    /// Verifies EmitLiquidStakeEvent reports against the contract's stARCH balance.
+    /// The current balance is taken as the starting point.
+    EnableLiquidStakeVerification {
+        starch_denom: String,
+        tolerance_bps: u64,
+    },
+    /// Stops verifying EmitLiquidStakeEvent reports
+    DisableLiquidStakeVerification {},
+    /// Sets the protocol fee, in basis points, kept from every contract's converted rewards
+    SetProtocolFee { fee_bps: u64 },
+    /// Sets the performance fee, in basis points, kept from the ARCH gained between a deposit's
+    /// entry and exit redemption rate
+    SetPerformanceFee { fee_bps: u64 },
+    /// Overrides the protocol fee for one contract. None falls back to the protocol fee.
+    SetContractFee {
+        contract_address: String,
+        fee_bps: Option<u64>,
+    },
+    /// Sends `amount` of the treasury's `denom` to `recipient`
+    WithdrawTreasury {
+        amount: Uint128,
+        denom: String,
+        recipient: String,
+    },
+    /// Creates the TokenFactory receipt token minted to dApps, one per pool share, as their
+    /// deposits complete. Tokenised shares are no longer counted as the dApp's own
+    CreateReceiptToken { subdenom: String },
+    /// Burns the receipt tokens sent along and the pool shares backing them, and adds the liquid
+    /// staking tokens those shares are worth to the sender's pending redeem tokens. Any holder may
+    /// redeem; the payout is credited to the sender's claimable balance, or to its redemption
+    /// address if it is a registered contract
+    RedeemReceiptTokens {},
+    /// Sends the sender everything credited to it in `denom`, such as the redemption payouts of
+    /// the contracts it is the redemption address of. A registered contract can also claim the
+    /// payouts credited to its redemption and liquidity provider addresses
+    Claim { denom: String },
 }
 
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(Config)]
     GetConfig {},
+    #[returns(TotalLiquidStakeResponse)]
     GetTotalLiquidStakeQuery {},
+    #[returns(Vec<DepositRecord>)]
     GetDepositRecords { contract: String },
+    #[returns(StakeRatioResponse)]
     GetStakeRatio { contract: String },
+    #[returns(Vec<StakeRatioResponse>)]
     GetAllStakeRatios {},
+    #[returns(Vec<RedemptionRatioResponse>)]
     GetAllRedemptionRatios {},
+    #[returns(RewardResponse)]
     GetReward { rewards_address: String },
+    /// Returns the contract's pending redeem tokens
+    #[returns(RedeemTokensResponse)]
     GetRedeemTokens { contract: String },
+    /// Returns the pool exchange rate, the ARCH value of one share
+    #[returns(ExchangeRateResponse)]
+    GetExchangeRate {},
+    /// Returns a contract's pool shares and their value
+    #[returns(SharesResponse)]
+    GetShares { contract: String },
+    /// Returns the receipt token denom, if created
+    #[returns(ReceiptTokenResponse)]
+    GetReceiptToken {},
+    /// Returns the protocol fees held in the treasury
+    #[returns(TreasuryResponse)]
+    GetTreasury {},
+    /// Returns the coins `address` can claim with `Claim`
+    #[returns(ClaimableResponse)]
+    GetClaimable { address: String },
+    /// Returns a contract's pending, distributed and claimed redeem tokens
+    #[returns(RedemptionLedgerResponse)]
+    GetRedemptionLedger { contract: String },
+    /// Returns the redemption ledger of each contract in address order
+    #[returns(Vec<RedemptionLedgerResponse>)]
+    GetRedemptionLedgers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ContractStakeResponse)]
     GetContractStake { contract: String },
+    #[returns(ContractMetadata)]
     GetContractMetadata { contract: String },
+    #[returns(ContractsResponse)]
     GetAllContracts {},
     /// Returns the reward summary for each contract and cumulative totals
+    #[returns(RewardSummariesResponse)]
     GetRewardSummaries {},
+    /// Returns a host zone with its liquid stake and last redemption rate
+    #[returns(HostZoneResponse)]
+    GetHostZone { zone_id: String },
+    /// Returns every registered host zone
+    #[returns(Vec<HostZoneResponse>)]
+    GetHostZones {},
+    /// Returns an RRA strategy with its compounded rate and base value
+    #[returns(StrategyResponse)]
+    GetStrategy { strategy_id: u64 },
+    /// Returns every RRA strategy
+    #[returns(Vec<StrategyResponse>)]
+    GetStrategies {},
+    /// Returns the steps taken on a strategy in order
+    #[returns(Vec<StrategyStep>)]
+    GetStrategySteps {
+        strategy_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the redemption rates recorded for a host zone, oldest first
+    #[returns(Vec<RedemptionRateHistoryEntry>)]
+    GetRedemptionRateHistory {
+        zone_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the Redemption Rate Advisor configuration
+    #[returns(AdvisorConfig)]
+    GetAdvisorConfig {},
+    /// Returns the advisor phase, projected rate and recommended action for a contract's main
+    /// host zone
+    #[returns(AdviceResponse)]
+    GetAdvice { contract: String },
+    /// Returns the liquid staking tokens `amount` ARCH would mint on the default host zone
+    #[returns(SimulationResponse)]
+    SimulateStake { amount: Uint128 },
+    /// Returns the ARCH `amount` liquid staking tokens would redeem for on the default host zone
+    #[returns(SimulationResponse)]
+    SimulateRedeem { amount: Uint128 },
+    /// Dry-runs `CronJob {}` at `at_time` (seconds) and `at_height`, which default to the current
+    /// block, without changing any state. `CronJob {}` does not distribute liquidity, so the stake
+    /// ratios only include a `DistributeLiquidity {}` run when `distribute_liquidity` is true
+    #[returns(CronSimulationResponse)]
+    SimulateCronJob {
+        at_time: Option<u64>,
+        at_height: Option<u64>,
+        distribute_liquidity: Option<bool>,
+    },
+    /// Returns the addresses allowed to run tasks besides the owner
+    #[returns(OperatorsResponse)]
+    GetOperators {},
+    /// Returns when each periodic task last ran and when it is next due
+    #[returns(Vec<TaskScheduleResponse>)]
+    GetSchedule {},
+    /// Returns the stake ratio of each contract within a host zone
+    #[returns(Vec<StakeRatioResponse>)]
+    GetZoneStakeRatios { zone_id: String },
+    /// Returns the IBC route of the default host zone, if any
+    #[returns(Option<LiquidStakeRoute>)]
+    GetLiquidStakeRoute {},
+    /// Returns the liquid stake verification settings, if enabled
+    #[returns(Option<LiquidStakeVerification>)]
+    GetLiquidStakeVerification {},
+    /// Returns the liquid stake transfers that are in flight or failed
+    #[returns(Vec<InFlightTransfer>)]
+    GetInFlightTransfers {},
+    /// Returns an unbonding batch
+    #[returns(UnbondingBatch)]
+    GetUnbondingBatch { batch_id: u64 },
+    /// Returns unbonding batches in id order
+    #[returns(Vec<UnbondingBatch>)]
+    GetUnbondingBatches {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the liquid stake receipts in id order
+    #[returns(Vec<LiquidStakeReceipt>)]
+    GetLiquidStakeReceipts {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the stARCH received in an epoch and the cumulative amount up to it
+    #[returns(EpochStArchResponse)]
+    GetStArchByEpoch { epoch: u64 },
+    /// Returns the stARCH received per epoch with running totals
+    #[returns(Vec<EpochStArchResponse>)]
+    GetCumulativeStArch {},
+    /// Compares the receipt ledger with TOTAL_LIQUID_STAKE
+    #[returns(LiquidStakeReconciliationResponse)]
+    GetLiquidStakeReconciliation {},
+}
+
+/// Privileged messages delivered by the chain. The ibc-hooks middleware reports the outcome
+/// of transfers whose memo names this contract as `ibc_callback`.
+#[cw_serde]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -97,6 +381,228 @@ pub struct RewardSummariesResponse {
     pub total_pending_rewards: Uint128,
     pub total_deposit_pending: Uint128,
     pub total_deposit_completed: Uint128,
+    pub total_performance_fees: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EpochStArchResponse {
+    pub epoch: u64,
+    pub arch_in: Uint128,
+    pub starch_out: Uint128,
+    pub receipt_count: u64,
+    pub cumulative_arch_in: Uint128,
+    pub cumulative_starch_out: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidStakeReconciliationResponse {
+    pub current_epoch: u64,
+    pub total_liquid_stake: Uint128,
+    pub receipted_arch: Uint128,
+    pub receipted_starch: Uint128,
+    /// Liquid stake that has no receipt yet (TOTAL_LIQUID_STAKE - receipted ARCH)
+    pub unreceipted_arch: Uint128,
+    /// Receipted ARCH in excess of TOTAL_LIQUID_STAKE
+    pub over_receipted_arch: Uint128,
+    /// Average ARCH paid per stARCH across all receipts
+    pub average_redemption_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HostZoneResponse {
+    pub zone: HostZone,
+    pub is_default: bool,
+    /// Liquid stake acknowledged by this zone
+    pub liquid_stake: Uint128,
+    pub redemption_rate: Option<RedemptionRateRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyLayerMsg {
+    pub zone_id: String,
+    pub input_denom: String,
+    pub output_denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyResponse {
+    pub strategy: RraStrategy,
+    /// Product of the layer redemption rates
+    pub compounded_rate: Decimal,
+    /// Top layer position valued in base tokens
+    pub base_value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskScheduleResponse {
+    pub task: CronTask,
+    /// LAST_PROCESSING_TIMES key of the task
+    pub key: String,
+    pub schedule: Schedule,
+    pub last_run_time: u64,
+    pub last_run_height: u64,
+    /// Set for schedules in seconds
+    pub next_due_time: Option<u64>,
+    /// Set for schedules in blocks
+    pub next_due_height: Option<u64>,
+    /// The task is due and has not run yet
+    pub overdue: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CronSimulationResponse {
+    pub at_time: u64,
+    pub at_height: u64,
+    /// Tasks that would fire, in execution order
+    pub tasks: Vec<String>,
+    /// Deposit records the run would create
+    pub created_deposit_records: Vec<DepositRecord>,
+    /// Deposit records the run would transfer to a host zone
+    pub dispatched_deposit_records: Vec<DepositRecord>,
+    /// Deposit records the run would complete
+    pub completed_deposit_records: Vec<DepositRecord>,
+    /// Stake ratios a liquidity distribution would set after the run
+    pub stake_ratios: Vec<StakeRatioResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalLiquidStakeResponse {
+    /// Liquid stake recognized by the contract
+    pub total: Uint128,
+    /// Deposit records not completed yet: pending, in flight or failed and awaiting a retry
+    pub pending: Uint128,
+    /// Deposit records completed
+    pub completed: Uint128,
+    pub as_of_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeRatioResponse {
+    pub contract: String,
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionRatioResponse {
+    pub contract: String,
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardResponse {
+    pub rewards_address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedeemTokensResponse {
+    pub contract: String,
+    /// Pending redeem tokens
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiptTokenResponse {
+    /// None until `CreateReceiptToken` has run
+    pub denom: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    /// Protocol fees held, by ARCH denom
+    pub coins: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    pub address: String,
+    pub coins: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractStakeResponse {
+    pub contract: String,
+    pub stake: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractsResponse {
+    pub contracts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionRateHistoryEntry {
+    pub timestamp: u64,
+    pub redemption_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    /// Pool value over total shares; one while there are no shares
+    pub exchange_rate: Decimal,
+    pub total_shares: Uint128,
+    /// ARCH value of the pool at the host zones' redemption rates
+    pub pool_value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharesResponse {
+    pub contract: String,
+    pub shares: Uint128,
+    /// Shares times the exchange rate, in ARCH
+    pub value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionLedgerResponse {
+    pub contract: String,
+    /// Set by SetRedeemTokens and not yet distributed
+    pub pending: Uint128,
+    /// Sent out for redemption and not yet redeemed
+    pub distributed: Uint128,
+    /// Redeemed and credited to the contract's claimable balance
+    pub claimed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdviceResponse {
+    pub contract: String,
+    pub zone_id: String,
+    pub current_rate: Decimal,
+    pub short_moving_average: Decimal,
+    pub long_moving_average: Decimal,
+    pub projected_rate: Decimal,
+    pub phase: AdvisorPhase,
+    /// "stake", "hold" or "redeem"
+    pub action: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationResponse {
+    pub zone_id: String,
+    pub amount_in: Uint128,
+    pub redemption_rate: Decimal,
+    /// Output at the current redemption rate
+    pub amount_out: Uint128,
+    pub projected_rate: Decimal,
+    /// Output at the projected redemption rate
+    pub projected_amount_out: Uint128,
+}
+
+/// Query sent to a host zone's redemption rate oracle contract.
+#[cw_serde]
+pub enum RedemptionRateOracleQueryMsg {
+    RedemptionRate { denom: String },
+}
+
+#[cw_serde]
+pub struct RedemptionRateOracleResponse {
+    pub redemption_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -0,0 +1,292 @@
+// src/operator.rs
+//
+// Offline planning for the owner's operational messages. The operator supplies the contract's
+// current state as the JSON returned by its queries, together with the state it intends to reach,
+// and gets back validated ExecuteMsg payloads ready to be signed:
+// - BulkUpdateRewards: adds the difference between each contract's target and current rewards.
+// - SetRedeemTokens: adds the difference between each contract's target and current pending
+//   redeem tokens.
+// - EmitLiquidStakeEvent: reports a liquid stake, checked against the reconciliation query.
+//
+// Nothing here touches storage; the `operator` binary reads the JSON files and prints the result.
+
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::msg::{
+    ContractsResponse, ExecuteMsg, LiquidStakeReconciliationResponse, RedemptionLedgerResponse,
+    RewardResponse, RewardUpdate,
+};
+
+/// Why a plan or message was rejected. These mirror the checks the contract makes, but belong to
+/// the operator tooling rather than to the contract's own errors.
+#[derive(Error, Debug, PartialEq)]
+pub enum OperatorError {
+    #[error("Invalid operator message: {reason}")]
+    InvalidMessage { reason: String },
+
+    #[error("Contract not found: {contract_address}")]
+    ContractNotFound { contract_address: String },
+
+    #[error("Liquid stake report does not match on-chain state: reported {reported} {field}, observed {observed}")]
+    LiquidStakeVerificationFailed {
+        field: String,
+        reported: Uint128,
+        observed: Uint128,
+    },
+}
+
+/// The contract's current state, as returned by its queries.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OnChainState {
+    /// Address of the liquid staking contract the messages are sent to
+    pub contract: String,
+    /// GetAllContracts
+    pub contracts: ContractsResponse,
+    /// GetReward of each contract; a contract missing here has no rewards
+    #[serde(default)]
+    pub rewards: Vec<RewardResponse>,
+    /// GetRedemptionLedgers; a contract missing here has an empty ledger
+    #[serde(default)]
+    pub redemption_ledgers: Vec<RedemptionLedgerResponse>,
+    /// GetLiquidStakeReconciliation, needed to check a liquid stake report
+    pub reconciliation: Option<LiquidStakeReconciliationResponse>,
+}
+
+/// A contract's intended balance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TargetBalance {
+    pub contract_address: String,
+    pub amount: Uint128,
+}
+
+/// A liquid stake performed off-chain that is to be reported.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidStakeReport {
+    pub total_liquid_stake: Uint128,
+    pub stuarch_obtained: Uint128,
+    pub tx_hash: String,
+}
+
+/// The state the operator intends to reach.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorPlan {
+    /// Reward balance each contract should end up with
+    #[serde(default)]
+    pub rewards: Vec<TargetBalance>,
+    /// Pending redeem tokens each contract should end up with
+    #[serde(default)]
+    pub redeem_tokens: Vec<TargetBalance>,
+    pub liquid_stake: Option<LiquidStakeReport>,
+}
+
+/// Difference between a contract's current and target balance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalanceDiff {
+    /// "rewards" or "redeem_tokens"
+    pub field: String,
+    pub contract_address: String,
+    pub current: Uint128,
+    pub target: Uint128,
+}
+
+/// An execute message in the shape expected by `wasmd tx wasm execute`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnsignedExecute {
+    pub contract: String,
+    pub msg: ExecuteMsg,
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorOutput {
+    pub diffs: Vec<BalanceDiff>,
+    pub messages: Vec<UnsignedExecute>,
+}
+
+/// Diff the plan against the current state and build the messages that carry it out.
+/// Balances already at their target produce no message.
+pub fn build_messages(
+    state: &OnChainState,
+    plan: &OperatorPlan,
+) -> Result<OperatorOutput, OperatorError> {
+    let mut diffs = vec![];
+    let mut msgs = vec![];
+
+    let reward_diffs = diff_balances(state, "rewards", &plan.rewards, |contract| {
+        state
+            .rewards
+            .iter()
+            .find(|r| r.rewards_address == contract)
+            .map(|r| r.amount)
+    })?;
+    let updates: Vec<RewardUpdate> = reward_diffs
+        .iter()
+        .filter(|diff| diff.target > diff.current)
+        .map(|diff| RewardUpdate {
+            contract_address: diff.contract_address.clone(),
+            amount: diff.target - diff.current,
+        })
+        .collect();
+    if !updates.is_empty() {
+        msgs.push(ExecuteMsg::BulkUpdateRewards { updates });
+    }
+    diffs.extend(reward_diffs);
+
+    let redeem_diffs = diff_balances(state, "redeem_tokens", &plan.redeem_tokens, |contract| {
+        state
+            .redemption_ledgers
+            .iter()
+            .find(|l| l.contract == contract)
+            .map(|l| l.pending)
+    })?;
+    for diff in redeem_diffs.iter().filter(|diff| diff.target > diff.current) {
+        msgs.push(ExecuteMsg::SetRedeemTokens {
+            amount: diff.target - diff.current,
+            contract_address: diff.contract_address.clone(),
+        });
+    }
+    diffs.extend(redeem_diffs);
+
+    if let Some(report) = &plan.liquid_stake {
+        msgs.push(ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake: report.total_liquid_stake,
+            stuarch_obtained: report.stuarch_obtained,
+            tx_hash: report.tx_hash.clone(),
+        });
+    }
+
+    let messages = msgs
+        .into_iter()
+        .map(|msg| {
+            validate_message(state, &msg)?;
+            Ok(UnsignedExecute {
+                contract: state.contract.clone(),
+                msg,
+                funds: vec![],
+            })
+        })
+        .collect::<Result<Vec<_>, OperatorError>>()?;
+
+    Ok(OperatorOutput { diffs, messages })
+}
+
+/// Check a message against the current state the way the contract would execute it.
+/// Only BulkUpdateRewards, SetRedeemTokens and EmitLiquidStakeEvent are supported.
+pub fn validate_message(state: &OnChainState, msg: &ExecuteMsg) -> Result<(), OperatorError> {
+    match msg {
+        ExecuteMsg::BulkUpdateRewards { updates } => {
+            if updates.is_empty() {
+                return Err(invalid("BulkUpdateRewards has no updates"));
+            }
+            let mut seen = BTreeSet::new();
+            for update in updates {
+                check_registered(state, &update.contract_address)?;
+                if update.amount.is_zero() {
+                    return Err(invalid(format!(
+                        "reward update for {} is zero",
+                        update.contract_address
+                    )));
+                }
+                if !seen.insert(update.contract_address.as_str()) {
+                    return Err(invalid(format!(
+                        "{} is updated more than once",
+                        update.contract_address
+                    )));
+                }
+            }
+            Ok(())
+        }
+        ExecuteMsg::SetRedeemTokens {
+            amount,
+            contract_address,
+        } => {
+            check_registered(state, contract_address)?;
+            if amount.is_zero() {
+                return Err(invalid(format!(
+                    "redeem tokens for {} are zero",
+                    contract_address
+                )));
+            }
+            Ok(())
+        }
+        // Zero amounts are accepted, as by the contract; only the liquid stake is reconciled.
+        ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake,
+            tx_hash,
+            ..
+        } => {
+            if tx_hash.trim().is_empty() {
+                return Err(invalid("tx_hash is empty"));
+            }
+            let reconciliation = state
+                .reconciliation
+                .as_ref()
+                .ok_or_else(|| invalid("the reconciliation query result is required"))?;
+            if *total_liquid_stake > reconciliation.unreceipted_arch {
+                return Err(OperatorError::LiquidStakeVerificationFailed {
+                    field: "total_liquid_stake".to_string(),
+                    reported: *total_liquid_stake,
+                    observed: reconciliation.unreceipted_arch,
+                });
+            }
+            Ok(())
+        }
+        _ => Err(invalid("only BulkUpdateRewards, SetRedeemTokens and EmitLiquidStakeEvent are supported")),
+    }
+}
+
+/// Pair each target with its current balance. Targets below the current balance are rejected,
+/// since the contract can only add to these balances.
+fn diff_balances(
+    state: &OnChainState,
+    field: &str,
+    targets: &[TargetBalance],
+    current: impl Fn(&str) -> Option<Uint128>,
+) -> Result<Vec<BalanceDiff>, OperatorError> {
+    let mut seen = BTreeSet::new();
+    let mut diffs = vec![];
+    for target in targets {
+        check_registered(state, &target.contract_address)?;
+        if !seen.insert(target.contract_address.as_str()) {
+            return Err(invalid(format!(
+                "{} has more than one {} target",
+                target.contract_address, field
+            )));
+        }
+        let current = current(&target.contract_address).unwrap_or_default();
+        if target.amount < current {
+            return Err(invalid(format!(
+                "{} target {} for {} is below the current {}",
+                field, target.amount, target.contract_address, current
+            )));
+        }
+        diffs.push(BalanceDiff {
+            field: field.to_string(),
+            contract_address: target.contract_address.clone(),
+            current,
+            target: target.amount,
+        });
+    }
+    Ok(diffs)
+}
+
+fn check_registered(state: &OnChainState, contract_address: &str) -> Result<(), OperatorError> {
+    if state.contracts.contracts.iter().any(|c| c == contract_address) {
+        Ok(())
+    } else {
+        Err(OperatorError::ContractNotFound {
+            contract_address: contract_address.to_string(),
+        })
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> OperatorError {
+    OperatorError::InvalidMessage {
+        reason: reason.into(),
+    }
+}
@@ -0,0 +1,71 @@
+// src/overlay.rs
+//
+// Copy-on-write storage used to dry-run state changing logic from a query. Reads fall through to
+// the underlying read-only storage unless the key has been written or removed; writes are kept in
+// memory and dropped with the overlay, so the real contract state is never touched.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use cosmwasm_std::{Order, Record, Storage};
+
+pub struct StorageOverlay<'a> {
+    base: &'a dyn Storage,
+    // None marks a key removed in the overlay.
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> StorageOverlay<'a> {
+    pub fn new(base: &'a dyn Storage) -> Self {
+        StorageOverlay {
+            base,
+            writes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Storage for StorageOverlay<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        let mut merged: BTreeMap<Vec<u8>, Vec<u8>> =
+            self.base.range(start, end, Order::Ascending).collect();
+
+        let bounds = (
+            start.map_or(Bound::Unbounded, |s| Bound::Included(s.to_vec())),
+            end.map_or(Bound::Unbounded, |e| Bound::Excluded(e.to_vec())),
+        );
+        // An empty or inverted range has nothing to overlay (and would panic in BTreeMap::range).
+        if start.zip(end).is_none_or(|(s, e)| s < e) {
+            for (key, value) in self.writes.range(bounds) {
+                match value {
+                    Some(value) => merged.insert(key.clone(), value.clone()),
+                    None => merged.remove(key),
+                };
+            }
+        }
+
+        match order {
+            Order::Ascending => Box::new(merged.into_iter()),
+            Order::Descending => Box::new(merged.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
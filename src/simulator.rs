@@ -0,0 +1,354 @@
+// src/simulator.rs
+//
+// Deterministic lifecycle simulator for backtesting. It drives the contract's real `execute` and
+// `query` entry points through cw-multi-test along a scripted timeline of dApp rewards,
+// redemption rates and cron ticks, and records the contract's state after every block.
+//
+// No host zone is registered. The simulator plays the off-chain operator: after each cron tick it
+// liquid stakes the ARCH that has no receipt yet at that block's redemption rate, reports it with
+// EmitLiquidStakeEvent and distributes liquidity. Revenue is the ARCH value of all stARCH obtained
+// at the current redemption rate, less the ARCH staked for it.
+//
+// Running the same timeline under different Config intervals shows how the schedule changes the
+// stARCH obtained and the revenue before the schedule is changed in production.
+
+use cosmwasm_std::{Addr, Decimal, Empty, StdError, Timestamp, Uint128};
+use cw_multi_test::error::AnyError;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::{execute, instantiate, query, reply, sudo};
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, LiquidStakeReconciliationResponse, QueryMsg, RewardResponse,
+    StakeRatioResponse, TotalLiquidStakeResponse, TreasuryResponse,
+};
+use crate::state::{CronTask, Schedule};
+
+const OWNER: &str = "simulator_owner";
+// Unix time of the first simulated block.
+const GENESIS_TIME: u64 = 1_700_000_000;
+
+/// A scripted run of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Timeline {
+    /// Intervals the contract is instantiated with
+    pub config: InstantiateMsg,
+    /// Schedules applied after instantiation, e.g. to run a task every so many blocks
+    #[serde(default)]
+    pub schedules: Vec<TaskScheduleUpdate>,
+    pub contracts: Vec<SimulatedContract>,
+    /// Number of blocks to simulate; blocks are numbered from 1
+    pub blocks: u64,
+    pub block_time_seconds: u64,
+    pub initial_redemption_rate: Decimal,
+    /// Actions in the order they happen; several may share a block
+    pub events: Vec<TimelineEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskScheduleUpdate {
+    pub task: CronTask,
+    pub schedule: Schedule,
+}
+
+/// A dApp registered with SetContractMetadata before the first block.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulatedContract {
+    pub contract_address: String,
+    pub minimum_reward_amount: Uint128,
+    pub maximum_reward_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelineEvent {
+    pub block: u64,
+    pub action: TimelineAction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineAction {
+    /// Rewards earned by a dApp, added with UpdateReward
+    Reward {
+        contract_address: String,
+        amount: Uint128,
+    },
+    /// The host zone's redemption rate from this block on
+    RedemptionRate { rate: Decimal },
+    /// A CronJob, followed by the operator's liquid stake and liquidity distribution
+    CronTick {},
+}
+
+/// State of the contract at the end of a block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationRow {
+    pub block: u64,
+    pub timestamp: u64,
+    pub redemption_rate: Decimal,
+    /// Rewards not yet converted into deposit records
+    pub pending_rewards: Uint128,
+    /// Deposit records not yet completed
+    pub pending_deposits: Uint128,
+    pub total_liquid_stake: Uint128,
+    /// stARCH obtained so far
+    pub starch: Uint128,
+    /// ARCH value of the stARCH at the current redemption rate
+    pub starch_value: Uint128,
+    /// `starch_value` less the ARCH staked for it
+    pub revenue: i128,
+    pub treasury: Uint128,
+    /// Stake ratio of each contract, in the timeline's contract order
+    pub stake_ratios: Vec<Decimal>,
+}
+
+/// Run the timeline from a freshly instantiated contract and return one row per block.
+pub fn simulate(timeline: &Timeline) -> Result<Vec<SimulationRow>, ContractError> {
+    validate_timeline(timeline)?;
+
+    let mut app = App::default();
+    app.update_block(|block| {
+        block.height = 0;
+        block.time = Timestamp::from_seconds(GENESIS_TIME);
+    });
+    let owner = Addr::unchecked(OWNER);
+
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &timeline.config, &[], "LiquidStaking", None)
+        .map_err(simulation_error)?;
+    let run = |app: &mut App, msg: &ExecuteMsg| {
+        app.execute_contract(owner.clone(), contract_addr.clone(), msg, &[])
+            .map(|_| ())
+            .map_err(simulation_error)
+    };
+
+    for update in &timeline.schedules {
+        run(
+            &mut app,
+            &ExecuteMsg::UpdateSchedule {
+                task: update.task.clone(),
+                schedule: update.schedule,
+            },
+        )?;
+    }
+    for dapp in &timeline.contracts {
+        run(
+            &mut app,
+            &ExecuteMsg::SetContractMetadata {
+                contract_address: dapp.contract_address.clone(),
+                rewards_address: dapp.contract_address.clone(),
+                liquidity_provider_address: dapp.contract_address.clone(),
+                redemption_address: dapp.contract_address.clone(),
+                minimum_reward_amount: dapp.minimum_reward_amount,
+                maximum_reward_amount: dapp.maximum_reward_amount,
+            },
+        )?;
+    }
+
+    let mut rate = timeline.initial_redemption_rate;
+    let mut rows = vec![];
+    for block in 1..=timeline.blocks {
+        app.update_block(|b| {
+            b.height = block;
+            b.time = b.time.plus_seconds(timeline.block_time_seconds);
+        });
+
+        for event in timeline.events.iter().filter(|event| event.block == block) {
+            match &event.action {
+                TimelineAction::Reward {
+                    contract_address,
+                    amount,
+                } => run(
+                    &mut app,
+                    &ExecuteMsg::UpdateReward {
+                        rewards_address: contract_address.clone(),
+                        amount: *amount,
+                    },
+                )?,
+                TimelineAction::RedemptionRate { rate: new_rate } => rate = *new_rate,
+                TimelineAction::CronTick {} => {
+                    run(&mut app, &ExecuteMsg::CronJob {})?;
+
+                    // Liquid stake whatever has no receipt yet at the current rate.
+                    let reconciliation: LiquidStakeReconciliationResponse =
+                        query_contract(&app, &contract_addr, &QueryMsg::GetLiquidStakeReconciliation {})?;
+                    let arch = reconciliation.unreceipted_arch;
+                    let starch = arch.mul_floor(Decimal::one() / rate);
+                    if !starch.is_zero() {
+                        run(
+                            &mut app,
+                            &ExecuteMsg::EmitLiquidStakeEvent {
+                                total_liquid_stake: arch,
+                                stuarch_obtained: starch,
+                                tx_hash: format!("simulated-{}", block),
+                            },
+                        )?;
+                    }
+                    run(&mut app, &ExecuteMsg::DistributeLiquidity {})?;
+                }
+            }
+        }
+
+        rows.push(record_block(&app, &contract_addr, timeline, block, rate)?);
+    }
+
+    Ok(rows)
+}
+
+/// Render the rows as CSV with a header line. Each contract gets a `stake_ratio:<address>` column.
+pub fn to_csv(timeline: &Timeline, rows: &[SimulationRow]) -> String {
+    let mut header = vec![
+        "block".to_string(),
+        "timestamp".to_string(),
+        "redemption_rate".to_string(),
+        "pending_rewards".to_string(),
+        "pending_deposits".to_string(),
+        "total_liquid_stake".to_string(),
+        "starch".to_string(),
+        "starch_value".to_string(),
+        "revenue".to_string(),
+        "treasury".to_string(),
+    ];
+    header.extend(
+        timeline
+            .contracts
+            .iter()
+            .map(|dapp| format!("stake_ratio:{}", dapp.contract_address)),
+    );
+
+    let mut csv = header.join(",") + "\n";
+    for row in rows {
+        let mut fields = vec![
+            row.block.to_string(),
+            row.timestamp.to_string(),
+            row.redemption_rate.to_string(),
+            row.pending_rewards.to_string(),
+            row.pending_deposits.to_string(),
+            row.total_liquid_stake.to_string(),
+            row.starch.to_string(),
+            row.starch_value.to_string(),
+            row.revenue.to_string(),
+            row.treasury.to_string(),
+        ];
+        fields.extend(row.stake_ratios.iter().map(|ratio| ratio.to_string()));
+        csv += &(fields.join(",") + "\n");
+    }
+    csv
+}
+
+fn validate_timeline(timeline: &Timeline) -> Result<(), ContractError> {
+    let invalid = |reason: String| ContractError::InvalidTimeline { reason };
+
+    if timeline.initial_redemption_rate.is_zero() {
+        return Err(invalid("initial_redemption_rate must be greater than zero".to_string()));
+    }
+    for event in &timeline.events {
+        if event.block == 0 || event.block > timeline.blocks {
+            return Err(invalid(format!(
+                "event at block {} is outside blocks 1 to {}",
+                event.block, timeline.blocks
+            )));
+        }
+        match &event.action {
+            TimelineAction::RedemptionRate { rate } if rate.is_zero() => {
+                return Err(invalid(format!(
+                    "redemption rate at block {} must be greater than zero",
+                    event.block
+                )));
+            }
+            TimelineAction::Reward {
+                contract_address, ..
+            } if !timeline
+                .contracts
+                .iter()
+                .any(|dapp| dapp.contract_address == *contract_address) =>
+            {
+                return Err(invalid(format!(
+                    "reward at block {} is for unknown contract {}",
+                    event.block, contract_address
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn record_block(
+    app: &App,
+    contract_addr: &Addr,
+    timeline: &Timeline,
+    block: u64,
+    rate: Decimal,
+) -> Result<SimulationRow, ContractError> {
+    let mut pending_rewards = Uint128::zero();
+    for dapp in &timeline.contracts {
+        let reward: RewardResponse = query_contract(
+            app,
+            contract_addr,
+            &QueryMsg::GetReward {
+                rewards_address: dapp.contract_address.clone(),
+            },
+        )?;
+        pending_rewards += reward.amount;
+    }
+
+    let stake: TotalLiquidStakeResponse =
+        query_contract(app, contract_addr, &QueryMsg::GetTotalLiquidStakeQuery {})?;
+    let reconciliation: LiquidStakeReconciliationResponse =
+        query_contract(app, contract_addr, &QueryMsg::GetLiquidStakeReconciliation {})?;
+    let treasury: TreasuryResponse = query_contract(app, contract_addr, &QueryMsg::GetTreasury {})?;
+    let ratios: Vec<StakeRatioResponse> =
+        query_contract(app, contract_addr, &QueryMsg::GetAllStakeRatios {})?;
+
+    let starch_value = reconciliation.receipted_starch.mul_floor(rate);
+    let stake_ratios = timeline
+        .contracts
+        .iter()
+        .map(|dapp| {
+            ratios
+                .iter()
+                .find(|r| r.contract == dapp.contract_address)
+                .map(|r| r.ratio)
+                .unwrap_or_default()
+        })
+        .collect();
+
+    Ok(SimulationRow {
+        block,
+        timestamp: app.block_info().time.seconds(),
+        redemption_rate: rate,
+        pending_rewards,
+        pending_deposits: stake.pending,
+        total_liquid_stake: stake.total,
+        starch: reconciliation.receipted_starch,
+        starch_value,
+        revenue: starch_value.u128() as i128 - reconciliation.receipted_arch.u128() as i128,
+        treasury: treasury.coins.iter().map(|coin| coin.amount).sum(),
+        stake_ratios,
+    })
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_sudo(sudo),
+    )
+}
+
+fn query_contract<T: serde::de::DeserializeOwned>(
+    app: &App,
+    contract_addr: &Addr,
+    msg: &QueryMsg,
+) -> Result<T, ContractError> {
+    Ok(app.wrap().query_wasm_smart(contract_addr, msg)?)
+}
+
+// Keep the contract's own error when a message fails; anything else becomes a generic error.
+fn simulation_error(err: AnyError) -> ContractError {
+    err.downcast::<ContractError>()
+        .unwrap_or_else(|err| ContractError::Std(StdError::generic_err(err.to_string())))
+}
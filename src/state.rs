@@ -1,7 +1,7 @@
 // src/state.rs
 
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 use serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
 
@@ -9,20 +9,105 @@ use schemars::JsonSchema;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
-    pub liquid_staking_interval: u64,
-    pub arch_liquid_stake_interval: u64,
-    pub redemption_rate_query_interval: u64,
-    pub rewards_withdrawal_interval: u64,
+    pub liquid_staking_interval: Schedule,
+    pub arch_liquid_stake_interval: Schedule,
+    pub redemption_rate_query_interval: Schedule,
+    pub rewards_withdrawal_interval: Schedule,
     pub redemption_interval_threshold: u64,
+    // Share of converted rewards kept by the protocol, in basis points.
+    #[serde(default)]
+    pub protocol_fee_bps: u64,
+    // Share of the ARCH gained between a deposit's entry and exit redemption rate kept by the
+    // protocol, in basis points.
+    #[serde(default)]
+    pub performance_fee_bps: u64,
+}
+
+// Upper bound of fees in basis points (100%).
+pub const MAX_FEE_BPS: u64 = 10_000;
+
+// How often a periodic task runs: every so many seconds or every so many blocks. Configs stored
+// before schedules existed hold a bare number of seconds, which still deserializes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", from = "ScheduleRepr")]
+pub enum Schedule {
+    Seconds(u64),
+    Blocks(u64),
+}
+
+impl Schedule {
+    // Time or height from which a task last processed at `last_time` / `last_height` is due.
+    pub fn next_due(&self, last_time: u64, last_height: u64) -> u64 {
+        match self {
+            Schedule::Seconds(interval) => last_time + interval,
+            Schedule::Blocks(interval) => last_height + interval,
+        }
+    }
+
+    pub fn is_due(&self, last_time: u64, last_height: u64, time: u64, height: u64) -> bool {
+        let now = match self {
+            Schedule::Seconds(_) => time,
+            Schedule::Blocks(_) => height,
+        };
+        now >= self.next_due(last_time, last_height)
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Schedule::Seconds(interval) => write!(f, "{} seconds", interval),
+            Schedule::Blocks(interval) => write!(f, "{} blocks", interval),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScheduleRepr {
+    Legacy(u64),
+    Tagged(TaggedSchedule),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TaggedSchedule {
+    Seconds(u64),
+    Blocks(u64),
+}
+
+impl From<ScheduleRepr> for Schedule {
+    fn from(repr: ScheduleRepr) -> Self {
+        match repr {
+            ScheduleRepr::Legacy(seconds) | ScheduleRepr::Tagged(TaggedSchedule::Seconds(seconds)) => {
+                Schedule::Seconds(seconds)
+            }
+            ScheduleRepr::Tagged(TaggedSchedule::Blocks(blocks)) => Schedule::Blocks(blocks),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ContractMetadata {
-    pub rewards_address: String,
-    pub liquidity_provider_address: String,
+    pub rewards_address: Addr,
+    pub liquidity_provider_address: Addr,
     pub minimum_reward_amount: Uint128,
     pub maximum_reward_amount: Uint128,
-    pub redemption_address: String,
+    pub redemption_address: Addr,
+    // How the contract's rewards are split across host zones. Empty sends everything to the
+    // default host zone.
+    #[serde(default)]
+    pub strategy: Vec<ZoneAllocation>,
+    // Protocol fee in basis points charged on this contract's rewards instead of the config's.
+    #[serde(default)]
+    pub fee_bps: Option<u64>,
+}
+
+// Weighted share of a contract's rewards liquid staked on a host zone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ZoneAllocation {
+    pub zone_id: String,
+    pub weight: u64,
 }
 
 // Define DepositRecord with all necessary fields
@@ -31,25 +116,519 @@ pub struct DepositRecord {
     pub id: u64,
     pub contract_address: Addr,
     pub amount: Uint128,
-    pub status: String, // "pending" or "completed"
+    pub status: DepositStatus,
     pub timestamp: u64,
     pub block_height: u64,
+    // IBC channel and packet sequence of the transfer carrying this deposit to the host zone.
+    #[serde(default)]
+    pub ibc_channel: Option<String>,
+    #[serde(default)]
+    pub ibc_sequence: Option<u64>,
+    // Host zone this deposit is liquid staked on; None means the default host zone.
+    #[serde(default)]
+    pub zone_id: Option<String>,
+    // Host zone redemption rate when the deposit completed, i.e. the rate its liquid staking
+    // tokens were minted at. None when no rate was reported yet.
+    #[serde(default)]
+    pub entry_redemption_rate: Option<Decimal>,
+    // Pool shares minted to the contract when the deposit completed.
+    #[serde(default)]
+    pub shares: Uint128,
+    // Protocol fee kept in the treasury when the record was created from rewards, returned to
+    // the contract's rewards along with the amount if the record is refunded.
+    #[serde(default)]
+    pub protocol_fee: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    // Waiting for the next liquid stake transfer.
+    Pending,
+    // Carried by a transfer that has not been acknowledged yet.
+    InFlight,
+    Completed,
+    // The transfer failed; the record waits for a retry.
+    Failed,
+    // The transfer failed and the amount was refunded.
+    Refunded,
+}
+
+impl DepositStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DepositStatus::Pending => "pending",
+            DepositStatus::InFlight => "in_flight",
+            DepositStatus::Completed => "completed",
+            DepositStatus::Failed => "failed",
+            DepositStatus::Refunded => "refunded",
+        }
+    }
+}
+
+impl std::fmt::Display for DepositStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Deposit record as stored before 0.2.0, in a Vec per contract with a free-form status. Only
+// read by `migrate`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyDepositRecord {
+    pub id: u64,
+    pub contract_address: Addr,
+    pub amount: Uint128,
+    pub status: String,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+// Secondary indexes of DEPOSIT_RECORDS.
+pub struct DepositRecordIndexes<'a> {
+    pub status: MultiIndex<'a, String, DepositRecord, (Addr, u64)>,
+}
+
+impl<'a> IndexList<DepositRecord> for DepositRecordIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DepositRecord>> + '_> {
+        let v: Vec<&dyn Index<DepositRecord>> = vec![&self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+// Deposit records keyed by (contract, record id) and indexed by status.
+pub fn deposit_records<'a>() -> IndexedMap<'a, (&'a Addr, u64), DepositRecord, DepositRecordIndexes<'a>> {
+    let indexes = DepositRecordIndexes {
+        status: MultiIndex::new(
+            |_, record| record.status.to_string(),
+            "deposit_records_v2",
+            "deposit_records_v2__status",
+        ),
+    };
+    IndexedMap::new("deposit_records_v2", indexes)
+}
+
+// Liquid staking tokens a contract holds on a host zone and the ARCH they cost at the entry
+// redemption rates of its deposits.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+pub struct CostBasis {
+    pub lst_amount: Uint128,
+    pub arch_cost: Uint128,
+}
+
+// A liquid staking host zone that pending deposits can be transferred to over IBC.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HostZone {
+    // Registry key, e.g. "stride" or "quicksilver".
+    pub zone_id: String,
+    pub protocol: LiquidStakeProtocol,
+    // Transfer channel from Archway to the host zone.
+    pub channel_id: String,
+    // Denom of the ARCH held by this contract (e.g. "aarch").
+    pub arch_denom: String,
+    // IBC denom, on Archway, of the liquid staking token the zone returns (e.g. stARCH).
+    pub lst_denom: String,
+    // Address on the host zone that receives transfers and liquid stakes them.
+    pub receiver: String,
+    // Packet timeout, relative to the block time the transfer is sent at.
+    pub timeout_seconds: u64,
+    pub redemption_rate_source: RedemptionRateSource,
+    pub unbonding_period_seconds: u64,
+    // Where the amounts of a failed transfer are returned to.
+    pub refund_target: RefundTarget,
+    // Disabled zones receive no new transfers; in-flight transfers still complete.
+    pub enabled: bool,
+}
+
+// Liquid staking protocol running on a host zone. It decides the memo sent with transfers:
+// Stride gets an autopilot LiquidStake instruction, the others liquid stake whatever reaches
+// their deposit receiver.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquidStakeProtocol {
+    Stride,
+    Quicksilver,
+    Pstake,
+    Milkyway,
+}
+
+// Where a host zone's redemption rate comes from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedemptionRateSource {
+    // Reported by the owner with ReportRedemptionRate.
+    Reported,
+    // Queried from an oracle contract by the redemption rate cron task.
+    Oracle { contract_address: Addr },
+}
+
+// Last known redemption rate of a host zone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionRateRecord {
+    pub redemption_rate: Decimal,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+// IBC route to Stride as taken by SetLiquidStakeRoute and returned by GetLiquidStakeRoute, which
+// map it onto the default host zone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidStakeRoute {
+    // Transfer channel from Archway to Stride.
+    pub channel_id: String,
+    // Denom of the ARCH held by this contract (e.g. "aarch").
+    pub arch_denom: String,
+    // Stride address that receives the transfer and runs the autopilot LiquidStake action.
+    pub stride_receiver: String,
+    // Packet timeout, relative to the block time the transfer is sent at.
+    pub timeout_seconds: u64,
+    // Where the amounts of a failed transfer are returned to.
+    #[serde(default)]
+    pub refund_target: RefundTarget,
+}
+
+// Where the amounts of a failed liquid stake transfer are returned to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundTarget {
+    // Keep the amounts in CONTRACT_STAKES; the transfer can be retried with RetryFailedTransfers.
+    #[default]
+    ContractStakes,
+    // Move the amounts back into the contracts' pending rewards (CONTRACT_REWARDS) so the next
+    // liquid staking rewards run creates fresh deposit records for them.
+    PendingRewards,
+}
+
+// Deposit record carried by an IBC transfer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferDeposit {
+    pub contract_address: Addr,
+    pub deposit_record_id: u64,
+    pub amount: Uint128,
+}
+
+// A liquid stake transfer sent by this contract that has not been acknowledged yet, or that failed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InFlightTransfer {
+    // Host zone the transfer was sent to.
+    #[serde(default)]
+    pub zone_id: String,
+    pub channel_id: String,
+    pub sequence: u64,
+    pub amount: Uint128,
+    pub denom: String,
+    pub deposits: Vec<TransferDeposit>,
+    pub status: TransferStatus,
+    pub attempts: u32,
+    pub failure_reason: Option<String>,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    InFlight,
+    Failed,
+}
+
+// Outcome of one liquid stake reported through EmitLiquidStakeEvent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidStakeReceipt {
+    pub id: u64,
+    pub epoch: u64,
+    pub arch_in: Uint128,
+    pub starch_out: Uint128,
+    // ARCH paid per stARCH received (arch_in / starch_out), zero when no stARCH was reported.
+    pub redemption_rate: Decimal,
+    pub tx_hash: String,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+// Receipt totals for a single epoch.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct EpochReceiptTotals {
+    pub arch_in: Uint128,
+    pub starch_out: Uint128,
+    pub receipt_count: u64,
+}
+
+// Optional check of EmitLiquidStakeEvent reports against the contract's stARCH bank balance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidStakeVerification {
+    // IBC denom of stARCH held by this contract.
+    pub starch_denom: String,
+    // Allowed difference between reported and observed amounts, in basis points of the report.
+    pub tolerance_bps: u64,
+    // stARCH balance after the last verified report (or when verification was enabled).
+    pub last_balance: Uint128,
+}
+
+// A stacked liquid staking path (e.g. ARCH -> stARCH -> qstARCH -> pstk) and the position held
+// on each layer. Each layer liquid stakes the previous layer's token on a host zone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RraStrategy {
+    pub id: u64,
+    pub name: String,
+    pub layers: Vec<StrategyLayer>,
+    // Compounded redemption rate at which the strategy is unwound.
+    pub target_rate: Decimal,
+    pub status: StrategyStatus,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyLayer {
+    // Host zone that liquid stakes `input_denom` into `output_denom`.
+    pub zone_id: String,
+    pub input_denom: String,
+    pub output_denom: String,
+    // Amount of `output_denom` held on this layer; all but the last layer back the next layer.
+    pub position: Uint128,
+    // Input tokens redeemable per output token, as last synced from the host zone.
+    pub redemption_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyStatus {
+    Active,
+    Unwound,
+}
+
+// One step taken by the RRA engine on a strategy layer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyStep {
+    pub id: u64,
+    pub strategy_id: u64,
+    pub action: StrategyAction,
+    pub layer: u32,
+    pub amount_in: Uint128,
+    pub amount_out: Uint128,
+    pub redemption_rate: Decimal,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyAction {
+    // `amount_in` input tokens liquid staked into `amount_out` output tokens.
+    Stake,
+    // `amount_in` output tokens redeemed for `amount_out` input tokens.
+    Unwind,
+}
+
+// Thresholds and windows used by the Redemption Rate Advisor.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdvisorConfig {
+    // Rate from which the advisor recommends holding instead of staking.
+    pub caution_rate: Decimal,
+    // Rate from which the advisor recommends redeeming.
+    pub redemption_rate: Decimal,
+    // Number of history points in the short and long moving averages.
+    pub short_window: u32,
+    pub long_window: u32,
+    // How far ahead the projected rate looks.
+    pub projection_seconds: u64,
+}
+
+impl Default for AdvisorConfig {
+    fn default() -> Self {
+        AdvisorConfig {
+            caution_rate: Decimal::percent(120),
+            redemption_rate: Decimal::percent(140),
+            short_window: 3,
+            long_window: 10,
+            projection_seconds: 86_400,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvisorPhase {
+    Accumulation,
+    Caution,
+    Redemption,
+}
+
+// A periodic task run by the cron job or on demand with `RunTask`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CronTask {
+    LiquidStakingDappRewards,
+    ArchLiquidStakeInterval,
+    RedemptionRateQuery,
+    RewardsWithdrawal,
+}
+
+impl CronTask {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CronTask::LiquidStakingDappRewards => "liquid_staking_dapp_rewards",
+            CronTask::ArchLiquidStakeInterval => "arch_liquid_stake_interval",
+            CronTask::RedemptionRateQuery => "redemption_rate_query",
+            CronTask::RewardsWithdrawal => "rewards_withdrawal",
+        }
+    }
+}
+
+// Liquid staking tokens sent to a host zone to be redeemed for ARCH. Once the unbonding period has
+// passed and the ARCH is back, it is paid out to each contract's redemption address.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingBatch {
+    pub id: u64,
+    pub zone_id: String,
+    pub lst_denom: String,
+    pub lst_amount: Uint128,
+    // Host zone redemption rate when the batch was created.
+    pub redemption_rate: Decimal,
+    // ARCH expected back: `lst_amount` at `redemption_rate`.
+    pub expected_arch: Uint128,
+    pub redemptions: Vec<BatchRedemption>,
+    pub status: UnbondingStatus,
+    pub channel_id: String,
+    // Packet sequence of the transfer to the host zone, set in `reply`.
+    pub sequence: Option<u64>,
+    // Time from which the redeemed ARCH is expected back, set when the transfer is acknowledged.
+    pub completion_time: Option<u64>,
+    pub failure_reason: Option<String>,
+    pub timestamp: u64,
+    pub block_height: u64,
+}
+
+// A contract's redeem tokens, in liquid staking token units: `pending` is set aside by the owner
+// and not yet distributed, `distributed` has been sent out for redemption and `claimed` has been
+// redeemed and credited to the contract's claimable balance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
+pub struct RedemptionLedger {
+    pub pending: Uint128,
+    pub distributed: Uint128,
+    pub claimed: Uint128,
+    // Part of `pending` already taken out of the pool when receipt tokens were redeemed.
+    #[serde(default)]
+    pub burned: Uint128,
+}
+
+// One contract's share of an unbonding batch.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchRedemption {
+    pub contract_address: Addr,
+    pub lst_amount: Uint128,
+    // ARCH paid to the contract's redemption address when the batch completes.
+    pub arch_amount: Uint128,
+    // Part of the contract's cost basis redeemed by this batch, given back if the batch fails.
+    #[serde(default)]
+    pub cost_basis: CostBasis,
+    // Performance fee kept from `arch_amount` for the gain over the cost basis.
+    #[serde(default)]
+    pub performance_fee: Uint128,
+    // Pool shares of the contract burned for the redeemed tokens, given back if the batch fails.
+    #[serde(default)]
+    pub shares: Uint128,
+    // Part of `lst_amount` that left the pool when receipt tokens were redeemed.
+    #[serde(default)]
+    pub burned: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnbondingStatus {
+    // The liquid staking tokens are on their way to the host zone.
+    Transferring,
+    // The host zone accepted the redemption and is unbonding.
+    Unbonding,
+    // The redeemed ARCH has been paid out.
+    Completed,
+    // The transfer failed; the amounts went back to the contracts' redemption records.
+    Failed,
 }
 
 // Storage Items
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const LAST_PROCESSING_TIMES: Map<&str, u64> = Map::new("last_processing_times");
-pub const DEPOSIT_RECORDS: Map<&Addr, Vec<DepositRecord>> = Map::new("deposit_records");
+pub const LAST_PROCESSING_HEIGHTS: Map<&str, u64> = Map::new("last_processing_heights");
+// Addresses allowed to run tasks with `RunTask` besides the owner.
+pub const OPERATORS: Map<&Addr, Empty> = Map::new("operators");
+// Deposit records before 0.2.0, replaced by `deposit_records()`, only read by `migrate`.
+pub const LEGACY_DEPOSIT_RECORDS: Map<&Addr, Vec<LegacyDepositRecord>> = Map::new("deposit_records");
 pub const TOTAL_LIQUID_STAKE: Item<Uint128> = Item::new("total_liquid_stake");
 pub const CONTRACT_STAKES: Map<&Addr, Uint128> = Map::new("contract_stakes");
 pub const STAKE_RATIOS: Map<&Addr, Decimal> = Map::new("stake_ratios");
-pub const REDEEM_TOKENS: Map<&Addr, Uint128> = Map::new("redeem_tokens");
 pub const REDEEM_TOKEN_RATIOS: Map<&Addr, Decimal> = Map::new("redeem_token_ratios");
 pub const CONTRACT_METADATA: Map<&Addr, ContractMetadata> = Map::new("contract_metadata");
 pub const CONTRACT_REWARDS: Map<&Addr, Uint128> = Map::new("contract_rewards");
 pub const NEXT_DEPOSIT_RECORD_ID: Item<u64> = Item::new("next_deposit_record_id");
+pub const REDEMPTION_LEDGER: Map<&Addr, RedemptionLedger> = Map::new("redemption_ledger");
+// Tokens waiting to be claimed, keyed by (payee, contract, denom), and the total owed per denom.
+// The payee is the address a contract's payout was credited to, e.g. its redemption address;
+// either of them can claim it.
+pub const CLAIMABLE: Map<(&Addr, &Addr, &str), Uint128> = Map::new("claimable");
+pub const CLAIMABLE_TOTALS: Map<&str, Uint128> = Map::new("claimable_totals");
+// Liquid stake pool shares. Completed deposits mint shares at the pool exchange rate, the pool
+// value over the total shares; the pool is valued as POOL_ARCH, stake completed without a host
+// zone, plus each zone's POOL_LST at the zone's redemption rate.
+pub const TOTAL_SHARES: Item<Uint128> = Item::new("total_shares");
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+pub const POOL_ARCH: Item<Uint128> = Item::new("pool_arch");
+pub const POOL_LST: Map<&str, Uint128> = Map::new("pool_lst");
+// TokenFactory denom of the receipt token minted for completed deposits, once created.
+pub const RECEIPT_TOKEN_DENOM: Item<String> = Item::new("receipt_token_denom");
+// Pool shares backing the receipt token supply, one per token. They are counted in TOTAL_SHARES
+// but no longer held in SHARES by the dApp the tokens were minted to.
+pub const RECEIPT_SHARES: Item<Uint128> = Item::new("receipt_shares");
+// Fees kept by the protocol until the owner withdraws them, by ARCH denom.
+pub const TREASURY: Map<&str, Uint128> = Map::new("treasury_balances");
+// Cost basis of each (zone, contract), and the performance fees charged to each contract.
+pub const COST_BASES: Map<(&str, &Addr), CostBasis> = Map::new("cost_bases");
+pub const PERFORMANCE_FEES: Map<&Addr, Uint128> = Map::new("performance_fees");
+// Completed stake of each (zone, contract) already paid out as liquid staking tokens.
+pub const CREDITED_ZONE_STAKES: Map<(&str, &Addr), Uint128> = Map::new("credited_zone_stakes");
+// Redeem token maps replaced by REDEMPTION_LEDGER, only read by `migrate`.
+pub const REDEEM_TOKENS: Map<&Addr, Uint128> = Map::new("redeem_tokens");
 pub const REDEMPTION_RECORDS: Map<&Addr, Uint128> = Map::new("redemption_records");
 pub const REDEMPTION_TOKEN_RATIOS: Map<&Addr, Decimal> = Map::new("redemption_token_ratios");
+// Host zone registry keyed by zone id, and the zone pending deposits are sent to.
+pub const HOST_ZONES: Map<&str, HostZone> = Map::new("host_zones");
+pub const DEFAULT_HOST_ZONE: Item<String> = Item::new("default_host_zone");
+// Liquid stake acknowledged by each host zone.
+pub const HOST_ZONE_LIQUID_STAKE: Map<&str, Uint128> = Map::new("host_zone_liquid_stake");
+// Completed stake and stake ratio of each contract within a host zone, keyed by (zone, contract).
+pub const ZONE_COMPLETED_STAKES: Map<(&str, &Addr), Uint128> = Map::new("zone_completed_stakes");
+pub const ZONE_STAKE_RATIOS: Map<(&str, &Addr), Decimal> = Map::new("zone_stake_ratios");
+pub const HOST_ZONE_REDEMPTION_RATES: Map<&str, RedemptionRateRecord> =
+    Map::new("host_zone_redemption_rates");
+// Every redemption rate recorded for a host zone, keyed by (zone, timestamp).
+pub const REDEMPTION_RATE_HISTORY: Map<(&str, u64), Decimal> = Map::new("redemption_rate_history");
+pub const ADVISOR_CONFIG: Item<AdvisorConfig> = Item::new("advisor_config");
+// Transfers keyed by (channel, sequence), kept until acknowledged or refunded.
+pub const IN_FLIGHT_TRANSFERS: Map<(&str, u64), InFlightTransfer> = Map::new("in_flight_transfers");
+// Transfers dispatched in the current transaction that are waiting for their sequence in `reply`,
+// keyed by an increasing id so replies are matched in dispatch order.
+pub const PENDING_TRANSFERS: Map<u64, InFlightTransfer> = Map::new("pending_transfers");
+pub const NEXT_PENDING_TRANSFER_ID: Item<u64> = Item::new("next_pending_transfer_id");
+// Ledger of liquid stake receipts keyed by receipt id.
+pub const LIQUID_STAKE_RECEIPTS: Map<u64, LiquidStakeReceipt> = Map::new("liquid_stake_receipts");
+pub const NEXT_RECEIPT_ID: Item<u64> = Item::new("next_receipt_id");
+// Per-epoch receipt totals and the running totals across all epochs.
+pub const EPOCH_RECEIPT_TOTALS: Map<u64, EpochReceiptTotals> = Map::new("epoch_receipt_totals");
+pub const RECEIPT_TOTALS: Item<EpochReceiptTotals> = Item::new("receipt_totals");
+// Liquid stake epoch, advanced each time the arch liquid stake interval runs.
+pub const LIQUID_STAKE_EPOCH: Item<u64> = Item::new("liquid_stake_epoch");
+pub const LIQUID_STAKE_VERIFICATION: Item<LiquidStakeVerification> = Item::new("liquid_stake_verification");
+// RRA strategies keyed by id, and their steps keyed by (strategy id, step id).
+pub const STRATEGIES: Map<u64, RraStrategy> = Map::new("strategies");
+pub const NEXT_STRATEGY_ID: Item<u64> = Item::new("next_strategy_id");
+pub const STRATEGY_STEPS: Map<(u64, u64), StrategyStep> = Map::new("strategy_steps");
+// Unbonding batches keyed by id. Batches whose transfer awaits its sequence are queued in
+// PENDING_BATCH_TRANSFERS; sent ones are found by (channel, sequence) in BATCH_TRANSFERS.
+pub const UNBONDING_BATCHES: Map<u64, UnbondingBatch> = Map::new("unbonding_batches");
+pub const NEXT_UNBONDING_BATCH_ID: Item<u64> = Item::new("next_unbonding_batch_id");
+pub const PENDING_BATCH_TRANSFERS: Map<u64, Empty> = Map::new("pending_batch_transfers");
+pub const BATCH_TRANSFERS: Map<(&str, u64), u64> = Map::new("batch_transfers");
 pub const CALLBACK_INTERVAL_BLOCKS: u64 = 5;
 pub const CALLBACK_JOB_ID: u64 = 1;
 
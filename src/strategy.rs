@@ -0,0 +1,118 @@
+// src/strategy.rs
+//
+// Multi-layer Parallel Redemption Rate Arbitrage (RRA) engine. A strategy is an ordered path of
+// liquid staking layers, e.g. ARCH -> stARCH (Stride) -> qstARCH (Quicksilver) -> pstk (pSTAKE).
+// Each layer's redemption rate appreciates independently, so the value of the top layer in base
+// tokens is the product of all layer rates (1.4 x 1.4 = 1.96 with two layers).
+//
+// The functions here only do the arithmetic: they plan the stake and unwind steps for a path and
+// apply them to the layer positions. Storing the strategy and its steps is up to the contract.
+
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+
+use crate::state::{StrategyAction, StrategyLayer};
+
+/// A step planned by the engine, before it is stored with an id and block info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedStep {
+    pub action: StrategyAction,
+    pub layer: u32,
+    pub amount_in: Uint128,
+    pub amount_out: Uint128,
+    pub redemption_rate: Decimal,
+}
+
+/// Check that a path has at least one layer and that each layer stakes the previous layer's
+/// output token.
+pub fn validate_path(layers: &[StrategyLayer]) -> StdResult<()> {
+    if layers.is_empty() {
+        return Err(StdError::generic_err("strategy needs at least one layer"));
+    }
+    for pair in layers.windows(2) {
+        if pair[1].input_denom != pair[0].output_denom {
+            return Err(StdError::generic_err(format!(
+                "layer on {} stakes {} but the previous layer outputs {}",
+                pair[1].zone_id, pair[1].input_denom, pair[0].output_denom
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Product of the redemption rates of every layer: base tokens per top layer token.
+pub fn compounded_rate(layers: &[StrategyLayer]) -> Decimal {
+    layers
+        .iter()
+        .fold(Decimal::one(), |rate, layer| rate * layer.redemption_rate)
+}
+
+/// Value of the top layer position in base tokens at the current rates.
+pub fn base_value(layers: &[StrategyLayer]) -> Uint128 {
+    match layers.last() {
+        Some(top) => top.position.mul_floor(compounded_rate(layers)),
+        None => Uint128::zero(),
+    }
+}
+
+/// Liquid stake `amount` base tokens through every layer in order. Each layer mints
+/// `amount_in / redemption_rate` output tokens, which become the next layer's input. Positions are
+/// updated in place.
+pub fn stake(layers: &mut [StrategyLayer], amount: Uint128) -> StdResult<Vec<PlannedStep>> {
+    let mut steps = Vec::with_capacity(layers.len());
+    let mut amount_in = amount;
+
+    for (i, layer) in layers.iter_mut().enumerate() {
+        if layer.redemption_rate.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "no redemption rate for layer on {}",
+                layer.zone_id
+            )));
+        }
+        let amount_out = amount_in
+            .checked_div_floor(layer.redemption_rate)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        layer.position += amount_out;
+
+        steps.push(PlannedStep {
+            action: StrategyAction::Stake,
+            layer: i as u32,
+            amount_in,
+            amount_out,
+            redemption_rate: layer.redemption_rate,
+        });
+        amount_in = amount_out;
+    }
+
+    Ok(steps)
+}
+
+/// Unwind a strategy layer by layer, starting from the top. Each layer redeems its position at its
+/// redemption rate; the tokens received are the previous layer's output, which is redeemed next.
+/// Every position ends at zero and the last step's `amount_out` is in base tokens.
+pub fn unwind(layers: &mut [StrategyLayer]) -> Vec<PlannedStep> {
+    let mut steps = Vec::with_capacity(layers.len());
+    let mut redeemed = Uint128::zero();
+
+    for (i, layer) in layers.iter_mut().enumerate().rev() {
+        // Lower layer positions back the layer above, so only the top layer redeems its own
+        // position; lower layers redeem what the layer above returned.
+        let amount_in = if steps.is_empty() {
+            layer.position
+        } else {
+            redeemed
+        };
+        let amount_out = amount_in.mul_floor(layer.redemption_rate);
+        layer.position = Uint128::zero();
+
+        steps.push(PlannedStep {
+            action: StrategyAction::Unwind,
+            layer: i as u32,
+            amount_in,
+            amount_out,
+            redemption_rate: layer.redemption_rate,
+        });
+        redeemed = amount_out;
+    }
+
+    steps
+}
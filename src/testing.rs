@@ -0,0 +1,550 @@
+// src/testing.rs
+//
+// Test support for running the contract against a host zone in cw-multi-test.
+// - The mock host zone is a contract that stands in for Stride. It liquid stakes the ARCH it
+//   receives into its stToken at a redemption rate the test controls, and pays out redemptions
+//   once a configurable unbonding delay has passed. It also answers the redemption rate oracle
+//   query, so a host zone can use it as its `RedemptionRateSource::Oracle`.
+// - `MockIbcBridge` is the stargate module that connects the two. It delivers the contract's
+//   ICS-20 `MsgTransfer`s to the host zone connected to their channel, following the autopilot
+//   memo, queues the packet acks for `relay`, and mints and burns the TokenFactory messages of
+//   both contracts through the bank module. Transfers over a stalled channel are held instead and
+//   can only time out through `relay_timeouts`.
+//
+// On a real chain the stToken and the unbonded ARCH come back over IBC; here the host zone sends
+// them directly to the address named in the memo.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg,
+    CustomMsg, CustomQuery, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Querier, Response,
+    StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, BankSudo, Contract, ContractWrapper, CosmosRouter,
+    DistributionKeeper, FailingModule, GovFailingModule, IbcFailingModule, Module,
+    StakeKeeper, Stargate, StargateMsg, StargateQuery, WasmKeeper,
+};
+use cw_storage_plus::Item;
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use crate::archway::MSG_WITHDRAW_REWARDS_TYPE_URL;
+use crate::ibc::{MsgTransfer, MsgTransferResponse, ProtoCoin, MSG_TRANSFER_TYPE_URL};
+use crate::msg::{IbcLifecycleComplete, RedemptionRateOracleResponse, SudoMsg};
+use crate::tokenfactory::{
+    MsgBurn, MsgMint, MSG_BURN_TYPE_URL, MSG_CREATE_DENOM_TYPE_URL, MSG_MINT_TYPE_URL,
+};
+
+/// A cw-multi-test app whose stargate messages go through a `MockIbcBridge`.
+pub type MockApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    MockIbcBridge,
+>;
+
+#[cw_serde]
+pub struct MockHostZoneInstantiateMsg {
+    pub arch_denom: String,
+    pub lst_denom: String,
+    pub redemption_rate: Decimal,
+    pub unbonding_delay_seconds: u64,
+}
+
+#[cw_serde]
+pub enum MockHostZoneExecuteMsg {
+    /// Liquid stake the ARCH sent along and mint the stToken to `receiver`
+    LiquidStake { receiver: String },
+    /// Burn the stToken sent along and pay its ARCH to `receiver` after the unbonding delay
+    RedeemStake { receiver: String },
+    /// Pay out every redemption whose unbonding delay has passed
+    ProcessRedemptions {},
+    SetRedemptionRate { redemption_rate: Decimal },
+    SetUnbondingDelay { seconds: u64 },
+}
+
+#[cw_serde]
+pub enum MockHostZoneQueryMsg {
+    /// Same shape as `RedemptionRateOracleQueryMsg::RedemptionRate`
+    RedemptionRate { denom: String },
+    Config {},
+    PendingRedemptions {},
+}
+
+#[cw_serde]
+pub struct MockHostZoneConfig {
+    pub arch_denom: String,
+    pub lst_denom: String,
+    pub redemption_rate: Decimal,
+    pub unbonding_delay_seconds: u64,
+}
+
+#[cw_serde]
+pub struct PendingRedemption {
+    pub receiver: Addr,
+    /// ARCH owed, fixed at the redemption rate when the stToken was redeemed
+    pub amount: Uint128,
+    pub completion_time: u64,
+}
+
+const MOCK_CONFIG: Item<MockHostZoneConfig> = Item::new("mock_host_zone_config");
+const MOCK_REDEMPTIONS: Item<Vec<PendingRedemption>> = Item::new("mock_host_zone_redemptions");
+
+/// The mock host zone contract, to be stored with `App::store_code`.
+pub fn mock_host_zone_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        mock_host_zone_execute,
+        mock_host_zone_instantiate,
+        mock_host_zone_query,
+    ))
+}
+
+fn mock_host_zone_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockHostZoneInstantiateMsg,
+) -> StdResult<Response> {
+    if msg.redemption_rate.is_zero() {
+        return Err(StdError::generic_err("redemption rate must be greater than zero"));
+    }
+    MOCK_CONFIG.save(
+        deps.storage,
+        &MockHostZoneConfig {
+            arch_denom: msg.arch_denom,
+            lst_denom: msg.lst_denom,
+            redemption_rate: msg.redemption_rate,
+            unbonding_delay_seconds: msg.unbonding_delay_seconds,
+        },
+    )?;
+    MOCK_REDEMPTIONS.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn mock_host_zone_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: MockHostZoneExecuteMsg,
+) -> StdResult<Response> {
+    let mut config = MOCK_CONFIG.load(deps.storage)?;
+    match msg {
+        MockHostZoneExecuteMsg::LiquidStake { receiver } => {
+            let amount = sent_amount(&info, &config.arch_denom)?;
+            let minted = amount.div_floor(config.redemption_rate);
+            Ok(Response::new()
+                .add_message(mint_msg(&env, &config.lst_denom, minted, &receiver))
+                .add_attribute("action", "liquid_stake")
+                .add_attribute("amount", amount)
+                .add_attribute("minted", minted))
+        }
+        MockHostZoneExecuteMsg::RedeemStake { receiver } => {
+            let amount = sent_amount(&info, &config.lst_denom)?;
+            let redemption = PendingRedemption {
+                receiver: deps.api.addr_validate(&receiver)?,
+                amount: amount.mul_floor(config.redemption_rate),
+                completion_time: env.block.time.seconds() + config.unbonding_delay_seconds,
+            };
+            MOCK_REDEMPTIONS.update(deps.storage, |mut redemptions| {
+                redemptions.push(redemption.clone());
+                Ok::<_, StdError>(redemptions)
+            })?;
+            Ok(Response::new()
+                .add_message(BankMsg::Burn {
+                    amount: coins(amount.u128(), &config.lst_denom),
+                })
+                .add_attribute("action", "redeem_stake")
+                .add_attribute("amount", amount)
+                .add_attribute("arch_amount", redemption.amount)
+                .add_attribute("completion_time", redemption.completion_time.to_string()))
+        }
+        MockHostZoneExecuteMsg::ProcessRedemptions {} => {
+            let now = env.block.time.seconds();
+            let (matured, pending): (Vec<_>, Vec<_>) = MOCK_REDEMPTIONS
+                .load(deps.storage)?
+                .into_iter()
+                .partition(|redemption| redemption.completion_time <= now);
+            MOCK_REDEMPTIONS.save(deps.storage, &pending)?;
+
+            // Pay from the staked ARCH and mint the staking rewards on top of it.
+            let mut balance = deps
+                .querier
+                .query_balance(&env.contract.address, &config.arch_denom)?
+                .amount;
+            let mut res = Response::new().add_attribute("action", "process_redemptions");
+            for redemption in matured {
+                let from_balance = redemption.amount.min(balance);
+                balance -= from_balance;
+                if !from_balance.is_zero() {
+                    res = res.add_message(BankMsg::Send {
+                        to_address: redemption.receiver.to_string(),
+                        amount: coins(from_balance.u128(), &config.arch_denom),
+                    });
+                }
+                let rewards = redemption.amount - from_balance;
+                if !rewards.is_zero() {
+                    res = res.add_message(mint_msg(
+                        &env,
+                        &config.arch_denom,
+                        rewards,
+                        redemption.receiver.as_str(),
+                    ));
+                }
+            }
+            Ok(res)
+        }
+        MockHostZoneExecuteMsg::SetRedemptionRate { redemption_rate } => {
+            if redemption_rate.is_zero() {
+                return Err(StdError::generic_err("redemption rate must be greater than zero"));
+            }
+            config.redemption_rate = redemption_rate;
+            MOCK_CONFIG.save(deps.storage, &config)?;
+            Ok(Response::new().add_attribute("action", "set_redemption_rate"))
+        }
+        MockHostZoneExecuteMsg::SetUnbondingDelay { seconds } => {
+            config.unbonding_delay_seconds = seconds;
+            MOCK_CONFIG.save(deps.storage, &config)?;
+            Ok(Response::new().add_attribute("action", "set_unbonding_delay"))
+        }
+    }
+}
+
+fn mock_host_zone_query(deps: Deps, _env: Env, msg: MockHostZoneQueryMsg) -> StdResult<Binary> {
+    match msg {
+        MockHostZoneQueryMsg::RedemptionRate { denom } => {
+            let config = MOCK_CONFIG.load(deps.storage)?;
+            if denom != config.lst_denom {
+                return Err(StdError::generic_err(format!("unknown denom {}", denom)));
+            }
+            to_json_binary(&RedemptionRateOracleResponse {
+                redemption_rate: config.redemption_rate,
+            })
+        }
+        MockHostZoneQueryMsg::Config {} => to_json_binary(&MOCK_CONFIG.load(deps.storage)?),
+        MockHostZoneQueryMsg::PendingRedemptions {} => {
+            to_json_binary(&MOCK_REDEMPTIONS.load(deps.storage)?)
+        }
+    }
+}
+
+fn sent_amount(info: &MessageInfo, denom: &str) -> StdResult<Uint128> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(StdError::generic_err(format!("expected a single {} coin", denom))),
+    }
+}
+
+fn mint_msg(env: &Env, denom: &str, amount: Uint128, to: &str) -> CosmosMsg {
+    let msg = MsgMint {
+        sender: env.contract.address.to_string(),
+        amount: Some(ProtoCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }),
+        mint_to_address: to.to_string(),
+    };
+    CosmosMsg::Stargate {
+        type_url: MSG_MINT_TYPE_URL.to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    }
+}
+
+/// A transfer handed to a host zone whose ack has not been relayed yet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedPacket {
+    pub channel: String,
+    pub sequence: u64,
+    /// Address named in the memo's `ibc_callback`, which receives the ack through `sudo`
+    pub callback: Option<String>,
+    /// Error of the host zone when it could not process the transfer
+    pub error: Option<String>,
+    /// Held on a stalled channel without reaching the host zone, so it can only time out
+    pub stalled: bool,
+}
+
+#[cw_serde]
+struct TransferMemo {
+    autopilot: Option<AutopilotMemo>,
+    ibc_callback: Option<String>,
+}
+
+#[cw_serde]
+struct AutopilotMemo {
+    receiver: String,
+    stakeibc: StakeibcMemo,
+}
+
+#[cw_serde]
+struct StakeibcMemo {
+    action: String,
+    ibc_receiver: String,
+}
+
+/// Stargate module connecting the contract's IBC transfers to mock host zones.
+#[derive(Default, Clone)]
+pub struct MockIbcBridge {
+    host_zones: Rc<RefCell<HashMap<String, Addr>>>,
+    packets: Rc<RefCell<Vec<QueuedPacket>>>,
+    next_sequence: Rc<RefCell<u64>>,
+    stalled_channels: Rc<RefCell<HashSet<String>>>,
+}
+
+impl MockIbcBridge {
+    /// Build an app with this bridge and the given initial balances.
+    pub fn app(&self, balances: &[(&str, Vec<Coin>)]) -> MockApp {
+        AppBuilder::default()
+            .with_stargate(self.clone())
+            .build(|router, _, storage| {
+                for (address, coins) in balances {
+                    router
+                        .bank
+                        .init_balance(storage, &Addr::unchecked(*address), coins.clone())
+                        .unwrap();
+                }
+            })
+    }
+
+    /// Deliver transfers over `channel_id` to `host_zone`.
+    pub fn connect(&self, channel_id: &str, host_zone: &Addr) {
+        self.host_zones
+            .borrow_mut()
+            .insert(channel_id.to_string(), host_zone.clone());
+    }
+
+    /// Hold transfers over `channel_id` instead of delivering them, as if no relayer carried
+    /// them to the host zone.
+    pub fn stall(&self, channel_id: &str) {
+        self.stalled_channels.borrow_mut().insert(channel_id.to_string());
+    }
+
+    /// Deliver transfers over `channel_id` again. Packets already held stay held.
+    pub fn resume(&self, channel_id: &str) {
+        self.stalled_channels.borrow_mut().remove(channel_id);
+    }
+
+    /// Packets whose acks or timeouts have not been relayed yet.
+    pub fn queued_packets(&self) -> Vec<QueuedPacket> {
+        self.packets.borrow().clone()
+    }
+
+    /// Deliver the ack of every queued packet that reached its host zone to its callback address.
+    /// Held packets stay queued for `relay_timeouts`.
+    pub fn relay(&self, app: &mut MockApp) -> AnyResult<Vec<AppResponse>> {
+        let packets = self.take_packets(false);
+        let mut responses = vec![];
+        for packet in packets {
+            let Some(callback) = packet.callback else {
+                continue;
+            };
+            let (ack, success) = match packet.error {
+                None => (r#"{"result":"AQ=="}"#.to_string(), true),
+                Some(error) => (format!(r#"{{"error":"{}"}}"#, error), false),
+            };
+            let msg = SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: packet.channel,
+                sequence: packet.sequence,
+                ack,
+                success,
+            });
+            responses.push(app.wasm_sudo(Addr::unchecked(callback), &msg)?);
+        }
+        Ok(responses)
+    }
+
+    /// Time out every held packet, delivering the timeout to its callback address. The tokens of
+    /// a held transfer never left the sender, as if the ICS-20 escrow had refunded them.
+    pub fn relay_timeouts(&self, app: &mut MockApp) -> AnyResult<Vec<AppResponse>> {
+        let packets = self.take_packets(true);
+        let mut responses = vec![];
+        for packet in packets {
+            let Some(callback) = packet.callback else {
+                continue;
+            };
+            let msg = SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                channel: packet.channel,
+                sequence: packet.sequence,
+            });
+            responses.push(app.wasm_sudo(Addr::unchecked(callback), &msg)?);
+        }
+        Ok(responses)
+    }
+
+    /// Remove and return the queued packets that are, or are not, held.
+    fn take_packets(&self, stalled: bool) -> Vec<QueuedPacket> {
+        let mut packets = self.packets.borrow_mut();
+        let (taken, kept) = packets.drain(..).partition(|packet| packet.stalled == stalled);
+        *packets = kept;
+        taken
+    }
+
+    fn transfer<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        transfer: MsgTransfer,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let Some(host_zone) = self.host_zones.borrow().get(&transfer.source_channel).cloned() else {
+            bail!("No host zone connected to {}", transfer.source_channel);
+        };
+        let memo: TransferMemo = if transfer.memo.is_empty() {
+            TransferMemo {
+                autopilot: None,
+                ibc_callback: None,
+            }
+        } else {
+            from_json(transfer.memo.as_bytes())?
+        };
+
+        // Without autopilot the host zone liquid stakes whatever reaches it for the sender.
+        let (action, receiver) = match &memo.autopilot {
+            Some(autopilot) => (
+                autopilot.stakeibc.action.as_str(),
+                autopilot.stakeibc.ibc_receiver.clone(),
+            ),
+            None => ("LiquidStake", sender.to_string()),
+        };
+        let msg = match action {
+            "LiquidStake" => MockHostZoneExecuteMsg::LiquidStake { receiver },
+            "RedeemStake" => MockHostZoneExecuteMsg::RedeemStake { receiver },
+            _ => bail!("Unsupported autopilot action {}", action),
+        };
+        let token = transfer.token.unwrap_or_default();
+        let execute = WasmMsg::Execute {
+            contract_addr: host_zone.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: coins(token.amount.parse::<u128>()?, token.denom),
+        };
+        let stalled = self.stalled_channels.borrow().contains(&transfer.source_channel);
+        // A failing host zone answers with an error ack rather than failing the transfer.
+        let error = if stalled {
+            None
+        } else {
+            router
+                .execute(api, storage, block, sender, execute.into())
+                .err()
+                .map(|err| err.root_cause().to_string())
+        };
+
+        let mut next_sequence = self.next_sequence.borrow_mut();
+        *next_sequence += 1;
+        self.packets.borrow_mut().push(QueuedPacket {
+            channel: transfer.source_channel,
+            sequence: *next_sequence,
+            callback: memo.ibc_callback,
+            error,
+            stalled,
+        });
+
+        Ok(AppResponse {
+            events: vec![],
+            data: Some(Binary::from(
+                MsgTransferResponse {
+                    sequence: *next_sequence,
+                }
+                .encode_to_vec(),
+            )),
+        })
+    }
+}
+
+impl Module for MockIbcBridge {
+    type ExecT = StargateMsg;
+    type QueryT = StargateQuery;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: StargateMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg.type_url.as_str() {
+            MSG_TRANSFER_TYPE_URL => {
+                let transfer = MsgTransfer::decode(msg.value.as_slice())?;
+                self.transfer(api, storage, router, block, sender, transfer)
+            }
+            // There are no Archway rewards to withdraw in tests.
+            MSG_CREATE_DENOM_TYPE_URL | MSG_WITHDRAW_REWARDS_TYPE_URL => Ok(AppResponse::default()),
+            MSG_MINT_TYPE_URL => {
+                let mint = MsgMint::decode(msg.value.as_slice())?;
+                let amount = mint.amount.unwrap_or_default();
+                router.sudo(
+                    api,
+                    storage,
+                    block,
+                    BankSudo::Mint {
+                        to_address: mint.mint_to_address,
+                        amount: coins(amount.amount.parse::<u128>()?, amount.denom),
+                    }
+                    .into(),
+                )
+            }
+            MSG_BURN_TYPE_URL => {
+                let burn = MsgBurn::decode(msg.value.as_slice())?;
+                let amount = burn.amount.unwrap_or_default();
+                router.execute(
+                    api,
+                    storage,
+                    block,
+                    Addr::unchecked(burn.burn_from_address),
+                    BankMsg::Burn {
+                        amount: coins(amount.amount.parse::<u128>()?, amount.denom),
+                    }
+                    .into(),
+                )
+            }
+            _ => bail!("Unexpected stargate msg {}", msg.type_url),
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: StargateQuery,
+    ) -> AnyResult<Binary> {
+        bail!("Unexpected stargate query {}", request.path)
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        msg: Empty,
+    ) -> AnyResult<AppResponse> {
+        bail!("Unexpected sudo msg {:?}", msg)
+    }
+}
+
+impl Stargate for MockIbcBridge {}
@@ -0,0 +1,93 @@
+// src/tokenfactory.rs
+//
+// TokenFactory module messages for the receipt token. The contract creates a single denom,
+// `factory/{contract}/{subdenom}`, mints it to a dApp when the dApp's deposit completes and burns
+// what the dApp hands back when it redeems. The messages are sent as stargate messages encoded
+// with prost.
+
+use cosmwasm_std::{Binary, Coin, CosmosMsg, Env};
+use prost::Message;
+
+use crate::ibc::ProtoCoin;
+
+pub const MSG_CREATE_DENOM_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+pub const MSG_MINT_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+pub const MSG_BURN_TYPE_URL: &str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+
+/// Protobuf encoding of `osmosis.tokenfactory.v1beta1.MsgCreateDenom`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgCreateDenom {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub subdenom: String,
+}
+
+/// Protobuf encoding of `osmosis.tokenfactory.v1beta1.MsgMint`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgMint {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+    #[prost(string, tag = "3")]
+    pub mint_to_address: String,
+}
+
+/// Protobuf encoding of `osmosis.tokenfactory.v1beta1.MsgBurn`.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgBurn {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+    #[prost(string, tag = "3")]
+    pub burn_from_address: String,
+}
+
+/// Full denom of a TokenFactory token created by this contract.
+pub fn factory_denom(env: &Env, subdenom: &str) -> String {
+    format!("factory/{}/{}", env.contract.address, subdenom)
+}
+
+pub fn create_denom_msg(env: &Env, subdenom: &str) -> CosmosMsg {
+    let msg = MsgCreateDenom {
+        sender: env.contract.address.to_string(),
+        subdenom: subdenom.to_string(),
+    };
+    stargate_msg(MSG_CREATE_DENOM_TYPE_URL, msg.encode_to_vec())
+}
+
+/// Mint `amount` of a denom created by this contract to `recipient`.
+pub fn mint_msg(env: &Env, amount: Coin, recipient: &str) -> CosmosMsg {
+    let msg = MsgMint {
+        sender: env.contract.address.to_string(),
+        amount: Some(proto_coin(amount)),
+        mint_to_address: recipient.to_string(),
+    };
+    stargate_msg(MSG_MINT_TYPE_URL, msg.encode_to_vec())
+}
+
+/// Burn `amount` of a denom created by this contract from this contract's own balance.
+pub fn burn_msg(env: &Env, amount: Coin) -> CosmosMsg {
+    let msg = MsgBurn {
+        sender: env.contract.address.to_string(),
+        amount: Some(proto_coin(amount)),
+        burn_from_address: env.contract.address.to_string(),
+    };
+    stargate_msg(MSG_BURN_TYPE_URL, msg.encode_to_vec())
+}
+
+fn proto_coin(coin: Coin) -> ProtoCoin {
+    ProtoCoin {
+        denom: coin.denom,
+        amount: coin.amount.to_string(),
+    }
+}
+
+fn stargate_msg(type_url: &str, value: Vec<u8>) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: Binary::from(value),
+    }
+}
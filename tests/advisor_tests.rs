@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod advisor_tests {
+    use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+    use cosmwasm_liquid_staking::contract::{execute, instantiate, query};
+    use cosmwasm_liquid_staking::error::ContractError;
+    use cosmwasm_liquid_staking::msg::{
+        AdviceResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RedemptionRateHistoryEntry, SimulationResponse,
+    };
+    use cosmwasm_liquid_staking::state::{
+        AdvisorConfig, AdvisorPhase, LiquidStakeProtocol, RedemptionRateSource,
+    };
+
+    const OWNER: &str = "wasm1ownerxyz";
+    const DAPP: &str = "wasm1dappxyz";
+
+    fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    /// Instantiate the contract and register Stride as the default host zone.
+    fn setup(app: &mut App) -> Addr {
+        let code_id = app.store_code(contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    liquid_staking_interval: 100,
+                    arch_liquid_stake_interval: 100,
+                    redemption_rate_query_interval: 100,
+                    rewards_withdrawal_interval: 100,
+                    redemption_interval_threshold: 100,
+                },
+                &[],
+                "LiquidStaking",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::AddHostZone {
+                zone_id: "stride".to_string(),
+                protocol: LiquidStakeProtocol::Stride,
+                channel_id: "channel-0".to_string(),
+                arch_denom: "aarch".to_string(),
+                lst_denom: "ibc/starch".to_string(),
+                receiver: "stride1receiverxyz".to_string(),
+                timeout_seconds: 600,
+                redemption_rate_source: RedemptionRateSource::Reported,
+                unbonding_period_seconds: 14 * 24 * 3600,
+                refund_target: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        contract_addr
+    }
+
+    /// Report a rate for Stride, 100 seconds after the previous report.
+    fn report_rate(app: &mut App, contract_addr: &Addr, rate: Decimal) {
+        app.update_block(|b| b.time = b.time.plus_seconds(100));
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: "stride".to_string(),
+                redemption_rate: rate,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    fn set_advisor_config(
+        app: &mut App,
+        contract_addr: &Addr,
+        sender: &str,
+        config: &AdvisorConfig,
+    ) -> anyhow::Result<()> {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            contract_addr.clone(),
+            &ExecuteMsg::SetAdvisorConfig {
+                caution_rate: config.caution_rate,
+                redemption_rate: config.redemption_rate,
+                short_window: config.short_window,
+                long_window: config.long_window,
+                projection_seconds: config.projection_seconds,
+            },
+            &[],
+        )
+        .map(|_| ())
+    }
+
+    fn get_advice(app: &App, contract_addr: &Addr) -> AdviceResponse {
+        app.wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetAdvice {
+                    contract: DAPP.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_set_advisor_config_validation() {
+        let mut app = App::default();
+        let contract_addr = setup(&mut app);
+
+        let config: AdvisorConfig = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetAdvisorConfig {})
+            .unwrap();
+        assert_eq!(config, AdvisorConfig::default());
+
+        let err = set_advisor_config(&mut app, &contract_addr, DAPP, &config).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+
+        let inverted = AdvisorConfig {
+            caution_rate: Decimal::percent(150),
+            ..AdvisorConfig::default()
+        };
+        let err = set_advisor_config(&mut app, &contract_addr, OWNER, &inverted).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidAdvisorConfig { .. })
+        ));
+
+        let bad_windows = AdvisorConfig {
+            short_window: 5,
+            long_window: 4,
+            ..AdvisorConfig::default()
+        };
+        let err = set_advisor_config(&mut app, &contract_addr, OWNER, &bad_windows).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidAdvisorConfig { .. })
+        ));
+
+        // Without any reported rate the advisor has nothing to go on.
+        let err = app
+            .wrap()
+            .query_wasm_smart::<AdviceResponse>(
+                &contract_addr,
+                &QueryMsg::GetAdvice {
+                    contract: DAPP.to_string(),
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("No redemption rate"));
+    }
+
+    #[test]
+    fn test_advice_follows_projected_rate() {
+        let mut app = App::default();
+        let contract_addr = setup(&mut app);
+        let config = AdvisorConfig {
+            projection_seconds: 100,
+            ..AdvisorConfig::default()
+        };
+        set_advisor_config(&mut app, &contract_addr, OWNER, &config).unwrap();
+
+        // A single point has no trend: the projection is the current rate.
+        report_rate(&mut app, &contract_addr, Decimal::one());
+        let advice = get_advice(&app, &contract_addr);
+        assert_eq!(advice.zone_id, "stride");
+        assert_eq!(advice.projected_rate, Decimal::one());
+        assert_eq!(advice.phase, AdvisorPhase::Accumulation);
+        assert_eq!(advice.action, "stake");
+
+        // 1.0 -> 1.1 over 100s projects 1.2 another 100s out.
+        report_rate(&mut app, &contract_addr, Decimal::percent(110));
+        let advice = get_advice(&app, &contract_addr);
+        assert_eq!(advice.current_rate, Decimal::percent(110));
+        assert_eq!(advice.projected_rate, Decimal::percent(120));
+        assert_eq!(advice.phase, AdvisorPhase::Caution);
+        assert_eq!(advice.action, "hold");
+
+        // 1.0 -> 1.3 over 200s projects 1.45 another 100s out.
+        report_rate(&mut app, &contract_addr, Decimal::percent(130));
+        let advice = get_advice(&app, &contract_addr);
+        assert_eq!(advice.projected_rate, Decimal::percent(145));
+        assert_eq!(advice.short_moving_average, Decimal::from_ratio(34u128, 30u128));
+        assert_eq!(advice.phase, AdvisorPhase::Redemption);
+        assert_eq!(advice.action, "redeem");
+
+        let history: Vec<RedemptionRateHistoryEntry> = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::GetRedemptionRateHistory {
+                    zone_id: "stride".to_string(),
+                    start_after: None,
+                    limit: Some(2),
+                },
+            )
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].redemption_rate, Decimal::one());
+        assert_eq!(history[1].timestamp, history[0].timestamp + 100);
+    }
+
+    #[test]
+    fn test_simulate_stake_and_redeem() {
+        let mut app = App::default();
+        let contract_addr = setup(&mut app);
+        report_rate(&mut app, &contract_addr, Decimal::percent(125));
+
+        let stake: SimulationResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::SimulateStake {
+                    amount: Uint128::new(1000),
+                },
+            )
+            .unwrap();
+        assert_eq!(stake.zone_id, "stride");
+        assert_eq!(stake.amount_out, Uint128::new(800));
+        assert_eq!(stake.projected_amount_out, Uint128::new(800));
+
+        let redeem: SimulationResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::SimulateRedeem {
+                    amount: Uint128::new(1000),
+                },
+            )
+            .unwrap();
+        assert_eq!(redeem.redemption_rate, Decimal::percent(125));
+        assert_eq!(redeem.amount_out, Uint128::new(1250));
+    }
+}
@@ -0,0 +1,1710 @@
+#[cfg(test)]
+mod ibc_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use anyhow::{bail, Result as AnyResult};
+    use cosmwasm_std::{
+        coin, coins, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CustomMsg, CustomQuery, Decimal,
+        Deps, DepsMut, Empty, Env, MessageInfo, Querier, Response, StdResult, Storage, Uint128,
+    };
+    use cw_multi_test::{
+        App, AppBuilder, AppResponse, BankKeeper, BankSudo, ContractWrapper, CosmosRouter, DistributionKeeper,
+        Executor, FailingModule, GovFailingModule, IbcFailingModule, Module, StakeKeeper, Stargate,
+        StargateMsg, StargateQuery, WasmKeeper,
+    };
+    use prost::Message;
+    use serde::de::DeserializeOwned;
+
+    use cosmwasm_liquid_staking::archway::MSG_WITHDRAW_REWARDS_TYPE_URL;
+    use cosmwasm_liquid_staking::contract::{execute, instantiate, query, reply, sudo};
+    use cosmwasm_liquid_staking::error::ContractError;
+    use cosmwasm_liquid_staking::ibc::{MsgTransfer, MsgTransferResponse, MSG_TRANSFER_TYPE_URL};
+    use cosmwasm_liquid_staking::tokenfactory::{
+        MsgBurn, MsgMint, MSG_BURN_TYPE_URL, MSG_CREATE_DENOM_TYPE_URL, MSG_MINT_TYPE_URL,
+    };
+    use cosmwasm_liquid_staking::msg::{
+        ExecuteMsg, HostZoneResponse, IbcLifecycleComplete, InstantiateMsg, QueryMsg,
+        ClaimableResponse, ContractStakeResponse, ExchangeRateResponse, RewardResponse, ReceiptTokenResponse, RedemptionLedgerResponse, StakeRatioResponse,
+        TotalLiquidStakeResponse, TreasuryResponse, RewardSummariesResponse, SharesResponse, RedemptionRateOracleQueryMsg, RedemptionRateOracleResponse, SudoMsg,
+    };
+    use cosmwasm_liquid_staking::state::{
+        ContractMetadata, DepositRecord, DepositStatus, InFlightTransfer, LiquidStakeProtocol, LiquidStakeVerification, RedemptionRateSource,
+        RefundTarget, TransferStatus, UnbondingBatch, UnbondingStatus, ZoneAllocation,
+    };
+
+    const OWNER: &str = "wasm1ownerxyz";
+    const DAPP: &str = "wasm1dappxyz";
+    const ESCROW: &str = "wasm1ibcescrowxyz";
+    const CHANNEL: &str = "channel-0";
+    const STRIDE_RECEIVER: &str = "stride1receiverxyz";
+    const QUICKSILVER_CHANNEL: &str = "channel-1";
+    const QUICKSILVER_RECEIVER: &str = "quick1depositxyz";
+    const DENOM: &str = "aarch";
+    const STRIDE_LST: &str = "ibc/ststride";
+    const REDEMPTION_ADDRESS: &str = "wasm1redemptionxyz";
+
+    /// Mock IBC transfer module. It accepts stargate `MsgTransfer`s, escrows the tokens, records
+    /// the packet and answers with a `MsgTransferResponse` carrying an increasing sequence. The
+    /// TokenFactory messages of the receipt token mint and burn through the bank module, and the
+    /// cron job's Archway rewards withdrawals are accepted without rewards to withdraw.
+    #[derive(Default, Clone)]
+    struct MockIbcTransfer {
+        sent: Rc<RefCell<Vec<MsgTransfer>>>,
+    }
+
+    impl Module for MockIbcTransfer {
+        type ExecT = StargateMsg;
+        type QueryT = StargateQuery;
+        type SudoT = Empty;
+
+        fn execute<ExecC, QueryC>(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            block: &BlockInfo,
+            sender: Addr,
+            msg: StargateMsg,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: CustomMsg + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            match msg.type_url.as_str() {
+                MSG_TRANSFER_TYPE_URL => {}
+                MSG_CREATE_DENOM_TYPE_URL | MSG_WITHDRAW_REWARDS_TYPE_URL => return Ok(AppResponse::default()),
+                MSG_MINT_TYPE_URL => {
+                    let mint = MsgMint::decode(msg.value.as_slice())?;
+                    let amount = mint.amount.unwrap();
+                    return router.sudo(
+                        api,
+                        storage,
+                        block,
+                        BankSudo::Mint {
+                            to_address: mint.mint_to_address,
+                            amount: coins(amount.amount.parse::<u128>()?, amount.denom),
+                        }
+                        .into(),
+                    );
+                }
+                MSG_BURN_TYPE_URL => {
+                    let burn = MsgBurn::decode(msg.value.as_slice())?;
+                    let amount = burn.amount.unwrap();
+                    return router.execute(
+                        api,
+                        storage,
+                        block,
+                        Addr::unchecked(burn.burn_from_address),
+                        BankMsg::Burn {
+                            amount: coins(amount.amount.parse::<u128>()?, amount.denom),
+                        }
+                        .into(),
+                    );
+                }
+                _ => bail!("Unexpected stargate msg {}", msg.type_url),
+            }
+            let transfer = MsgTransfer::decode(msg.value.as_slice())?;
+            let token = transfer.token.clone().unwrap();
+
+            // Escrow the transferred tokens like the ICS-20 module does.
+            router.execute(
+                api,
+                storage,
+                block,
+                sender,
+                BankMsg::Send {
+                    to_address: ESCROW.to_string(),
+                    amount: coins(token.amount.parse::<u128>()?, token.denom),
+                }
+                .into(),
+            )?;
+
+            let mut sent = self.sent.borrow_mut();
+            sent.push(transfer);
+            let data = MsgTransferResponse {
+                sequence: sent.len() as u64,
+            }
+            .encode_to_vec();
+
+            Ok(AppResponse {
+                events: vec![],
+                data: Some(Binary::from(data)),
+            })
+        }
+
+        fn query(
+            &self,
+            _api: &dyn Api,
+            _storage: &dyn Storage,
+            _querier: &dyn Querier,
+            _block: &BlockInfo,
+            request: StargateQuery,
+        ) -> AnyResult<Binary> {
+            bail!("Unexpected stargate query {}", request.path)
+        }
+
+        fn sudo<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            msg: Empty,
+        ) -> AnyResult<AppResponse> {
+            bail!("Unexpected sudo msg {:?}", msg)
+        }
+    }
+
+    impl Stargate for MockIbcTransfer {}
+
+    type IbcApp = App<
+        BankKeeper,
+        cosmwasm_std::testing::MockApi,
+        cosmwasm_std::testing::MockStorage,
+        FailingModule<Empty, Empty, Empty>,
+        WasmKeeper<Empty, Empty>,
+        StakeKeeper,
+        DistributionKeeper,
+        IbcFailingModule,
+        GovFailingModule,
+        MockIbcTransfer,
+    >;
+
+    fn mock_app(ibc: MockIbcTransfer) -> IbcApp {
+        AppBuilder::default()
+            .with_stargate(ibc)
+            .build(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(OWNER),
+                        vec![coin(1_000_000, DENOM), coin(1_000_000, STRIDE_LST)],
+                    )
+                    .unwrap();
+            })
+    }
+
+    fn add_host_zone_msg(
+        zone_id: &str,
+        protocol: LiquidStakeProtocol,
+        channel_id: &str,
+        receiver: &str,
+        redemption_rate_source: RedemptionRateSource,
+    ) -> ExecuteMsg {
+        ExecuteMsg::AddHostZone {
+            zone_id: zone_id.to_string(),
+            protocol,
+            channel_id: channel_id.to_string(),
+            arch_denom: DENOM.to_string(),
+            lst_denom: format!("ibc/st{}", zone_id),
+            receiver: receiver.to_string(),
+            timeout_seconds: 600,
+            redemption_rate_source,
+            unbonding_period_seconds: 14 * 24 * 3600,
+            refund_target: None,
+        }
+    }
+
+    fn update_host_zone_msg(zone_id: &str) -> ExecuteMsg {
+        ExecuteMsg::UpdateHostZone {
+            zone_id: zone_id.to_string(),
+            channel_id: None,
+            lst_denom: None,
+            receiver: None,
+            timeout_seconds: None,
+            redemption_rate_source: None,
+            unbonding_period_seconds: None,
+            refund_target: None,
+        }
+    }
+
+    fn host_zone(app: &IbcApp, contract_addr: &Addr, zone_id: &str) -> HostZoneResponse {
+        app.wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetHostZone {
+                    zone_id: zone_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    /// Instantiate the contract, register one dApp with 100 pending rewards, register Stride as
+    /// the default host zone and fund the contract with ARCH to transfer.
+    fn setup(app: &mut IbcApp) -> Addr {
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::new(execute, instantiate, query)
+                .with_reply(reply)
+                .with_sudo(sudo),
+        ));
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    liquid_staking_interval: 1,
+                    arch_liquid_stake_interval: 3,
+                    redemption_rate_query_interval: 100,
+                    rewards_withdrawal_interval: 100,
+                    redemption_interval_threshold: 100,
+                },
+                &[],
+                "LiquidStaking",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::SetContractMetadata {
+                contract_address: DAPP.to_string(),
+                rewards_address: "wasm1rewardsxyz".to_string(),
+                liquidity_provider_address: "wasm1lpxyz".to_string(),
+                redemption_address: "wasm1redemptionxyz".to_string(),
+                minimum_reward_amount: Uint128::new(50),
+                maximum_reward_amount: Uint128::new(1000),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &add_host_zone_msg(
+                "stride",
+                LiquidStakeProtocol::Stride,
+                CHANNEL,
+                STRIDE_RECEIVER,
+                RedemptionRateSource::Reported,
+            ),
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: DAPP.to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(1000, DENOM))
+            .unwrap();
+
+        contract_addr
+    }
+
+    /// Run the cron twice: the first run creates the pending deposit record, the second one
+    /// reaches the arch liquid stake interval and dispatches the transfer.
+    fn run_cron_until_transfer(app: &mut IbcApp, contract_addr: &Addr) {
+        for _ in 0..2 {
+            app.update_block(|b| b.time = b.time.plus_seconds(2));
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::CronJob {},
+                &[],
+            )
+            .unwrap();
+        }
+    }
+
+    fn deposit_records(app: &IbcApp, contract_addr: &Addr) -> Vec<DepositRecord> {
+        app.wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetDepositRecords {
+                    contract: DAPP.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn in_flight_transfers(app: &IbcApp, contract_addr: &Addr) -> Vec<InFlightTransfer> {
+        app.wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetInFlightTransfers {})
+            .unwrap()
+    }
+
+    fn timeout(app: &mut IbcApp, contract_addr: &Addr, sequence: u64) {
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                channel: CHANNEL.to_string(),
+                sequence,
+            }),
+        )
+        .unwrap();
+    }
+
+    fn total_liquid_stake(app: &IbcApp, contract_addr: &Addr) -> Uint128 {
+        let response: TotalLiquidStakeResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetTotalLiquidStakeQuery {})
+            .unwrap();
+        response.total
+    }
+
+    #[test]
+    fn test_arch_liquid_stake_interval_sends_autopilot_transfer() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+
+        run_cron_until_transfer(&mut app, &contract_addr);
+
+        let sent = ibc.sent.borrow();
+        assert_eq!(sent.len(), 1);
+        let transfer = &sent[0];
+        assert_eq!(transfer.source_channel, CHANNEL);
+        assert_eq!(transfer.sender, contract_addr.to_string());
+        assert_eq!(transfer.receiver, STRIDE_RECEIVER);
+        assert_eq!(transfer.token.as_ref().unwrap().amount, "100");
+
+        let memo: serde_json::Value = serde_json::from_str(&transfer.memo).unwrap();
+        assert_eq!(memo["autopilot"]["receiver"], STRIDE_RECEIVER);
+        assert_eq!(memo["autopilot"]["stakeibc"]["action"], "LiquidStake");
+        assert_eq!(memo["autopilot"]["stakeibc"]["ibc_receiver"], contract_addr.to_string());
+        assert_eq!(memo["ibc_callback"], contract_addr.to_string());
+
+        // The deposit is in flight and tagged with the packet, but not yet counted as staked.
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, DepositStatus::InFlight);
+        assert_eq!(records[0].ibc_channel.as_deref(), Some(CHANNEL));
+        assert_eq!(records[0].ibc_sequence, Some(1));
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::zero());
+
+        let escrowed = app.wrap().query_balance(ESCROW, DENOM).unwrap();
+        assert_eq!(escrowed.amount, Uint128::new(100));
+    }
+
+    #[test]
+    fn test_protocol_fee_goes_to_treasury() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+
+        let execute = |app: &mut IbcApp, sender: &str, msg: &ExecuteMsg| {
+            app.execute_contract(Addr::unchecked(sender), contract_addr.clone(), msg, &[])
+        };
+        let err = execute(&mut app, DAPP, &ExecuteMsg::SetProtocolFee { fee_bps: 1000 }).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+        let err = execute(&mut app, OWNER, &ExecuteMsg::SetProtocolFee { fee_bps: 10_001 }).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::InvalidFee { .. })));
+        execute(&mut app, OWNER, &ExecuteMsg::SetProtocolFee { fee_bps: 1000 }).unwrap();
+
+        // The contract's own fee takes precedence over the protocol fee.
+        execute(
+            &mut app,
+            OWNER,
+            &ExecuteMsg::SetContractFee {
+                contract_address: DAPP.to_string(),
+                fee_bps: Some(2000),
+            },
+        )
+        .unwrap();
+
+        run_cron_until_transfer(&mut app, &contract_addr);
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records[0].amount, Uint128::new(80));
+        assert_eq!(ibc.sent.borrow()[0].token.as_ref().unwrap().amount, "80");
+        let treasury: TreasuryResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetTreasury {})
+            .unwrap();
+        assert_eq!(treasury.coins, coins(20, DENOM));
+
+        let withdraw = |amount: u128, denom: &str| ExecuteMsg::WithdrawTreasury {
+            amount: Uint128::new(amount),
+            denom: denom.to_string(),
+            recipient: "wasm1treasuryxyz".to_string(),
+        };
+        execute(&mut app, OWNER, &withdraw(21, DENOM)).unwrap_err();
+        execute(&mut app, OWNER, &withdraw(20, STRIDE_LST)).unwrap_err();
+        execute(&mut app, OWNER, &withdraw(20, DENOM)).unwrap();
+        let withdrawn = app.wrap().query_balance("wasm1treasuryxyz", DENOM).unwrap();
+        assert_eq!(withdrawn.amount, Uint128::new(20));
+    }
+
+    #[test]
+    fn test_receipt_token_minted_and_redeemed() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        let receipt = format!("factory/{}/receipt", contract_addr);
+
+        let create = ExecuteMsg::CreateReceiptToken {
+            subdenom: "receipt".to_string(),
+        };
+        let err = app
+            .execute_contract(Addr::unchecked(DAPP), contract_addr.clone(), &create, &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &create, &[])
+            .unwrap();
+        let err = app
+            .execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &create, &[])
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidReceiptToken { .. })
+        ));
+        let token: ReceiptTokenResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetReceiptToken {})
+            .unwrap();
+        assert_eq!(token.denom.as_deref(), Some(receipt.as_str()));
+
+        // The dApp receives receipt tokens once its deposit completes, instead of claimable stARCH.
+        run_cron_until_transfer(&mut app, &contract_addr);
+        assert_eq!(app.wrap().query_balance(DAPP, &receipt).unwrap().amount, Uint128::zero());
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+        assert_eq!(app.wrap().query_balance(DAPP, &receipt).unwrap().amount, Uint128::new(100));
+        // Stride mints the stARCH of the deposit to the contract.
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(100, STRIDE_LST))
+            .unwrap();
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::DistributeLiquidity {}, &[])
+            .unwrap();
+        assert!(claimable(&app, &contract_addr, DAPP).is_empty());
+
+        // Redeeming burns the tokens and queues the stARCH their shares are worth at the exchange
+        // rate: 50 shares are worth 62 ARCH, or 49 stARCH at 1.25.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: "stride".to_string(),
+                redemption_rate: Decimal::percent(125),
+            },
+            &[],
+        )
+        .unwrap();
+        let err = app
+            .execute_contract(
+                Addr::unchecked(DAPP),
+                contract_addr.clone(),
+                &ExecuteMsg::RedeemReceiptTokens {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::InvalidFunds {})));
+        app.execute_contract(
+            Addr::unchecked(DAPP),
+            contract_addr.clone(),
+            &ExecuteMsg::RedeemReceiptTokens {},
+            &coins(50, &receipt),
+        )
+        .unwrap();
+        assert_eq!(app.wrap().query_balance(DAPP, &receipt).unwrap().amount, Uint128::new(50));
+        assert_eq!(app.wrap().query_balance(&contract_addr, &receipt).unwrap().amount, Uint128::zero());
+        assert_eq!(redemption_ledger(&app, &contract_addr), (49, 0, 0));
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::new(100));
+
+        // The deposit's shares back the tokens rather than the dApp, and the queued stARCH left
+        // the pool with the burned tokens, so the batch that redeems them burns nothing more.
+        assert_eq!(shares(&app, &contract_addr, DAPP), Uint128::zero());
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::DistributeRedeemTokens {}, &[])
+            .unwrap();
+        assert_eq!(redemption_ledger(&app, &contract_addr), (0, 49, 0));
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.total_shares, Uint128::new(50));
+
+        // The owner cannot redeem the tokenised position on the dApp's behalf.
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::SetRedeemTokens {
+                    amount: Uint128::new(10),
+                    contract_address: DAPP.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InsufficientShares { .. })
+        ));
+
+        // Any holder can redeem the tokens it was sent: 10 tokens are worth 12 ARCH, or 9 stARCH.
+        let holder = "wasm1holderxyz";
+        app.send_tokens(Addr::unchecked(DAPP), Addr::unchecked(holder), &coins(10, &receipt))
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(holder),
+            contract_addr.clone(),
+            &ExecuteMsg::RedeemReceiptTokens {},
+            &coins(10, &receipt),
+        )
+        .unwrap();
+        assert_eq!(app.wrap().query_balance(holder, &receipt).unwrap().amount, Uint128::zero());
+        let ledger: RedemptionLedgerResponse = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::GetRedemptionLedger {
+                    contract: holder.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(ledger.pending, Uint128::new(9));
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.total_shares, Uint128::new(40));
+    }
+
+    fn shares(app: &IbcApp, contract_addr: &Addr, contract: &str) -> Uint128 {
+        let response: SharesResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetShares { contract: contract.to_string() })
+            .unwrap();
+        response.shares
+    }
+
+    #[test]
+    fn test_successful_ack_completes_deposit_records() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+        run_cron_until_transfer(&mut app, &contract_addr);
+        let stake = |app: &IbcApp| -> TotalLiquidStakeResponse {
+            app.wrap()
+                .query_wasm_smart(&contract_addr, &QueryMsg::GetTotalLiquidStakeQuery {})
+                .unwrap()
+        };
+        let in_flight = stake(&app);
+        assert_eq!((in_flight.total, in_flight.pending, in_flight.completed), (Uint128::zero(), Uint128::new(100), Uint128::zero()));
+
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records[0].status, DepositStatus::Completed);
+        let completed = stake(&app);
+        assert_eq!((completed.total, completed.pending, completed.completed), (Uint128::new(100), Uint128::zero(), Uint128::new(100)));
+        assert_eq!(completed.as_of_height, app.block_info().height);
+
+        // A later cron run has nothing pending and sends no new transfer.
+        app.update_block(|b| b.time = b.time.plus_seconds(4));
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::CronJob {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::new(100));
+    }
+
+    #[test]
+    fn test_error_ack_and_timeout_fail_deposit_records() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+        run_cron_until_transfer(&mut app, &contract_addr);
+
+        // An ack for an unrelated packet leaves the record untouched.
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                channel: CHANNEL.to_string(),
+                sequence: 42,
+            }),
+        )
+        .unwrap();
+        assert_eq!(deposit_records(&app, &contract_addr)[0].status, DepositStatus::InFlight);
+
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+            }),
+        )
+        .unwrap();
+
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records[0].status, DepositStatus::Failed);
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::zero());
+    }
+
+    #[test]
+    fn test_add_host_zone_validation() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+
+        let quicksilver = || {
+            add_host_zone_msg(
+                "quicksilver",
+                LiquidStakeProtocol::Quicksilver,
+                QUICKSILVER_CHANNEL,
+                QUICKSILVER_RECEIVER,
+                RedemptionRateSource::Reported,
+            )
+        };
+
+        let err = app
+            .execute_contract(Addr::unchecked("wasm1notownerxyz"), contract_addr.clone(), &quicksilver(), &[])
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+
+        let mut msg = quicksilver();
+        if let ExecuteMsg::AddHostZone { timeout_seconds, .. } = &mut msg {
+            *timeout_seconds = 0;
+        }
+        let err = app
+            .execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &msg, &[])
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidHostZone { .. })
+        ));
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &add_host_zone_msg(
+                    "stride",
+                    LiquidStakeProtocol::Stride,
+                    CHANNEL,
+                    STRIDE_RECEIVER,
+                    RedemptionRateSource::Reported,
+                ),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::HostZoneAlreadyExists { .. })
+        ));
+
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &quicksilver(), &[])
+            .unwrap();
+        let zones: Vec<HostZoneResponse> = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetHostZones {})
+            .unwrap();
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].zone.zone_id, "quicksilver");
+        assert!(!zones[0].is_default);
+        assert!(zones[1].is_default);
+        assert_eq!(zones[1].zone.timeout_seconds, 600);
+        assert!(zones[1].zone.enabled);
+    }
+
+    #[test]
+    fn test_failed_transfer_is_kept_and_can_be_retried() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        run_cron_until_transfer(&mut app, &contract_addr);
+
+        let transfers = in_flight_transfers(&app, &contract_addr);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].sequence, 1);
+        assert_eq!(transfers[0].status, TransferStatus::InFlight);
+        assert_eq!(transfers[0].amount, Uint128::new(100));
+        assert_eq!(transfers[0].deposits.len(), 1);
+
+        timeout(&mut app, &contract_addr, 1);
+
+        // The failed amount stays in CONTRACT_STAKES and the transfer is kept for a retry.
+        let transfers = in_flight_transfers(&app, &contract_addr);
+        assert_eq!(transfers[0].status, TransferStatus::Failed);
+        assert_eq!(transfers[0].failure_reason.as_deref(), Some("timeout"));
+        let stake: ContractStakeResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetContractStake { contract: DAPP.to_string() })
+            .unwrap();
+        assert_eq!(stake.stake, Uint128::new(100));
+
+        // Only the owner can retry.
+        let err = app
+            .execute_contract(
+                Addr::unchecked("wasm1notownerxyz"),
+                contract_addr.clone(),
+                &ExecuteMsg::RetryFailedTransfers {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+
+        // A transfer to a disabled zone is skipped and stays failed.
+        let set_enabled = |app: &mut IbcApp, enabled: bool| {
+            let zone_id = "stride".to_string();
+            let msg = if enabled {
+                ExecuteMsg::EnableHostZone { zone_id }
+            } else {
+                ExecuteMsg::DisableHostZone { zone_id }
+            };
+            app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &msg, &[])
+                .unwrap();
+        };
+        set_enabled(&mut app, false);
+        let res = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::RetryFailedTransfers {},
+                &[],
+            )
+            .unwrap();
+        assert!(res.events.iter().any(|e| e.ty == "wasm-liquid_stake_transfer_retry_skipped"));
+        assert_eq!(ibc.sent.borrow().len(), 1);
+        assert_eq!(in_flight_transfers(&app, &contract_addr)[0].status, TransferStatus::Failed);
+        set_enabled(&mut app, true);
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::RetryFailedTransfers {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(ibc.sent.borrow().len(), 2);
+
+        let transfers = in_flight_transfers(&app, &contract_addr);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].sequence, 2);
+        assert_eq!(transfers[0].status, TransferStatus::InFlight);
+        assert_eq!(transfers[0].attempts, 2);
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records[0].status, DepositStatus::InFlight);
+        assert_eq!(records[0].ibc_sequence, Some(2));
+
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 2,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+
+        assert!(in_flight_transfers(&app, &contract_addr).is_empty());
+        assert_eq!(deposit_records(&app, &contract_addr)[0].status, DepositStatus::Completed);
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::new(100));
+
+        // Nothing left to retry.
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::RetryFailedTransfers {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::NoFailedTransfers {})));
+    }
+
+    #[test]
+    fn test_failed_transfer_refunded_to_pending_rewards() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+
+        let mut msg = update_host_zone_msg("stride");
+        if let ExecuteMsg::UpdateHostZone { refund_target, .. } = &mut msg {
+            *refund_target = Some(RefundTarget::PendingRewards);
+        }
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &msg, &[])
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::SetProtocolFee { fee_bps: 1000 },
+            &[],
+        )
+        .unwrap();
+        run_cron_until_transfer(&mut app, &contract_addr);
+        assert_eq!(deposit_records(&app, &contract_addr)[0].protocol_fee, Uint128::new(10));
+
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJlcnJvciI6ImZhaWxlZCJ9".to_string(),
+                success: false,
+            }),
+        )
+        .unwrap();
+
+        assert!(in_flight_transfers(&app, &contract_addr).is_empty());
+        assert_eq!(deposit_records(&app, &contract_addr)[0].status, DepositStatus::Refunded);
+
+        // The fee comes back from the treasury with the deposit, so it is only charged once the
+        // rewards are deposited again.
+        let treasury: TreasuryResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetTreasury {})
+            .unwrap();
+        assert_eq!(treasury.coins, coins(0, DENOM));
+
+        let stake: ContractStakeResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetContractStake { contract: DAPP.to_string() })
+            .unwrap();
+        assert_eq!(stake.stake, Uint128::zero());
+        let reward: RewardResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetReward { rewards_address: DAPP.to_string() })
+            .unwrap();
+        assert_eq!(reward.amount, Uint128::new(100));
+    }
+
+    #[test]
+    fn test_deposits_follow_default_host_zone() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &add_host_zone_msg(
+                "quicksilver",
+                LiquidStakeProtocol::Quicksilver,
+                QUICKSILVER_CHANNEL,
+                QUICKSILVER_RECEIVER,
+                RedemptionRateSource::Reported,
+            ),
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::SetDefaultHostZone {
+                zone_id: "quicksilver".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::DisableHostZone {
+                zone_id: "quicksilver".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // The default zone is disabled: deposits stay pending and nothing is sent.
+        run_cron_until_transfer(&mut app, &contract_addr);
+        assert!(ibc.sent.borrow().is_empty());
+        assert_eq!(deposit_records(&app, &contract_addr)[0].status, DepositStatus::Pending);
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::EnableHostZone {
+                zone_id: "quicksilver".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.update_block(|b| b.time = b.time.plus_seconds(4));
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::CronJob {}, &[])
+            .unwrap();
+
+        // Quicksilver liquid stakes on receipt, so the memo only asks for the lifecycle callback.
+        let sent = ibc.sent.borrow().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].source_channel, QUICKSILVER_CHANNEL);
+        assert_eq!(sent[0].receiver, QUICKSILVER_RECEIVER);
+        let memo: serde_json::Value = serde_json::from_str(&sent[0].memo).unwrap();
+        assert!(memo.get("autopilot").is_none());
+        assert_eq!(memo["ibc_callback"], contract_addr.to_string());
+
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: QUICKSILVER_CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(host_zone(&app, &contract_addr, "quicksilver").liquid_stake, Uint128::new(100));
+        assert_eq!(host_zone(&app, &contract_addr, "stride").liquid_stake, Uint128::zero());
+        assert_eq!(total_liquid_stake(&app, &contract_addr), Uint128::new(100));
+    }
+
+    fn oracle_instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn oracle_execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn oracle_query(_: Deps, _: Env, msg: RedemptionRateOracleQueryMsg) -> StdResult<Binary> {
+        let RedemptionRateOracleQueryMsg::RedemptionRate { denom } = msg;
+        assert_eq!(denom, "ibc/stpstake");
+        to_json_binary(&RedemptionRateOracleResponse {
+            redemption_rate: Decimal::percent(125),
+        })
+    }
+
+    #[test]
+    fn test_redemption_rates_per_host_zone() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+
+        let oracle_code = app.store_code(Box::new(ContractWrapper::new(
+            oracle_execute,
+            oracle_instantiate,
+            oracle_query,
+        )));
+        let oracle = app
+            .instantiate_contract(oracle_code, Addr::unchecked(OWNER), &Empty {}, &[], "Oracle", None)
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &add_host_zone_msg(
+                "pstake",
+                LiquidStakeProtocol::Pstake,
+                "channel-2",
+                "persistence1depositxyz",
+                RedemptionRateSource::Oracle {
+                    contract_address: oracle,
+                },
+            ),
+            &[],
+        )
+        .unwrap();
+
+        // Stride's rate is reported by the owner; pSTAKE's comes from its oracle.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: "stride".to_string(),
+                redemption_rate: Decimal::percent(110),
+            },
+            &[],
+        )
+        .unwrap();
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::ReportRedemptionRate {
+                    zone_id: "pstake".to_string(),
+                    redemption_rate: Decimal::percent(110),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::RedemptionRateNotReported { .. })
+        ));
+        assert!(host_zone(&app, &contract_addr, "pstake").redemption_rate.is_none());
+
+        app.update_block(|b| b.time = b.time.plus_seconds(101));
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::CronJob {}, &[])
+            .unwrap();
+
+        let pstake = host_zone(&app, &contract_addr, "pstake");
+        assert_eq!(pstake.redemption_rate.unwrap().redemption_rate, Decimal::percent(125));
+        let stride = host_zone(&app, &contract_addr, "stride");
+        assert_eq!(stride.redemption_rate.unwrap().redemption_rate, Decimal::percent(110));
+        assert_eq!(stride.zone.unbonding_period_seconds, 14 * 24 * 3600);
+    }
+
+    #[test]
+    fn test_contract_strategy_splits_deposits_across_zones() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &add_host_zone_msg(
+                "quicksilver",
+                LiquidStakeProtocol::Quicksilver,
+                QUICKSILVER_CHANNEL,
+                QUICKSILVER_RECEIVER,
+                RedemptionRateSource::Reported,
+            ),
+            &[],
+        )
+        .unwrap();
+
+        let strategy = |allocations: Vec<(&str, u64)>| ExecuteMsg::SetContractStrategy {
+            contract_address: DAPP.to_string(),
+            allocations: allocations
+                .into_iter()
+                .map(|(zone_id, weight)| ZoneAllocation {
+                    zone_id: zone_id.to_string(),
+                    weight,
+                })
+                .collect(),
+        };
+
+        let err = app
+            .execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &strategy(vec![("milkyway", 1)]), &[])
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::HostZoneNotFound { .. })
+        ));
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &strategy(vec![("stride", 1), ("quicksilver", 0)]),
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidStrategy { .. })
+        ));
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &strategy(vec![("stride", 1), ("quicksilver", 3)]),
+            &[],
+        )
+        .unwrap();
+        let meta: ContractMetadata = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetContractMetadata { contract: DAPP.to_string() })
+            .unwrap();
+        assert_eq!(meta.strategy.len(), 2);
+
+        run_cron_until_transfer(&mut app, &contract_addr);
+
+        // One deposit record and one transfer per zone, split 1:3.
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].zone_id.as_deref(), Some("stride"));
+        assert_eq!(records[0].amount, Uint128::new(25));
+        assert_eq!(records[1].zone_id.as_deref(), Some("quicksilver"));
+        assert_eq!(records[1].amount, Uint128::new(75));
+
+        let sent = ibc.sent.borrow().clone();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].source_channel, QUICKSILVER_CHANNEL);
+        assert_eq!(sent[0].token.as_ref().unwrap().amount, "75");
+        assert_eq!(sent[1].source_channel, CHANNEL);
+        assert_eq!(sent[1].token.as_ref().unwrap().amount, "25");
+
+        for (channel, sequence) in [(QUICKSILVER_CHANNEL, 1), (CHANNEL, 2)] {
+            app.wasm_sudo(
+                contract_addr.clone(),
+                &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                    channel: channel.to_string(),
+                    sequence,
+                    ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                    success: true,
+                }),
+            )
+            .unwrap();
+        }
+        assert_eq!(host_zone(&app, &contract_addr, "stride").liquid_stake, Uint128::new(25));
+        assert_eq!(host_zone(&app, &contract_addr, "quicksilver").liquid_stake, Uint128::new(75));
+
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::DistributeLiquidity {}, &[])
+            .unwrap();
+        let ratios: Vec<StakeRatioResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::GetZoneStakeRatios {
+                    zone_id: "quicksilver".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            ratios,
+            vec![StakeRatioResponse {
+                contract: DAPP.to_string(),
+                ratio: Decimal::one(),
+            }]
+        );
+
+        // The liquid staking tokens of each zone are paid out of the pool to the liquidity
+        // provider address, once, along with the shares they were worth. The dApp can claim
+        // them as well.
+        let mut expected = coins(75, "ibc/stquicksilver");
+        expected.extend(coins(25, "ibc/ststride"));
+        assert_eq!(claimable(&app, &contract_addr, "wasm1lpxyz"), expected);
+        assert_eq!(claimable(&app, &contract_addr, DAPP), expected);
+        assert_eq!(shares(&app, &contract_addr, DAPP), Uint128::zero());
+        app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), &ExecuteMsg::DistributeLiquidity {}, &[])
+            .unwrap();
+        assert_eq!(claimable(&app, &contract_addr, "wasm1lpxyz"), expected);
+    }
+
+    fn unbonding_batch(app: &IbcApp, contract_addr: &Addr, batch_id: u64) -> UnbondingBatch {
+        app.wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetUnbondingBatch { batch_id })
+            .unwrap()
+    }
+
+    fn claimable(app: &IbcApp, contract_addr: &Addr, address: &str) -> Vec<Coin> {
+        let response: ClaimableResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetClaimable {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        response.coins
+    }
+
+    fn redemption_ledger(app: &IbcApp, contract_addr: &Addr) -> (u128, u128, u128) {
+        let ledger: RedemptionLedgerResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetRedemptionLedger {
+                    contract: DAPP.to_string(),
+                },
+            )
+            .unwrap();
+        (ledger.pending.u128(), ledger.distributed.u128(), ledger.claimed.u128())
+    }
+
+    #[test]
+    fn test_unbonding_batch_redeems_and_pays_out() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        let report_rate = |app: &mut IbcApp, percent: u64| {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::ReportRedemptionRate {
+                    zone_id: "stride".to_string(),
+                    redemption_rate: Decimal::percent(percent),
+                },
+                &[],
+            )
+            .unwrap();
+        };
+
+        // The dApp stakes 800 ARCH at a rate of 1.0 and Stride mints the stARCH to the contract.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: DAPP.to_string(),
+                amount: Uint128::new(700),
+            },
+            &[],
+        )
+        .unwrap();
+        report_rate(&mut app, 100);
+        run_cron_until_transfer(&mut app, &contract_addr);
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(800, STRIDE_LST))
+            .unwrap();
+        report_rate(&mut app, 125);
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::EnableLiquidStakeVerification {
+                starch_denom: STRIDE_LST.to_string(),
+                tolerance_bps: 0,
+            },
+            &[],
+        )
+        .unwrap();
+        let verified_balance = |app: &IbcApp| {
+            let verification: Option<LiquidStakeVerification> = app
+                .wrap()
+                .query_wasm_smart(&contract_addr, &QueryMsg::GetLiquidStakeVerification {})
+                .unwrap();
+            verification.unwrap().last_balance
+        };
+
+        // Redemptions are limited to what the dApp's shares are worth.
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::SetRedeemTokens {
+                    amount: Uint128::new(801),
+                    contract_address: DAPP.to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InsufficientShares { .. })
+        ));
+
+        let redeem = |app: &mut IbcApp| {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::SetRedeemTokens {
+                    amount: Uint128::new(400),
+                    contract_address: DAPP.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::DistributeRedeemTokens {},
+                &[],
+            )
+            .unwrap();
+        };
+
+        // The stARCH goes to Stride with a RedeemStake memo.
+        redeem(&mut app);
+        let transfer = ibc.sent.borrow().last().cloned().unwrap();
+        assert_eq!(transfer.token.as_ref().unwrap().denom, STRIDE_LST);
+        assert_eq!(transfer.token.as_ref().unwrap().amount, "400");
+        let memo: serde_json::Value = serde_json::from_str(&transfer.memo).unwrap();
+        assert_eq!(memo["autopilot"]["stakeibc"]["action"], "RedeemStake");
+
+        let batch = unbonding_batch(&app, &contract_addr, 1);
+        assert_eq!(batch.status, UnbondingStatus::Transferring);
+        assert_eq!(batch.sequence, Some(2));
+        assert_eq!(batch.expected_arch, Uint128::new(500));
+        assert_eq!(batch.redemptions[0].arch_amount, Uint128::new(500));
+        assert_eq!(batch.redemptions[0].shares, Uint128::new(400));
+        assert_eq!(redemption_ledger(&app, &contract_addr), (0, 400, 0));
+        // The stARCH sent out is not taken for a liquid stake that was never reported.
+        assert_eq!(verified_balance(&app), Uint128::new(400));
+
+        // A timed out batch gives the amounts back as pending redeem tokens, and the transfer
+        // module refunds the escrowed stARCH.
+        timeout(&mut app, &contract_addr, 2);
+        app.send_tokens(Addr::unchecked(ESCROW), contract_addr.clone(), &coins(400, STRIDE_LST))
+            .unwrap();
+        assert_eq!(unbonding_batch(&app, &contract_addr, 1).status, UnbondingStatus::Failed);
+        assert_eq!(redemption_ledger(&app, &contract_addr), (400, 0, 0));
+        assert_eq!(verified_balance(&app), Uint128::new(800));
+
+        // The retry includes the restored 400 on top of the new 400.
+        redeem(&mut app);
+        let batch = unbonding_batch(&app, &contract_addr, 2);
+        assert_eq!(batch.lst_amount, Uint128::new(800));
+        assert_eq!(verified_balance(&app), Uint128::zero());
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 3,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+        let batch = unbonding_batch(&app, &contract_addr, 2);
+        assert_eq!(batch.status, UnbondingStatus::Unbonding);
+        assert_eq!(shares(&app, &contract_addr, DAPP), Uint128::zero());
+        let completion_time = batch.completion_time.unwrap();
+        assert_eq!(completion_time, app.block_info().time.seconds() + 14 * 24 * 3600);
+
+        let run_cron = |app: &mut IbcApp| {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::CronJob {},
+                &[],
+            )
+            .unwrap();
+        };
+
+        // Nothing is paid before the unbonding period has passed. Meanwhile a new 200 ARCH deposit
+        // times out and its ARCH is refunded to the contract.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: DAPP.to_string(),
+                amount: Uint128::new(200),
+            },
+            &[],
+        )
+        .unwrap();
+        app.update_block(|b| b.time = b.time.plus_seconds(3600));
+        run_cron(&mut app);
+        assert_eq!(unbonding_batch(&app, &contract_addr, 2).status, UnbondingStatus::Unbonding);
+        timeout(&mut app, &contract_addr, 4);
+        app.send_tokens(Addr::unchecked(ESCROW), contract_addr.clone(), &coins(200, DENOM))
+            .unwrap();
+        assert_eq!(deposit_records(&app, &contract_addr)[1].status, DepositStatus::Failed);
+
+        // Afterwards the batch waits until the redeemed ARCH is back from Stride.
+        app.update_block(|b| b.time = b.time.plus_seconds(14 * 24 * 3600));
+        let balance = app.wrap().query_balance(&contract_addr, DENOM).unwrap().amount;
+        assert!(balance < Uint128::new(1000));
+        run_cron(&mut app);
+        assert_eq!(unbonding_batch(&app, &contract_addr, 2).status, UnbondingStatus::Unbonding);
+
+        // The ARCH of the failed deposit is kept for its retry, so 800 more are not enough.
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(800, DENOM))
+            .unwrap();
+        assert!(app.wrap().query_balance(&contract_addr, DENOM).unwrap().amount >= Uint128::new(1000));
+        run_cron(&mut app);
+        assert_eq!(unbonding_batch(&app, &contract_addr, 2).status, UnbondingStatus::Unbonding);
+
+        // Once it all arrives it is credited to the contract's redemption address.
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(200, DENOM))
+            .unwrap();
+        run_cron(&mut app);
+        assert_eq!(unbonding_batch(&app, &contract_addr, 2).status, UnbondingStatus::Completed);
+        assert_eq!(claimable(&app, &contract_addr, REDEMPTION_ADDRESS), coins(1000, DENOM));
+
+        let claim = |app: &mut IbcApp, sender: &str| {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                contract_addr.clone(),
+                &ExecuteMsg::Claim {
+                    denom: DENOM.to_string(),
+                },
+                &[],
+            )
+        };
+        // Only the redemption address the payout was credited to, or the dApp it was credited
+        // for, can claim it, and only once.
+        let err = claim(&mut app, "wasm1strangerxyz").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NothingToClaim { .. })
+        ));
+        assert_eq!(claimable(&app, &contract_addr, DAPP), coins(1000, DENOM));
+        claim(&mut app, REDEMPTION_ADDRESS).unwrap();
+        let paid = app.wrap().query_balance(REDEMPTION_ADDRESS, DENOM).unwrap();
+        assert_eq!(paid.amount, Uint128::new(1000));
+        assert!(claimable(&app, &contract_addr, REDEMPTION_ADDRESS).is_empty());
+        let err = claim(&mut app, DAPP).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NothingToClaim { .. })
+        ));
+        assert_eq!(redemption_ledger(&app, &contract_addr), (0, 0, 800));
+    }
+
+    #[test]
+    fn test_redemptions_use_stride_when_default_zone_cannot_redeem() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        let execute = |app: &mut IbcApp, msg: &ExecuteMsg| {
+            app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), msg, &[])
+        };
+
+        // 100 ARCH are liquid staked on Stride, then deposits move to Quicksilver.
+        run_cron_until_transfer(&mut app, &contract_addr);
+        app.wasm_sudo(
+            contract_addr.clone(),
+            &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: CHANNEL.to_string(),
+                sequence: 1,
+                ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                success: true,
+            }),
+        )
+        .unwrap();
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(100, STRIDE_LST))
+            .unwrap();
+        execute(
+            &mut app,
+            &add_host_zone_msg(
+                "quicksilver",
+                LiquidStakeProtocol::Quicksilver,
+                QUICKSILVER_CHANNEL,
+                QUICKSILVER_RECEIVER,
+                RedemptionRateSource::Reported,
+            ),
+        )
+        .unwrap();
+        execute(
+            &mut app,
+            &ExecuteMsg::SetDefaultHostZone {
+                zone_id: "quicksilver".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Quicksilver has no autopilot redemption, so the stARCH held on Stride are redeemed.
+        execute(
+            &mut app,
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: "stride".to_string(),
+                redemption_rate: Decimal::one(),
+            },
+        )
+        .unwrap();
+        execute(
+            &mut app,
+            &ExecuteMsg::SetRedeemTokens {
+                amount: Uint128::new(100),
+                contract_address: DAPP.to_string(),
+            },
+        )
+        .unwrap();
+        execute(&mut app, &ExecuteMsg::DistributeRedeemTokens {}).unwrap();
+        let batch = unbonding_batch(&app, &contract_addr, 1);
+        assert_eq!(batch.zone_id, "stride");
+        assert_eq!(batch.lst_denom, STRIDE_LST);
+        assert_eq!(batch.lst_amount, Uint128::new(100));
+
+        // Without an enabled Stride zone nothing can be redeemed.
+        execute(
+            &mut app,
+            &ExecuteMsg::DisableHostZone {
+                zone_id: "stride".to_string(),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            &mut app,
+            &ExecuteMsg::SetRedeemTokens {
+                amount: Uint128::new(1),
+                contract_address: DAPP.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidHostZone { .. })
+        ));
+    }
+
+    #[test]
+    fn test_performance_fee_on_redemption_gain() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc.clone());
+        let contract_addr = setup(&mut app);
+        let execute = |app: &mut IbcApp, msg: &ExecuteMsg| {
+            app.execute_contract(Addr::unchecked(OWNER), contract_addr.clone(), msg, &[])
+                .unwrap();
+        };
+        let ack = |app: &mut IbcApp, sequence: u64| {
+            app.wasm_sudo(
+                contract_addr.clone(),
+                &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                    channel: CHANNEL.to_string(),
+                    sequence,
+                    ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                    success: true,
+                }),
+            )
+            .unwrap();
+        };
+        let report_rate = |app: &mut IbcApp, percent: u64| {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::ReportRedemptionRate {
+                    zone_id: "stride".to_string(),
+                    redemption_rate: Decimal::percent(percent),
+                },
+                &[],
+            )
+            .unwrap();
+        };
+        execute(&mut app, &ExecuteMsg::SetPerformanceFee { fee_bps: 1000 });
+
+        // 100 ARCH enter at a rate of 1.0 and 100 stARCH leave at 1.25.
+        report_rate(&mut app, 100);
+        run_cron_until_transfer(&mut app, &contract_addr);
+        ack(&mut app, 1);
+        let records = deposit_records(&app, &contract_addr);
+        assert_eq!(records[0].entry_redemption_rate, Some(Decimal::one()));
+
+        report_rate(&mut app, 125);
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(100, STRIDE_LST))
+            .unwrap();
+        execute(
+            &mut app,
+            &ExecuteMsg::SetRedeemTokens {
+                amount: Uint128::new(100),
+                contract_address: DAPP.to_string(),
+            },
+        );
+        execute(&mut app, &ExecuteMsg::DistributeRedeemTokens {});
+        ack(&mut app, 2);
+
+        // The 25 ARCH gain pays a 10% fee, rounded down.
+        let batch = unbonding_batch(&app, &contract_addr, 1);
+        assert_eq!(batch.redemptions[0].arch_amount, Uint128::new(125));
+        assert_eq!(batch.redemptions[0].cost_basis.arch_cost, Uint128::new(100));
+        assert_eq!(batch.redemptions[0].performance_fee, Uint128::new(2));
+
+        app.update_block(|b| b.time = b.time.plus_seconds(14 * 24 * 3600));
+        app.send_tokens(Addr::unchecked(OWNER), contract_addr.clone(), &coins(1000, DENOM))
+            .unwrap();
+        execute(&mut app, &ExecuteMsg::CronJob {});
+        assert_eq!(unbonding_batch(&app, &contract_addr, 1).status, UnbondingStatus::Completed);
+        assert_eq!(claimable(&app, &contract_addr, REDEMPTION_ADDRESS), coins(123, DENOM));
+
+        // The dApp claims the payout credited to its redemption address itself.
+        app.execute_contract(
+            Addr::unchecked(DAPP),
+            contract_addr.clone(),
+            &ExecuteMsg::Claim {
+                denom: DENOM.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(app.wrap().query_balance(DAPP, DENOM).unwrap().amount, Uint128::new(123));
+        assert!(claimable(&app, &contract_addr, REDEMPTION_ADDRESS).is_empty());
+
+        let treasury: TreasuryResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetTreasury {})
+            .unwrap();
+        assert_eq!(treasury.coins, coins(2, DENOM));
+        let summaries: RewardSummariesResponse = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetRewardSummaries {})
+            .unwrap();
+        assert_eq!(summaries.contract_summaries[0].performance_fees, Uint128::new(2));
+        assert_eq!(summaries.total_performance_fees, Uint128::new(2));
+    }
+
+    #[test]
+    fn test_shares_minted_at_exchange_rate() {
+        let ibc = MockIbcTransfer::default();
+        let mut app = mock_app(ibc);
+        let contract_addr = setup(&mut app);
+        let ack = |app: &mut IbcApp, sequence: u64| {
+            app.wasm_sudo(
+                contract_addr.clone(),
+                &SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                    channel: CHANNEL.to_string(),
+                    sequence,
+                    ack: "eyJyZXN1bHQiOiJBUT09In0=".to_string(),
+                    success: true,
+                }),
+            )
+            .unwrap();
+        };
+        let exchange_rate = |app: &IbcApp| -> ExchangeRateResponse {
+            app.wrap()
+                .query_wasm_smart(&contract_addr, &QueryMsg::GetExchangeRate {})
+                .unwrap()
+        };
+        let shares = |app: &IbcApp| -> SharesResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    &contract_addr,
+                    &QueryMsg::GetShares {
+                        contract: DAPP.to_string(),
+                    },
+                )
+                .unwrap()
+        };
+        assert_eq!(exchange_rate(&app).exchange_rate, Decimal::one());
+
+        // The first 100 ARCH buy 100 shares of 100 stARCH.
+        run_cron_until_transfer(&mut app, &contract_addr);
+        ack(&mut app, 1);
+        assert_eq!(shares(&app).shares, Uint128::new(100));
+        assert_eq!(records_shares(&app, &contract_addr), vec![Uint128::new(100)]);
+
+        // stARCH growth raises the exchange rate and what the shares are worth.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: "stride".to_string(),
+                redemption_rate: Decimal::percent(125),
+            },
+            &[],
+        )
+        .unwrap();
+        let rate = exchange_rate(&app);
+        assert_eq!(rate.exchange_rate, Decimal::percent(125));
+        assert_eq!(rate.total_shares, Uint128::new(100));
+        assert_eq!(rate.pool_value, Uint128::new(125));
+        assert_eq!(shares(&app).value, Uint128::new(125));
+
+        // The next 100 ARCH buy only 80 shares at the higher rate.
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: DAPP.to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+        run_cron_until_transfer(&mut app, &contract_addr);
+        ack(&mut app, 2);
+        assert_eq!(records_shares(&app, &contract_addr), vec![Uint128::new(100), Uint128::new(80)]);
+        let rate = exchange_rate(&app);
+        assert_eq!(rate.exchange_rate, Decimal::percent(125));
+        assert_eq!(rate.total_shares, Uint128::new(180));
+        assert_eq!(rate.pool_value, Uint128::new(225));
+        assert_eq!(shares(&app).value, Uint128::new(225));
+    }
+
+    fn records_shares(app: &IbcApp, contract_addr: &Addr) -> Vec<Uint128> {
+        deposit_records(app, contract_addr)
+            .into_iter()
+            .filter(|record| record.status == DepositStatus::Completed)
+            .map(|record| record.shares)
+            .collect()
+    }
+}
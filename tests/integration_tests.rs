@@ -1,23 +1,37 @@
 #[cfg(test)]
 mod integration_tests {
+    use std::collections::BTreeMap;
+
     // Import standard CosmWasm types
     use cosmwasm_std::{
         testing::{mock_dependencies, mock_env, mock_info},
-        Addr, Uint128, Empty, Decimal, StdError, from_binary, to_binary
+        Addr, Uint128, Empty, Decimal, StdError, from_json, coins, CosmosMsg
     };
-    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cw_multi_test::{Contract, ContractWrapper, Executor};
 
-    use cosmwasm_liquid_staking::contract::{execute, instantiate, query, migrate};
+    use cosmwasm_liquid_staking::contract::{execute, instantiate, query, migrate, reply, sudo, COMPLETED_STAKES, CONTRACT_NAME, CONTRACT_VERSION};
+    use cosmwasm_liquid_staking::testing::{
+        mock_host_zone_contract, MockApp, MockHostZoneExecuteMsg, MockHostZoneInstantiateMsg,
+        MockHostZoneQueryMsg, MockIbcBridge, PendingRedemption,
+    };
 
     use cosmwasm_liquid_staking::msg::{
-        InstantiateMsg, ExecuteMsg, QueryMsg, RewardUpdate, Distribution, RewardSummariesResponse
+        InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg, RewardUpdate, Distribution, RewardSummariesResponse,
+        EpochStArchResponse, LiquidStakeReconciliationResponse, CronSimulationResponse,
+        TaskScheduleResponse, RedemptionLedgerResponse, StakeRatioResponse, ContractStakeResponse,
+        ContractsResponse, OperatorsResponse, RedeemTokensResponse, RedemptionRatioResponse, RewardResponse,
+        ClaimableResponse, ExchangeRateResponse, TotalLiquidStakeResponse,
     };
 
     use cosmwasm_liquid_staking::error::ContractError;
     use cosmwasm_liquid_staking::state::{
-        CONFIG, CONTRACT_REWARDS, CONTRACT_METADATA, REDEEM_TOKENS, TOTAL_LIQUID_STAKE,
-        REDEMPTION_RECORDS, STAKE_RATIOS, REDEEM_TOKEN_RATIOS,
-        Config, ContractMetadata, DepositRecord,
+        CONFIG, CONTRACT_REWARDS, TOTAL_LIQUID_STAKE, REDEMPTION_RECORDS, REDEEM_TOKEN_RATIOS,
+        REDEMPTION_LEDGER, REDEMPTION_TOKEN_RATIOS,
+        UnbondingBatch, UnbondingStatus,
+        Config, ContractMetadata, DepositRecord, DepositStatus, LegacyDepositRecord, LEGACY_DEPOSIT_RECORDS,
+        CONTRACT_METADATA, deposit_records, LiquidStakeReceipt, LiquidStakeVerification,
+        CronTask, Schedule, LAST_PROCESSING_TIMES, LiquidStakeProtocol, RedemptionRateSource,
+        LiquidStakeRoute, RefundTarget, HOST_ZONES, DEFAULT_HOST_ZONE, POOL_ARCH, SHARES, TOTAL_SHARES,
     };
 
 
@@ -30,12 +44,12 @@ mod integration_tests {
         Box::new(contract)
     }
 
-    fn mock_app() -> App {
-        App::default()
+    fn mock_app() -> MockApp {
+        MockIbcBridge::default().app(&[])
     }
 
     fn init_contract(
-        router: &mut App,
+        router: &mut MockApp,
         owner: &str,
         init_msg: InstantiateMsg
     ) -> (Addr, u64) {
@@ -69,7 +83,7 @@ mod integration_tests {
 
         let config: Config = app.wrap().query_wasm_smart(&contract_addr, &QueryMsg::GetConfig {}).unwrap();
         assert_eq!(config.owner, Addr::unchecked(owner));
-        assert_eq!(config.liquid_staking_interval, init_msg.liquid_staking_interval);
+        assert_eq!(config.liquid_staking_interval, Schedule::Seconds(init_msg.liquid_staking_interval));
     }
 
     #[test]
@@ -164,11 +178,11 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let stake: Uint128 = app.wrap().query_wasm_smart(
+        let stake: ContractStakeResponse = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetContractStake { contract: staker.to_string() },
         ).unwrap();
-        assert_eq!(stake, Uint128::new(500));
+        assert_eq!(stake.stake, Uint128::new(500));
     }
 
     #[test]
@@ -223,11 +237,11 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let reward: Uint128 = app.wrap().query_wasm_smart(
+        let reward: RewardResponse = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetReward { rewards_address: dapp_contract.to_string() },
         ).unwrap();
-        assert_eq!(reward, Uint128::new(300));
+        assert_eq!(reward.amount, Uint128::new(300));
 
         // Bulk update
         let updates = vec![
@@ -248,11 +262,11 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let reward: Uint128 = app.wrap().query_wasm_smart(
+        let reward: RewardResponse = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetReward { rewards_address: dapp_contract.to_string() },
         ).unwrap();
-        assert_eq!(reward, Uint128::new(1000));
+        assert_eq!(reward.amount, Uint128::new(1000));
     }
 
     #[test]
@@ -316,7 +330,113 @@ mod integration_tests {
 
         assert_eq!(records.len(), 1);
         // Ensure it's still pending (not completed)
-        assert_eq!(records[0].status, "pending");
+        assert_eq!(records[0].status, DepositStatus::Pending);
+    }
+
+    #[test]
+    fn test_simulate_cron_job_matches_execution() {
+        let mut app = mock_app();
+        let owner = "wasm1ownerxyz";
+
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 1,
+            arch_liquid_stake_interval: 3,
+            redemption_rate_query_interval: 5,
+            rewards_withdrawal_interval: 1,
+            redemption_interval_threshold: 5,
+        };
+
+        let (contract_addr, _) = init_contract(&mut app, owner, init_msg);
+
+        let dapp_contract = "wasm1dappxyz";
+        app.execute_contract(
+            Addr::unchecked(owner),
+            contract_addr.clone(),
+            &ExecuteMsg::SetContractMetadata {
+                contract_address: dapp_contract.to_string(),
+                rewards_address: "wasm1rewardsxyz".to_string(),
+                liquidity_provider_address: "wasm1lpxyz".to_string(),
+                redemption_address: "wasm1redemptionxyz".to_string(),
+                minimum_reward_amount: Uint128::new(50),
+                maximum_reward_amount: Uint128::new(1000),
+            },
+            &[]
+        ).unwrap();
+        app.execute_contract(
+            Addr::unchecked(owner),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: dapp_contract.to_string(),
+                amount: Uint128::new(100),
+            },
+            &[]
+        ).unwrap();
+
+        // At the current block time no interval has elapsed yet.
+        let simulation: CronSimulationResponse = app.wrap().query_wasm_smart(
+            &contract_addr,
+            &QueryMsg::SimulateCronJob { at_time: None, at_height: None, distribute_liquidity: None },
+        ).unwrap();
+        assert!(simulation.tasks.is_empty());
+        assert!(simulation.created_deposit_records.is_empty());
+
+        // Three seconds later the rewards become a deposit record, which the arch liquid stake
+        // interval then completes in the same run.
+        let at_time = app.block_info().time.seconds() + 3;
+        let simulation: CronSimulationResponse = app.wrap().query_wasm_smart(
+            &contract_addr,
+            &QueryMsg::SimulateCronJob {
+                at_time: Some(at_time),
+                at_height: None,
+                distribute_liquidity: None,
+            },
+        ).unwrap();
+        // The cron job itself does not distribute liquidity.
+        assert!(simulation.stake_ratios.is_empty());
+        let simulation: CronSimulationResponse = app.wrap().query_wasm_smart(
+            &contract_addr,
+            &QueryMsg::SimulateCronJob {
+                at_time: Some(at_time),
+                at_height: None,
+                distribute_liquidity: Some(true),
+            },
+        ).unwrap();
+        assert_eq!(simulation.at_time, at_time);
+        assert_eq!(
+            simulation.tasks,
+            vec!["liquid_staking_dapp_rewards", "arch_liquid_stake_interval", "rewards_withdrawal"]
+        );
+        assert_eq!(simulation.created_deposit_records.len(), 1);
+        assert_eq!(simulation.created_deposit_records[0].amount, Uint128::new(100));
+        assert!(simulation.dispatched_deposit_records.is_empty());
+        assert_eq!(simulation.completed_deposit_records.len(), 1);
+        assert_eq!(
+            simulation.stake_ratios,
+            vec![StakeRatioResponse { contract: dapp_contract.to_string(), ratio: Decimal::one() }]
+        );
+
+        // The simulation left no trace.
+        let records: Vec<DepositRecord> = app.wrap().query_wasm_smart(
+            &contract_addr,
+            &QueryMsg::GetDepositRecords { contract: dapp_contract.to_string() },
+        ).unwrap();
+        assert!(records.is_empty());
+
+        // Executing the cron job at that time does what the simulation said.
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(3);
+        });
+        app.execute_contract(
+            Addr::unchecked(owner),
+            contract_addr.clone(),
+            &ExecuteMsg::CronJob {},
+            &[]
+        ).unwrap();
+        let records: Vec<DepositRecord> = app.wrap().query_wasm_smart(
+            &contract_addr,
+            &QueryMsg::GetDepositRecords { contract: dapp_contract.to_string() },
+        ).unwrap();
+        assert_eq!(records, simulation.completed_deposit_records);
     }
 
     #[test]
@@ -380,7 +500,7 @@ mod integration_tests {
             &contract_addr,
             &QueryMsg::GetDepositRecords { contract: c.to_string() },
         ).unwrap();
-        assert!(records.iter().any(|r| r.status == "completed"));
+        assert!(records.iter().any(|r| r.status == DepositStatus::Completed));
 
         // Reset completed
         app.execute_contract(
@@ -394,7 +514,7 @@ mod integration_tests {
             &contract_addr,
             &QueryMsg::GetDepositRecords { contract: c.to_string() },
         ).unwrap();
-        assert!(!records_after.iter().any(|r| r.status == "completed"));
+        assert!(!records_after.iter().any(|r| r.status == DepositStatus::Completed));
     }
 
     #[test]
@@ -455,16 +575,16 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let redemption_ratios: Vec<(String, String)> = app.wrap().query_wasm_smart(
+        let redemption_ratios: Vec<RedemptionRatioResponse> = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetAllRedemptionRatios {}
         ).unwrap();
         let mut ratio_map = std::collections::HashMap::new();
-        for (addr, ratio_str) in redemption_ratios {
-            ratio_map.insert(addr, ratio_str);
+        for ratio in redemption_ratios {
+            ratio_map.insert(ratio.contract, ratio.ratio);
         }
-        assert_eq!(ratio_map.get(c1), Some(&"0.2".to_string()));
-        assert_eq!(ratio_map.get(c2), Some(&"0.8".to_string()));
+        assert_eq!(ratio_map.get(c1), Some(&Decimal::percent(20)));
+        assert_eq!(ratio_map.get(c2), Some(&Decimal::percent(80)));
 
         app.execute_contract(
             Addr::unchecked(owner),
@@ -473,7 +593,7 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let redemption_ratios_after: Vec<(String, String)> = app.wrap().query_wasm_smart(
+        let redemption_ratios_after: Vec<RedemptionRatioResponse> = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetAllRedemptionRatios {}
         ).unwrap();
@@ -578,7 +698,7 @@ mod integration_tests {
             &[]
         ).unwrap();
 
-        let ratios: Vec<(String, String)> = app.wrap().query_wasm_smart(
+        let ratios: Vec<StakeRatioResponse> = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetAllStakeRatios {}
         ).unwrap();
@@ -618,12 +738,12 @@ mod integration_tests {
             assert!(res.is_ok());
         }
 
-        let all_contracts: Vec<String> = app.wrap().query_wasm_smart(
+        let all_contracts: ContractsResponse = app.wrap().query_wasm_smart(
             &contract_addr,
             &QueryMsg::GetAllContracts {}
         ).unwrap();
-        assert!(all_contracts.contains(&c1.to_string()));
-        assert!(all_contracts.contains(&c2.to_string()));
+        assert!(all_contracts.contracts.contains(&c1.to_string()));
+        assert!(all_contracts.contracts.contains(&c2.to_string()));
     }
 
     #[test]
@@ -730,7 +850,7 @@ mod integration_tests {
         let (contract_addr, _) = init_contract(&mut app, owner, init_msg);
 
         // Run cron immediately without advancing time
-        let res = app.execute_contract(
+        app.execute_contract(
             Addr::unchecked(owner),
             contract_addr.clone(),
             &ExecuteMsg::CronJob {},
@@ -764,7 +884,7 @@ mod integration_tests {
         // Query config
         let config = CONFIG.load(&deps.storage).unwrap();
         assert_eq!(config.owner, Addr::unchecked("creator"));
-        assert_eq!(config.liquid_staking_interval, 3600);
+        assert_eq!(config.liquid_staking_interval, Schedule::Seconds(3600));
     }
 
     #[test]
@@ -875,10 +995,10 @@ mod integration_tests {
         instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg.clone()).unwrap();
 
         let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
-        let cfg: Config = from_binary(&bin).unwrap();
+        let cfg: Config = from_json(&bin).unwrap();
         assert_eq!(cfg.owner, Addr::unchecked("creator"));
-        assert_eq!(cfg.arch_liquid_stake_interval, 20);
-        assert_eq!(cfg.redemption_rate_query_interval, 30);
+        assert_eq!(cfg.arch_liquid_stake_interval, Schedule::Seconds(20));
+        assert_eq!(cfg.redemption_rate_query_interval, Schedule::Seconds(30));
     }
 
     #[test]
@@ -923,7 +1043,7 @@ mod integration_tests {
         // Contract metadata must exist for contract1
         let meta_msg = ExecuteMsg::SetContractMetadata {
             contract_address: "contract1".to_string(),
-            rewards_address: "r1".to_string(),
+            rewards_address: "rewards1".to_string(),
             liquidity_provider_address: "lp1".to_string(),
             redemption_address: "red1".to_string(),
             minimum_reward_amount: Uint128::new(10),
@@ -938,11 +1058,33 @@ mod integration_tests {
         };
         execute(deps.as_mut(), env.clone(), info.clone(), redeem_msg).unwrap();
 
-        let tokens = REDEMPTION_RECORDS
-            .may_load(&deps.storage, &Addr::unchecked("contract1"))
-            .unwrap()
+        let ledger = REDEMPTION_LEDGER
+            .load(&deps.storage, &Addr::unchecked("contract1"))
             .unwrap();
-        assert_eq!(tokens, Uint128::new(200));
+        assert_eq!(ledger.pending, Uint128::new(200));
+
+        // Distributing moves the pending tokens to distributed.
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::DistributeRedeemTokens {}).unwrap();
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetRedemptionLedgers { start_after: None, limit: None },
+        )
+        .unwrap();
+        let ledgers: Vec<RedemptionLedgerResponse> = from_json(res).unwrap();
+        assert_eq!(ledgers.len(), 1);
+        assert_eq!(ledgers[0].contract, "contract1");
+        assert_eq!(ledgers[0].pending, Uint128::zero());
+        assert_eq!(ledgers[0].distributed, Uint128::new(200));
+        assert_eq!(ledgers[0].claimed, Uint128::zero());
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::GetRedeemTokens { contract: "contract1".to_string() },
+        )
+        .unwrap();
+        assert_eq!(from_json::<RedeemTokensResponse>(res).unwrap().amount, Uint128::zero());
     }
 
     #[test]
@@ -1053,7 +1195,7 @@ mod integration_tests {
 
         // No stake ratios set, query all stake ratios should return empty
         let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetAllStakeRatios {}).unwrap();
-        let ratios: Vec<(String, String)> = from_binary(&bin).unwrap();
+        let ratios: Vec<StakeRatioResponse> = from_json(&bin).unwrap();
         assert!(ratios.is_empty());
     }
     
@@ -1080,9 +1222,885 @@ mod integration_tests {
         execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ResetRedemptionRatios {}).unwrap();
 
         let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetAllRedemptionRatios {}).unwrap();
-        let ratios: Vec<(String, String)> = from_binary(&bin).unwrap();
+        let ratios: Vec<RedemptionRatioResponse> = from_json(&bin).unwrap();
         assert!(ratios.is_empty());
     }
-}
 
-   
+    #[test]
+    fn test_run_task_with_force() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 100,
+            arch_liquid_stake_interval: 100,
+            redemption_rate_query_interval: 100,
+            rewards_withdrawal_interval: 100,
+            redemption_interval_threshold: 100,
+        };
+        instantiate(deps.as_mut(), env.clone(), owner.clone(), init_msg).unwrap();
+        let start = env.block.time.seconds();
+
+        let run = |task: CronTask, force: bool| ExecuteMsg::RunTask { task, force };
+
+        // Only the owner and operators can run tasks.
+        let operator = mock_info("operator", &[]);
+        let err = execute(deps.as_mut(), env.clone(), operator.clone(), run(CronTask::RedemptionRateQuery, true)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::AddOperator { address: "operator".to_string() }).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetOperators {}).unwrap();
+        let operators: OperatorsResponse = from_json(&bin).unwrap();
+        assert_eq!(operators.operators, vec!["operator".to_string()]);
+
+        // Without force the interval must have elapsed.
+        env.block.time = env.block.time.plus_seconds(10);
+        let err = execute(deps.as_mut(), env.clone(), operator.clone(), run(CronTask::ArchLiquidStakeInterval, false)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::TaskNotDue { ref task, next_due, .. } if task == "arch_liquid_stake_interval" && next_due == start + 100
+        ));
+
+        // Forcing runs it now and restarts its interval; other tasks are untouched.
+        let res = execute(deps.as_mut(), env.clone(), operator.clone(), run(CronTask::ArchLiquidStakeInterval, true)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "task" && a.value == "arch_liquid_stake_interval"));
+        let now = env.block.time.seconds();
+        assert_eq!(LAST_PROCESSING_TIMES.load(&deps.storage, "last_arch_liquid_stake_interval_time").unwrap(), now);
+        assert_eq!(LAST_PROCESSING_TIMES.load(&deps.storage, "last_redemption_rate_query_time").unwrap(), start);
+
+        // Rewards withdrawal sends an Archway MsgWithdrawRewards.
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), run(CronTask::RewardsWithdrawal, false)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Stargate { type_url, .. } if type_url == "/archway.rewards.v1.MsgWithdrawRewards"
+        ));
+
+        // A removed operator loses access.
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::RemoveOperator { address: "operator".to_string() }).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), operator, run(CronTask::RedemptionRateQuery, true)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn test_block_based_schedule() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 100,
+            arch_liquid_stake_interval: 100,
+            redemption_rate_query_interval: 100,
+            rewards_withdrawal_interval: 100,
+            redemption_interval_threshold: 100,
+        };
+        instantiate(deps.as_mut(), env.clone(), owner.clone(), init_msg).unwrap();
+
+        let update = ExecuteMsg::UpdateSchedule {
+            task: CronTask::ArchLiquidStakeInterval,
+            schedule: Schedule::Blocks(5),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), update.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), owner.clone(), update).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetConfig {}).unwrap();
+        let cfg: Config = from_json(&bin).unwrap();
+        assert_eq!(cfg.arch_liquid_stake_interval, Schedule::Blocks(5));
+        assert_eq!(cfg.liquid_staking_interval, Schedule::Seconds(100));
+
+        let tasks = |res: &cosmwasm_std::Response| -> Vec<String> {
+            res.attributes.iter().filter(|a| a.key == "task").map(|a| a.value.clone()).collect()
+        };
+
+        // Plenty of time but only four blocks: the block-based task waits.
+        env.block.time = env.block.time.plus_seconds(1000);
+        env.block.height += 4;
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::CronJob {}).unwrap();
+        assert_eq!(
+            tasks(&res),
+            vec!["liquid_staking_dapp_rewards", "redemption_rate_query", "rewards_withdrawal"]
+        );
+
+        // The fifth block makes it due, regardless of time.
+        env.block.height += 1;
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::CronJob {}).unwrap();
+        assert_eq!(tasks(&res), vec!["arch_liquid_stake_interval"]);
+        let processed = res.events.iter().find(|e| e.ty == "cron_task_processed").unwrap();
+        assert!(processed.attributes.iter().any(|a| a.key == "schedule" && a.value == "5 blocks"));
+        assert!(processed.attributes.iter().any(|a| a.key == "next_due" && a.value == (env.block.height + 5).to_string()));
+
+        // Configs stored before schedules existed hold plain seconds.
+        let legacy: Config = from_json(
+            br#"{"owner":"creator","liquid_staking_interval":10,"arch_liquid_stake_interval":20,"redemption_rate_query_interval":30,"rewards_withdrawal_interval":40,"redemption_interval_threshold":5}"#,
+        ).unwrap();
+        assert_eq!(legacy.arch_liquid_stake_interval, Schedule::Seconds(20));
+    }
+
+    #[test]
+    fn test_get_schedule() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 100,
+            arch_liquid_stake_interval: 200,
+            redemption_rate_query_interval: 300,
+            rewards_withdrawal_interval: 400,
+            redemption_interval_threshold: 100,
+        };
+        instantiate(deps.as_mut(), env.clone(), owner.clone(), init_msg).unwrap();
+        let start = env.block.time.seconds();
+        let start_height = env.block.height;
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::UpdateSchedule {
+            task: CronTask::RewardsWithdrawal,
+            schedule: Schedule::Blocks(10),
+        }).unwrap();
+
+        // 150 seconds and 3 blocks later only the rewards task has run.
+        env.block.time = env.block.time.plus_seconds(150);
+        env.block.height += 3;
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::CronJob {}).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(60);
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetSchedule {}).unwrap();
+        let schedules: Vec<TaskScheduleResponse> = from_json(&bin).unwrap();
+        assert_eq!(schedules.len(), 4);
+
+        let rewards = &schedules[0];
+        assert_eq!(rewards.task, CronTask::LiquidStakingDappRewards);
+        assert_eq!(rewards.key, "last_liquid_staking_dapp_rewards_time");
+        assert_eq!(rewards.last_run_time, start + 150);
+        assert_eq!(rewards.last_run_height, start_height + 3);
+        assert_eq!(rewards.next_due_time, Some(start + 250));
+        assert!(!rewards.overdue);
+
+        // The arch stake interval was due at start + 200 and has not run since.
+        let arch = &schedules[1];
+        assert_eq!(arch.last_run_time, start);
+        assert_eq!(arch.next_due_time, Some(start + 200));
+        assert!(arch.overdue);
+
+        let withdrawal = &schedules[3];
+        assert_eq!(withdrawal.schedule, Schedule::Blocks(10));
+        assert_eq!(withdrawal.next_due_time, None);
+        assert_eq!(withdrawal.next_due_height, Some(start_height + 10));
+        assert!(!withdrawal.overdue);
+    }
+
+    #[test]
+    fn test_liquid_stake_receipts_by_epoch() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let info = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 1000,
+            arch_liquid_stake_interval: 10,
+            redemption_rate_query_interval: 1000,
+            rewards_withdrawal_interval: 1000,
+            redemption_interval_threshold: 1000,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(3000)).unwrap();
+
+        let emit = |total: u128, obtained: u128, tx: &str| ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake: Uint128::new(total),
+            stuarch_obtained: Uint128::new(obtained),
+            tx_hash: tx.to_string(),
+        };
+
+        // Epoch 1: two receipts
+        env.block.time = env.block.time.plus_seconds(11);
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::CronJob {}).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 500, "tx1")).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), emit(500, 250, "tx2")).unwrap();
+
+        // Epoch 2: one receipt at a different rate
+        env.block.time = env.block.time.plus_seconds(11);
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::CronJob {}).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 400, "tx3")).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetLiquidStakeReceipts { start_after: Some(1), limit: None }).unwrap();
+        let receipts: Vec<LiquidStakeReceipt> = from_json(&bin).unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].id, 2);
+        assert_eq!(receipts[0].epoch, 1);
+        assert_eq!(receipts[1].epoch, 2);
+        assert_eq!(receipts[1].redemption_rate, Decimal::from_ratio(5u128, 2u128));
+        assert_eq!(receipts[1].tx_hash, "tx3");
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetStArchByEpoch { epoch: 1 }).unwrap();
+        let epoch_one: EpochStArchResponse = from_json(&bin).unwrap();
+        assert_eq!(epoch_one.arch_in, Uint128::new(1500));
+        assert_eq!(epoch_one.starch_out, Uint128::new(750));
+        assert_eq!(epoch_one.receipt_count, 2);
+        assert_eq!(epoch_one.cumulative_starch_out, Uint128::new(750));
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetCumulativeStArch {}).unwrap();
+        let epochs: Vec<EpochStArchResponse> = from_json(&bin).unwrap();
+        assert_eq!(epochs.len(), 2);
+        assert_eq!(epochs[1].starch_out, Uint128::new(400));
+        assert_eq!(epochs[1].cumulative_arch_in, Uint128::new(2500));
+        assert_eq!(epochs[1].cumulative_starch_out, Uint128::new(1150));
+    }
+
+    #[test]
+    fn test_liquid_stake_reconciliation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 10,
+            arch_liquid_stake_interval: 20,
+            redemption_rate_query_interval: 30,
+            rewards_withdrawal_interval: 40,
+            redemption_interval_threshold: 5,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(1500)).unwrap();
+
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake: Uint128::new(1000),
+            stuarch_obtained: Uint128::new(800),
+            tx_hash: "tx1".to_string(),
+        }).unwrap();
+
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetLiquidStakeReconciliation {}).unwrap();
+        let rec: LiquidStakeReconciliationResponse = from_json(&bin).unwrap();
+        assert_eq!(rec.total_liquid_stake, Uint128::new(1500));
+        assert_eq!(rec.receipted_arch, Uint128::new(1000));
+        assert_eq!(rec.receipted_starch, Uint128::new(800));
+        assert_eq!(rec.unreceipted_arch, Uint128::new(500));
+        assert_eq!(rec.over_receipted_arch, Uint128::zero());
+        assert_eq!(rec.average_redemption_rate, Some(Decimal::from_ratio(5u128, 4u128)));
+
+        // Reports are recorded as given, even with no stARCH obtained; the reconciliation only
+        // reports the mismatch.
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake: Uint128::new(1000),
+            stuarch_obtained: Uint128::zero(),
+            tx_hash: "tx2".to_string(),
+        }).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetLiquidStakeReconciliation {}).unwrap();
+        let rec: LiquidStakeReconciliationResponse = from_json(&bin).unwrap();
+        assert_eq!(rec.receipted_arch, Uint128::new(2000));
+        assert_eq!(rec.receipted_starch, Uint128::new(800));
+        assert_eq!(rec.unreceipted_arch, Uint128::zero());
+        assert_eq!(rec.over_receipted_arch, Uint128::new(500));
+    }
+
+    #[test]
+    fn test_liquid_stake_event_verification() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 10,
+            arch_liquid_stake_interval: 20,
+            redemption_rate_query_interval: 30,
+            rewards_withdrawal_interval: 40,
+            redemption_interval_threshold: 5,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(1000)).unwrap();
+
+        let contract = env.contract.address.to_string();
+        deps.querier.update_balance(&contract, coins(100, "ibc/starch"));
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EnableLiquidStakeVerification {
+            starch_denom: "ibc/starch".to_string(),
+            tolerance_bps: 100,
+        }).unwrap();
+
+        // 800 stARCH arrive on the contract
+        deps.querier.update_balance(&contract, coins(900, "ibc/starch"));
+        let emit = |total: u128, obtained: u128| ExecuteMsg::EmitLiquidStakeEvent {
+            total_liquid_stake: Uint128::new(total),
+            stuarch_obtained: Uint128::new(obtained),
+            tx_hash: "tx".to_string(),
+        };
+
+        // Fabricated stARCH amount is rejected
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 1000)).unwrap_err();
+        assert!(matches!(err, ContractError::LiquidStakeVerificationFailed { .. }));
+
+        // More ARCH than TOTAL_LIQUID_STAKE holds is rejected
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), emit(2000, 800)).unwrap_err();
+        assert!(matches!(err, ContractError::LiquidStakeVerificationFailed { .. }));
+
+        // A report within tolerance is accepted and moves the baseline
+        execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 805)).unwrap();
+        let bin = query(deps.as_ref(), env.clone(), QueryMsg::GetLiquidStakeVerification {}).unwrap();
+        let verification: Option<LiquidStakeVerification> = from_json(&bin).unwrap();
+        assert_eq!(verification.unwrap().last_balance, Uint128::new(900));
+
+        // Without new stARCH the next report fails; once disabled it is trusted again
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(2000)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 800)).unwrap_err();
+        assert!(matches!(err, ContractError::LiquidStakeVerificationFailed { .. }));
+        execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::DisableLiquidStakeVerification {}).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), emit(1000, 800)).unwrap();
+    }
+
+    #[test]
+    fn test_liquid_stake_route_maps_to_default_host_zone() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 10,
+            arch_liquid_stake_interval: 20,
+            redemption_rate_query_interval: 30,
+            rewards_withdrawal_interval: 40,
+            redemption_interval_threshold: 5,
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        let set_route = |lst_denom: Option<&str>, channel_id: &str| ExecuteMsg::SetLiquidStakeRoute {
+            channel_id: channel_id.to_string(),
+            arch_denom: "aarch".to_string(),
+            stride_receiver: "stride1receiverxyz".to_string(),
+            timeout_seconds: 600,
+            refund_target: None,
+            lst_denom: lst_denom.map(str::to_string),
+            unbonding_period_seconds: lst_denom.map(|_| 1_209_600),
+        };
+
+        // Without a default zone the route registers one, which needs the stToken denom.
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), set_route(None, "channel-0")).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHostZone { .. }));
+        execute(deps.as_mut(), env.clone(), info.clone(), set_route(Some("ibc/starch"), "channel-0")).unwrap();
+        assert_eq!(DEFAULT_HOST_ZONE.load(&deps.storage).unwrap(), "stride");
+
+        // Afterwards it only changes the default zone's route.
+        execute(deps.as_mut(), env.clone(), info, set_route(None, "channel-5")).unwrap();
+        let zone = HOST_ZONES.load(&deps.storage, "stride").unwrap();
+        assert_eq!(zone.channel_id, "channel-5");
+        assert_eq!(zone.lst_denom, "ibc/starch");
+
+        let bin = query(deps.as_ref(), env, QueryMsg::GetLiquidStakeRoute {}).unwrap();
+        let route: Option<LiquidStakeRoute> = from_json(&bin).unwrap();
+        assert_eq!(route, Some(LiquidStakeRoute {
+            channel_id: "channel-5".to_string(),
+            arch_denom: "aarch".to_string(),
+            stride_receiver: "stride1receiverxyz".to_string(),
+            timeout_seconds: 600,
+            refund_target: RefundTarget::ContractStakes,
+        }));
+    }
+
+    #[test]
+    fn test_migrate_redemption_ledger() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 10,
+            arch_liquid_stake_interval: 20,
+            redemption_rate_query_interval: 30,
+            rewards_withdrawal_interval: 40,
+            redemption_interval_threshold: 5,
+        };
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        cw2::CONTRACT.remove(&mut deps.storage);
+
+        let dapp = Addr::unchecked("contract1");
+        REDEMPTION_RECORDS.save(&mut deps.storage, &dapp, &Uint128::new(300)).unwrap();
+        REDEMPTION_TOKEN_RATIOS.save(&mut deps.storage, &dapp, &Decimal::one()).unwrap();
+        migrate(deps.as_mut(), env.clone(), MigrateMsg::default()).unwrap();
+
+        let ledger = REDEMPTION_LEDGER.load(&deps.storage, &dapp).unwrap();
+        assert_eq!(ledger.pending, Uint128::new(300));
+        assert!(ledger.distributed.is_zero());
+        assert!(ledger.claimed.is_zero());
+        assert!(REDEMPTION_RECORDS.may_load(&deps.storage, &dapp).unwrap().is_none());
+        assert!(REDEMPTION_TOKEN_RATIOS.may_load(&deps.storage, &dapp).unwrap().is_none());
+
+        // Migrating again does not count the records twice.
+        migrate(deps.as_mut(), env, MigrateMsg::default()).unwrap();
+        assert_eq!(REDEMPTION_LEDGER.load(&deps.storage, &dapp).unwrap(), ledger);
+    }
+
+    fn instantiate_pre_versioning(deps: cosmwasm_std::DepsMut) {
+        let init_msg = InstantiateMsg {
+            liquid_staking_interval: 10,
+            arch_liquid_stake_interval: 20,
+            redemption_rate_query_interval: 30,
+            rewards_withdrawal_interval: 40,
+            redemption_interval_threshold: 5,
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), init_msg).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_checks_contract_version() {
+        let mut deps = mock_dependencies();
+        instantiate_pre_versioning(deps.as_mut());
+        let version = cw2::get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+
+        // Migrating to the same version is allowed and runs nothing.
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        let event = res.events.iter().find(|e| e.ty == "migrate").unwrap();
+        assert!(event.attributes.iter().any(|a| a.key == "migrations_applied" && a.value == "0"));
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "9.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMigration { .. }));
+
+        cw2::set_contract_version(&mut deps.storage, "crates.io:other-contract", CONTRACT_VERSION).unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMigration { .. }));
+    }
+
+    #[test]
+    fn test_migrate_deposit_records_to_indexed_map() {
+        let mut deps = mock_dependencies();
+        instantiate_pre_versioning(deps.as_mut());
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let dapp = Addr::unchecked("contract1");
+        let legacy = |id: u64, status: &str| LegacyDepositRecord {
+            id,
+            contract_address: dapp.clone(),
+            amount: Uint128::new(100 * id as u128),
+            status: status.to_string(),
+            timestamp: 0,
+            block_height: 0,
+        };
+        LEGACY_DEPOSIT_RECORDS
+            .save(&mut deps.storage, &dapp, &vec![legacy(1, "completed"), legacy(2, "Pending "), legacy(3, "pending")])
+            .unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        let statuses: Vec<(u64, DepositStatus)> = deposit_records()
+            .prefix(&dapp)
+            .range(&deps.storage, None, None, cosmwasm_std::Order::Ascending)
+            .map(|item| item.map(|(id, record)| (id, record.status)))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            statuses,
+            vec![(1, DepositStatus::Completed), (2, DepositStatus::Pending), (3, DepositStatus::Pending)]
+        );
+        assert!(LEGACY_DEPOSIT_RECORDS.may_load(&deps.storage, &dapp).unwrap().is_none());
+        let records: Vec<DepositRecord> = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::GetDepositRecords { contract: dapp.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].amount, Uint128::new(200));
+
+        // An unknown status fails the migration.
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+        LEGACY_DEPOSIT_RECORDS.save(&mut deps.storage, &dapp, &vec![legacy(4, "lost")]).unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMigration { .. }));
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct StringAddressMetadata {
+        rewards_address: String,
+        liquidity_provider_address: String,
+        minimum_reward_amount: Uint128,
+        maximum_reward_amount: Uint128,
+        redemption_address: String,
+    }
+
+    #[test]
+    fn test_migrate_metadata_addresses() {
+        let mut deps = mock_dependencies();
+        instantiate_pre_versioning(deps.as_mut());
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+        let legacy_metadata: cw_storage_plus::Map<&Addr, StringAddressMetadata> =
+            cw_storage_plus::Map::new("contract_metadata");
+        let dapp = Addr::unchecked("contract1");
+        let metadata = StringAddressMetadata {
+            rewards_address: "rewards1".to_string(),
+            liquidity_provider_address: "provider1".to_string(),
+            minimum_reward_amount: Uint128::new(10),
+            maximum_reward_amount: Uint128::new(1000),
+            redemption_address: "redemption1".to_string(),
+        };
+        legacy_metadata.save(&mut deps.storage, &dapp, &metadata).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        let migrated = CONTRACT_METADATA.load(&deps.storage, &dapp).unwrap();
+        assert_eq!(migrated.rewards_address, Addr::unchecked("rewards1"));
+        assert_eq!(migrated.liquidity_provider_address, Addr::unchecked("provider1"));
+        assert_eq!(migrated.redemption_address, Addr::unchecked("redemption1"));
+        assert!(migrated.strategy.is_empty());
+
+        // Addresses that do not validate fail the migration.
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+        let invalid = StringAddressMetadata {
+            rewards_address: "r1".to_string(),
+            ..metadata
+        };
+        legacy_metadata.save(&mut deps.storage, &dapp, &invalid).unwrap();
+        assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).is_err());
+    }
+
+    #[test]
+    fn test_migrate_shares_from_total_liquid_stake() {
+        let mut deps = mock_dependencies();
+        instantiate_pre_versioning(deps.as_mut());
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+        TOTAL_SHARES.remove(&mut deps.storage);
+        let dapp = Addr::unchecked("contract1");
+        let other = Addr::unchecked("contract2");
+        COMPLETED_STAKES.save(&mut deps.storage, &dapp, &Uint128::new(300)).unwrap();
+        COMPLETED_STAKES.save(&mut deps.storage, &other, &Uint128::new(100)).unwrap();
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(800)).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        // The shares cover the whole pool, split by completed stake.
+        assert_eq!(POOL_ARCH.load(&deps.storage).unwrap(), Uint128::new(800));
+        assert_eq!(TOTAL_SHARES.load(&deps.storage).unwrap(), Uint128::new(800));
+        assert_eq!(SHARES.load(&deps.storage, &dapp).unwrap(), Uint128::new(600));
+        assert_eq!(SHARES.load(&deps.storage, &other).unwrap(), Uint128::new(200));
+    }
+
+    #[test]
+    fn test_migrate_reset_shares_then_deposit() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let owner = mock_info("creator", &[]);
+        instantiate_pre_versioning(deps.as_mut());
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+        TOTAL_SHARES.remove(&mut deps.storage);
+
+        // ResetStakeRatios left no completed stake behind the 800 ARCH already staked.
+        let dapp = Addr::unchecked("contract1");
+        COMPLETED_STAKES.save(&mut deps.storage, &dapp, &Uint128::zero()).unwrap();
+        TOTAL_LIQUID_STAKE.save(&mut deps.storage, &Uint128::new(800)).unwrap();
+        migrate(deps.as_mut(), env.clone(), MigrateMsg::default()).unwrap();
+        assert_eq!(TOTAL_SHARES.load(&deps.storage).unwrap(), Uint128::new(800));
+        assert_eq!(SHARES.load(&deps.storage, &Addr::unchecked("creator")).unwrap(), Uint128::new(800));
+
+        // The next deposit buys shares at the pool's exchange rate rather than the whole pool.
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::SetContractMetadata {
+            contract_address: dapp.to_string(),
+            rewards_address: "rewards1".to_string(),
+            liquidity_provider_address: "provider1".to_string(),
+            redemption_address: "redemption1".to_string(),
+            minimum_reward_amount: Uint128::new(50),
+            maximum_reward_amount: Uint128::new(1000),
+        }).unwrap();
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::UpdateReward {
+            rewards_address: dapp.to_string(),
+            amount: Uint128::new(100),
+        }).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::CronJob {}).unwrap();
+        assert_eq!(SHARES.load(&deps.storage, &dapp).unwrap(), Uint128::new(100));
+        assert_eq!(TOTAL_SHARES.load(&deps.storage).unwrap(), Uint128::new(900));
+        assert_eq!(POOL_ARCH.load(&deps.storage).unwrap(), Uint128::new(900));
+
+        // A pool with value but no shares refuses to mint them.
+        TOTAL_SHARES.save(&mut deps.storage, &Uint128::zero()).unwrap();
+        execute(deps.as_mut(), env.clone(), owner.clone(), ExecuteMsg::UpdateReward {
+            rewards_address: dapp.to_string(),
+            amount: Uint128::new(100),
+        }).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        let err = execute(deps.as_mut(), env.clone(), owner, ExecuteMsg::CronJob {}).unwrap_err();
+        assert!(matches!(err, ContractError::UnsharedPoolValue { pool_value } if pool_value == Uint128::new(900)));
+    }
+
+    #[test]
+    fn test_query_response_schemas() {
+        use cosmwasm_schema::QueryResponses;
+
+        let schemas = QueryMsg::response_schemas().unwrap();
+        assert!(schemas.contains_key("get_config"));
+        assert!(schemas.contains_key("get_liquid_stake_reconciliation"));
+    }
+
+    const HOST_ARCH: &str = "aarch";
+    const HOST_LST: &str = "ibc/ststride";
+    const HOST_CHANNEL: &str = "channel-0";
+    const UNBONDING_SECONDS: u64 = 1000;
+
+    /// Instantiate the contract next to a mock Stride connected over HOST_CHANNEL, register Stride
+    /// as the default host zone with the mock as its redemption rate oracle and register one dApp
+    /// with 1000 pending rewards. The contract holds the ARCH to liquid stake.
+    fn setup_host_zone(bridge: &MockIbcBridge, host_arch_denom: &str) -> (MockApp, Addr, Addr) {
+        let owner = "wasm1ownerxyz";
+        let mut app = bridge.app(&[(owner, coins(1_000_000, HOST_ARCH))]);
+
+        let host_code_id = app.store_code(mock_host_zone_contract());
+        let host_zone = app
+            .instantiate_contract(
+                host_code_id,
+                Addr::unchecked(owner),
+                &MockHostZoneInstantiateMsg {
+                    arch_denom: host_arch_denom.to_string(),
+                    lst_denom: HOST_LST.to_string(),
+                    redemption_rate: Decimal::one(),
+                    unbonding_delay_seconds: UNBONDING_SECONDS,
+                },
+                &[],
+                "MockStride",
+                None,
+            )
+            .unwrap();
+        bridge.connect(HOST_CHANNEL, &host_zone);
+
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::new(execute, instantiate, query)
+                .with_reply(reply)
+                .with_sudo(sudo),
+        ));
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(owner),
+                &InstantiateMsg {
+                    liquid_staking_interval: 1,
+                    arch_liquid_stake_interval: 3,
+                    redemption_rate_query_interval: 1,
+                    rewards_withdrawal_interval: 100,
+                    redemption_interval_threshold: 100,
+                },
+                &[],
+                "LiquidStaking",
+                None,
+            )
+            .unwrap();
+
+        let msgs = [
+            ExecuteMsg::SetContractMetadata {
+                contract_address: "wasm1dappxyz".to_string(),
+                rewards_address: "wasm1rewardsxyz".to_string(),
+                liquidity_provider_address: "wasm1lpxyz".to_string(),
+                redemption_address: "wasm1redemptionxyz".to_string(),
+                minimum_reward_amount: Uint128::new(50),
+                maximum_reward_amount: Uint128::new(10_000),
+            },
+            ExecuteMsg::AddHostZone {
+                zone_id: "stride".to_string(),
+                protocol: LiquidStakeProtocol::Stride,
+                channel_id: HOST_CHANNEL.to_string(),
+                arch_denom: HOST_ARCH.to_string(),
+                lst_denom: HOST_LST.to_string(),
+                receiver: "stride1receiverxyz".to_string(),
+                timeout_seconds: 600,
+                redemption_rate_source: RedemptionRateSource::Oracle {
+                    contract_address: host_zone.clone(),
+                },
+                unbonding_period_seconds: UNBONDING_SECONDS,
+                refund_target: None,
+            },
+            ExecuteMsg::UpdateReward {
+                rewards_address: "wasm1dappxyz".to_string(),
+                amount: Uint128::new(1000),
+            },
+        ];
+        for msg in &msgs {
+            app.execute_contract(Addr::unchecked(owner), contract_addr.clone(), msg, &[])
+                .unwrap();
+        }
+        app.send_tokens(Addr::unchecked(owner), contract_addr.clone(), &coins(1000, HOST_ARCH))
+            .unwrap();
+
+        (app, contract_addr, host_zone)
+    }
+
+    fn run_cron(app: &mut MockApp, contract_addr: &Addr, seconds: u64) {
+        app.update_block(|b| {
+            b.height += 1;
+            b.time = b.time.plus_seconds(seconds);
+        });
+        app.execute_contract(Addr::unchecked("wasm1ownerxyz"), contract_addr.clone(), &ExecuteMsg::CronJob {}, &[])
+            .unwrap();
+    }
+
+    /// Everything `addresses` can claim must be covered by what the contract holds.
+    fn assert_claimable_held(app: &MockApp, contract_addr: &Addr, addresses: &[&str]) {
+        let mut owed: BTreeMap<String, Uint128> = BTreeMap::new();
+        for address in addresses {
+            let claimable: ClaimableResponse = app.wrap()
+                .query_wasm_smart(contract_addr, &QueryMsg::GetClaimable { address: address.to_string() })
+                .unwrap();
+            for coin in claimable.coins {
+                *owed.entry(coin.denom).or_default() += coin.amount;
+            }
+        }
+        for (denom, amount) in owed {
+            let held = app.wrap().query_balance(contract_addr, &denom).unwrap().amount;
+            assert!(amount <= held, "{amount}{denom} claimable but only {held}{denom} held");
+        }
+    }
+
+    #[test]
+    fn test_host_zone_staking_distribution_redemption() {
+        let bridge = MockIbcBridge::default();
+        let (mut app, contract_addr, host_zone) = setup_host_zone(&bridge, HOST_ARCH);
+        let owner = Addr::unchecked("wasm1ownerxyz");
+
+        // Staking: the rewards become a deposit, which is sent to the host zone and completed by
+        // the ack. The host zone mints the stToken to the contract.
+        run_cron(&mut app, &contract_addr, 2);
+        run_cron(&mut app, &contract_addr, 2);
+        assert_eq!(bridge.queued_packets().len(), 1);
+        bridge.relay(&mut app).unwrap();
+
+        let stake: TotalLiquidStakeResponse = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetTotalLiquidStakeQuery {})
+            .unwrap();
+        assert_eq!(stake.completed, Uint128::new(1000));
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_LST).unwrap().amount, Uint128::new(1000));
+        assert_eq!(app.wrap().query_balance(&host_zone, HOST_ARCH).unwrap().amount, Uint128::new(1000));
+
+        // The host zone's redemption rate grows and the oracle query picks it up.
+        app.execute_contract(
+            owner.clone(),
+            host_zone.clone(),
+            &MockHostZoneExecuteMsg::SetRedemptionRate { redemption_rate: Decimal::percent(120) },
+            &[],
+        )
+        .unwrap();
+        run_cron(&mut app, &contract_addr, 2);
+        let rate: ExchangeRateResponse = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.pool_value, Uint128::new(1200));
+
+        // Redemption: 500 stToken are sent back to the host zone, which burns them and owes 600
+        // ARCH after the unbonding delay.
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::SetRedeemTokens {
+                amount: Uint128::new(500),
+                contract_address: "wasm1dappxyz".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(owner.clone(), contract_addr.clone(), &ExecuteMsg::DistributeRedeemTokens {}, &[])
+            .unwrap();
+        bridge.relay(&mut app).unwrap();
+        let pending: Vec<PendingRedemption> = app.wrap()
+            .query_wasm_smart(&host_zone, &MockHostZoneQueryMsg::PendingRedemptions {})
+            .unwrap();
+        assert_eq!(pending[0].amount, Uint128::new(600));
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_LST).unwrap().amount, Uint128::new(500));
+        let batches: Vec<UnbondingBatch> = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetUnbondingBatches { start_after: None, limit: None })
+            .unwrap();
+        assert_eq!(batches[0].status, UnbondingStatus::Unbonding);
+
+        // Nothing is paid before the unbonding delay has passed.
+        app.execute_contract(owner.clone(), host_zone.clone(), &MockHostZoneExecuteMsg::ProcessRedemptions {}, &[])
+            .unwrap();
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_ARCH).unwrap().amount, Uint128::zero());
+
+        app.update_block(|b| b.time = b.time.plus_seconds(UNBONDING_SECONDS));
+        app.execute_contract(owner.clone(), host_zone.clone(), &MockHostZoneExecuteMsg::ProcessRedemptions {}, &[])
+            .unwrap();
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_ARCH).unwrap().amount, Uint128::new(600));
+
+        // The next cron pays the batch out to the dApp, which claims its ARCH.
+        run_cron(&mut app, &contract_addr, 2);
+        let batches: Vec<UnbondingBatch> = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetUnbondingBatches { start_after: None, limit: None })
+            .unwrap();
+        assert_eq!(batches[0].status, UnbondingStatus::Completed);
+        let claimable: ClaimableResponse = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetClaimable { address: "wasm1redemptionxyz".to_string() })
+            .unwrap();
+        assert!(claimable.coins.contains(&cosmwasm_std::coin(600, HOST_ARCH)));
+        assert_claimable_held(&app, &contract_addr, &["wasm1redemptionxyz"]);
+
+        app.execute_contract(
+            Addr::unchecked("wasm1redemptionxyz"),
+            contract_addr.clone(),
+            &ExecuteMsg::Claim { denom: HOST_ARCH.to_string() },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            app.wrap().query_balance("wasm1redemptionxyz", HOST_ARCH).unwrap().amount,
+            Uint128::new(600)
+        );
+
+        // Distribution: the dApp owns the whole pool, and the 500 stToken left in it are paid out
+        // to its liquidity provider address along with its remaining shares.
+        app.execute_contract(owner.clone(), contract_addr.clone(), &ExecuteMsg::DistributeLiquidity {}, &[])
+            .unwrap();
+        let ratios: Vec<StakeRatioResponse> = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetAllStakeRatios {})
+            .unwrap();
+        assert_eq!(ratios[0].ratio, Decimal::one());
+        let claimable: ClaimableResponse = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetClaimable { address: "wasm1lpxyz".to_string() })
+            .unwrap();
+        assert_eq!(claimable.coins, vec![cosmwasm_std::coin(500, HOST_LST)]);
+        assert_claimable_held(&app, &contract_addr, &["wasm1lpxyz"]);
+        let rate: ExchangeRateResponse = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.total_shares, Uint128::zero());
+    }
+
+    #[test]
+    fn test_host_zone_error_ack_fails_deposit() {
+        // The host zone only accepts another denom, so it rejects the transfer with an error ack.
+        let bridge = MockIbcBridge::default();
+        let (mut app, contract_addr, _) = setup_host_zone(&bridge, "uother");
+
+        run_cron(&mut app, &contract_addr, 2);
+        run_cron(&mut app, &contract_addr, 2);
+        assert!(bridge.queued_packets()[0].error.is_some());
+        bridge.relay(&mut app).unwrap();
+
+        let records: Vec<DepositRecord> = app.wrap()
+            .query_wasm_smart(&contract_addr, &QueryMsg::GetDepositRecords { contract: "wasm1dappxyz".to_string() })
+            .unwrap();
+        assert_eq!(records[0].status, DepositStatus::Failed);
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_LST).unwrap().amount, Uint128::zero());
+    }
+
+    #[test]
+    fn test_host_zone_timeout_fails_deposit_and_retry_completes_it() {
+        let bridge = MockIbcBridge::default();
+        let (mut app, contract_addr, host_zone) = setup_host_zone(&bridge, HOST_ARCH);
+        let deposit_records = |app: &MockApp| -> Vec<DepositRecord> {
+            app.wrap()
+                .query_wasm_smart(&contract_addr, &QueryMsg::GetDepositRecords { contract: "wasm1dappxyz".to_string() })
+                .unwrap()
+        };
+
+        // No relayer carries the transfer, so it times out and the ARCH stays on the contract.
+        bridge.stall(HOST_CHANNEL);
+        run_cron(&mut app, &contract_addr, 2);
+        run_cron(&mut app, &contract_addr, 2);
+        assert!(bridge.queued_packets()[0].stalled);
+        assert!(bridge.relay(&mut app).unwrap().is_empty());
+        assert_eq!(deposit_records(&app)[0].status, DepositStatus::InFlight);
+        bridge.relay_timeouts(&mut app).unwrap();
+        assert!(bridge.queued_packets().is_empty());
+        assert_eq!(deposit_records(&app)[0].status, DepositStatus::Failed);
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_ARCH).unwrap().amount, Uint128::new(1000));
+        assert_eq!(app.wrap().query_balance(&host_zone, HOST_ARCH).unwrap().amount, Uint128::zero());
+        assert_claimable_held(&app, &contract_addr, &["wasm1redemptionxyz"]);
+
+        // Once the channel is relayed again the retried transfer completes.
+        bridge.resume(HOST_CHANNEL);
+        app.execute_contract(
+            Addr::unchecked("wasm1ownerxyz"),
+            contract_addr.clone(),
+            &ExecuteMsg::RetryFailedTransfers {},
+            &[],
+        )
+        .unwrap();
+        bridge.relay(&mut app).unwrap();
+        assert_eq!(deposit_records(&app)[0].status, DepositStatus::Completed);
+        assert_eq!(app.wrap().query_balance(&contract_addr, HOST_LST).unwrap().amount, Uint128::new(1000));
+    }
+}
@@ -0,0 +1,251 @@
+#[cfg(test)]
+mod operator_tests {
+    use cosmwasm_std::{from_json, Addr, Empty, Uint128};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+    use cosmwasm_liquid_staking::contract::{execute, instantiate, query};
+    use cosmwasm_liquid_staking::msg::{
+        ContractsResponse, ExecuteMsg, InstantiateMsg, LiquidStakeReconciliationResponse,
+        QueryMsg, RedemptionLedgerResponse, RewardResponse, RewardUpdate,
+    };
+    use cosmwasm_liquid_staking::operator::{
+        build_messages, validate_message, OnChainState, OperatorError, OperatorPlan, TargetBalance,
+    };
+
+    const OWNER: &str = "wasm1ownerxyz";
+
+    fn state_fixture() -> OnChainState {
+        from_json(include_str!("fixtures/operator/state.json")).unwrap()
+    }
+
+    fn plan_fixture() -> OperatorPlan {
+        from_json(include_str!("fixtures/operator/plan.json")).unwrap()
+    }
+
+    #[test]
+    fn test_build_messages_from_fixtures() {
+        let state = state_fixture();
+        let output = build_messages(&state, &plan_fixture()).unwrap();
+
+        assert_eq!(output.diffs.len(), 4);
+        assert!(output.messages.iter().all(|m| m.contract == "archway1liquidstaking"));
+        let msgs: Vec<ExecuteMsg> = output.messages.into_iter().map(|m| m.msg).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                // dappa is already at its reward target
+                ExecuteMsg::BulkUpdateRewards {
+                    updates: vec![RewardUpdate {
+                        contract_address: "archway1dappb".to_string(),
+                        amount: Uint128::new(250),
+                    }],
+                },
+                ExecuteMsg::SetRedeemTokens {
+                    amount: Uint128::new(30),
+                    contract_address: "archway1dappa".to_string(),
+                },
+                ExecuteMsg::SetRedeemTokens {
+                    amount: Uint128::new(20),
+                    contract_address: "archway1dappb".to_string(),
+                },
+                ExecuteMsg::EmitLiquidStakeEvent {
+                    total_liquid_stake: Uint128::new(1000),
+                    stuarch_obtained: Uint128::new(950),
+                    tx_hash: "A1B2C3".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_messages_rejects_invalid_plans() {
+        let state = state_fixture();
+
+        // Rewards can only be added to.
+        let mut plan = plan_fixture();
+        plan.rewards[0].amount = Uint128::new(300);
+        let err = build_messages(&state, &plan).unwrap_err();
+        assert!(matches!(err, OperatorError::InvalidMessage { .. }));
+
+        // Unregistered contract.
+        let mut plan = plan_fixture();
+        plan.redeem_tokens.push(TargetBalance {
+            contract_address: "archway1unknown".to_string(),
+            amount: Uint128::new(10),
+        });
+        let err = build_messages(&state, &plan).unwrap_err();
+        assert!(matches!(err, OperatorError::ContractNotFound { .. }));
+
+        // More liquid stake than is unreceipted.
+        let mut plan = plan_fixture();
+        plan.liquid_stake.as_mut().unwrap().total_liquid_stake = Uint128::new(1001);
+        let err = build_messages(&state, &plan).unwrap_err();
+        assert!(matches!(
+            err,
+            OperatorError::LiquidStakeVerificationFailed { observed, .. } if observed == Uint128::new(1000)
+        ));
+    }
+
+    #[test]
+    fn test_validate_message_payloads() {
+        let state = state_fixture();
+
+        let msg: ExecuteMsg = from_json(
+            r#"{"bulk_update_rewards":{"updates":[
+                {"contract_address":"archway1dappa","amount":"5"},
+                {"contract_address":"archway1dappa","amount":"6"}]}}"#,
+        )
+        .unwrap();
+        let err = validate_message(&state, &msg).unwrap_err();
+        assert!(matches!(err, OperatorError::InvalidMessage { .. }));
+
+        let msg: ExecuteMsg = from_json(
+            r#"{"set_redeem_tokens":{"amount":"0","contract_address":"archway1dappb"}}"#,
+        )
+        .unwrap();
+        assert!(validate_message(&state, &msg).is_err());
+
+        let msg: ExecuteMsg = from_json(
+            r#"{"emit_liquid_stake_event":{"total_liquid_stake":"1000","stuarch_obtained":"950","tx_hash":"A1B2C3"}}"#,
+        )
+        .unwrap();
+        validate_message(&state, &msg).unwrap();
+
+        // Zero amounts are accepted, as by the contract.
+        let zero: ExecuteMsg = from_json(
+            r#"{"emit_liquid_stake_event":{"total_liquid_stake":"0","stuarch_obtained":"0","tx_hash":"A1B2C3"}}"#,
+        )
+        .unwrap();
+        validate_message(&state, &zero).unwrap();
+
+        // Without the reconciliation result a liquid stake report cannot be checked.
+        let mut state = state;
+        state.reconciliation = None;
+        assert!(validate_message(&state, &msg).is_err());
+
+        let msg = ExecuteMsg::CronJob {};
+        assert!(validate_message(&state, &msg).is_err());
+    }
+
+    fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    /// Read the state the operator needs through the contract's own queries.
+    fn query_state(app: &App, contract_addr: &Addr) -> OnChainState {
+        let contracts: ContractsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetAllContracts {})
+            .unwrap();
+        let rewards = contracts
+            .contracts
+            .iter()
+            .map(|c| {
+                app.wrap()
+                    .query_wasm_smart::<RewardResponse>(
+                        contract_addr,
+                        &QueryMsg::GetReward { rewards_address: c.clone() },
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let redemption_ledgers: Vec<RedemptionLedgerResponse> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetRedemptionLedgers { start_after: None, limit: None },
+            )
+            .unwrap();
+        let reconciliation: LiquidStakeReconciliationResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetLiquidStakeReconciliation {})
+            .unwrap();
+
+        OnChainState {
+            contract: contract_addr.to_string(),
+            contracts,
+            rewards,
+            redemption_ledgers,
+            reconciliation: Some(reconciliation),
+        }
+    }
+
+    #[test]
+    fn test_built_messages_reach_the_plan_on_chain() {
+        let mut app = App::default();
+        let code_id = app.store_code(contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    liquid_staking_interval: 100,
+                    arch_liquid_stake_interval: 100,
+                    redemption_rate_query_interval: 10,
+                    rewards_withdrawal_interval: 100,
+                    redemption_interval_threshold: 100,
+                },
+                &[],
+                "LiquidStaking",
+                None,
+            )
+            .unwrap();
+
+        for dapp in ["wasm1dappa", "wasm1dappb"] {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::SetContractMetadata {
+                    contract_address: dapp.to_string(),
+                    rewards_address: format!("{}rewards", dapp),
+                    liquidity_provider_address: format!("{}lp", dapp),
+                    redemption_address: format!("{}redemption", dapp),
+                    minimum_reward_amount: Uint128::new(1),
+                    maximum_reward_amount: Uint128::new(1_000_000),
+                },
+                &[],
+            )
+            .unwrap();
+        }
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateReward {
+                rewards_address: "wasm1dappa".to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let plan = OperatorPlan {
+            rewards: vec![
+                TargetBalance { contract_address: "wasm1dappa".to_string(), amount: Uint128::new(300) },
+                TargetBalance { contract_address: "wasm1dappb".to_string(), amount: Uint128::new(50) },
+            ],
+            redeem_tokens: vec![TargetBalance {
+                contract_address: "wasm1dappb".to_string(),
+                amount: Uint128::new(40),
+            }],
+            liquid_stake: None,
+        };
+        let output = build_messages(&query_state(&app, &contract_addr), &plan).unwrap();
+        for message in &output.messages {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                Addr::unchecked(&message.contract),
+                &message.msg,
+                &message.funds,
+            )
+            .unwrap();
+        }
+
+        // The state now matches the plan, so a second run has nothing to do.
+        let state = query_state(&app, &contract_addr);
+        let reward = |c: &str| state.rewards.iter().find(|r| r.rewards_address == c).unwrap().amount;
+        assert_eq!(reward("wasm1dappa"), Uint128::new(300));
+        assert_eq!(reward("wasm1dappb"), Uint128::new(50));
+        assert_eq!(state.redemption_ledgers[0].pending, Uint128::new(40));
+        assert!(build_messages(&state, &plan).unwrap().messages.is_empty());
+    }
+}
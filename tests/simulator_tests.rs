@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod simulator_tests {
+    use cosmwasm_std::{from_json, Decimal, Uint128};
+
+    use cosmwasm_liquid_staking::error::ContractError;
+    use cosmwasm_liquid_staking::simulator::{
+        simulate, to_csv, TaskScheduleUpdate, Timeline, TimelineAction, TimelineEvent,
+    };
+    use cosmwasm_liquid_staking::state::{CronTask, Schedule};
+
+    fn timeline_fixture() -> Timeline {
+        from_json(include_str!("fixtures/simulator/timeline.json")).unwrap()
+    }
+
+    #[test]
+    fn test_simulate_timeline() {
+        let timeline = timeline_fixture();
+        let rows = simulate(&timeline).unwrap();
+        assert_eq!(rows.len(), 30);
+
+        // Rewards become deposits at the first cron tick and are staked at the arch liquid stake
+        // interval, at a redemption rate of one.
+        assert_eq!(rows[4].pending_rewards, Uint128::zero());
+        assert_eq!(rows[4].pending_deposits, Uint128::new(4000));
+        let row = &rows[9];
+        assert_eq!(row.total_liquid_stake, Uint128::new(4000));
+        assert_eq!(row.starch, Uint128::new(4000));
+        assert_eq!(row.stake_ratios, vec![Decimal::percent(75), Decimal::percent(25)]);
+
+        // The second batch is staked at 1.1 and everything is valued at 1.25 at the end.
+        let last = rows.last().unwrap();
+        assert_eq!(last.total_liquid_stake, Uint128::new(8000));
+        assert_eq!(last.starch, Uint128::new(4000 + 3636));
+        assert_eq!(last.starch_value, Uint128::new(9545));
+        assert_eq!(last.revenue, 1545);
+        assert_eq!(
+            last.stake_ratios,
+            vec![Decimal::permille(375), Decimal::permille(625)]
+        );
+
+        // The same timeline always produces the same rows.
+        assert_eq!(simulate(&timeline).unwrap(), rows);
+
+        let csv = to_csv(&timeline, &rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 31);
+        assert_eq!(
+            lines[0],
+            "block,timestamp,redemption_rate,pending_rewards,pending_deposits,total_liquid_stake,\
+             starch,starch_value,revenue,treasury,stake_ratio:archway1dappa,stake_ratio:archway1dappb"
+        );
+        assert_eq!(lines[30], "30,1700000180,1.25,0,0,8000,7636,9545,1545,0,0.375,0.625");
+    }
+
+    #[test]
+    fn test_backtest_schedule() {
+        // Staking every 20 blocks instead of every 60 seconds stakes everything at 1.1.
+        let mut timeline = timeline_fixture();
+        timeline.schedules = vec![TaskScheduleUpdate {
+            task: CronTask::ArchLiquidStakeInterval,
+            schedule: Schedule::Blocks(20),
+        }];
+        let rows = simulate(&timeline).unwrap();
+
+        assert!(rows[9].starch.is_zero());
+        let last = rows.last().unwrap();
+        assert_eq!(last.starch, Uint128::new(7272));
+        assert_eq!(last.revenue, 1090);
+    }
+
+    #[test]
+    fn test_simulate_rejects_invalid_timeline() {
+        let mut timeline = timeline_fixture();
+        timeline.events.push(TimelineEvent {
+            block: 3,
+            action: TimelineAction::Reward {
+                contract_address: "archway1unknown".to_string(),
+                amount: Uint128::new(100),
+            },
+        });
+        let err = simulate(&timeline).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTimeline { .. }));
+
+        let mut timeline = timeline_fixture();
+        timeline.events.push(TimelineEvent {
+            block: 31,
+            action: TimelineAction::CronTick {},
+        });
+        let err = simulate(&timeline).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTimeline { .. }));
+    }
+}
@@ -0,0 +1,262 @@
+#[cfg(test)]
+mod strategy_tests {
+    use cosmwasm_std::{Addr, Decimal, Empty, Uint128};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+    use cosmwasm_liquid_staking::contract::{execute, instantiate, query};
+    use cosmwasm_liquid_staking::error::ContractError;
+    use cosmwasm_liquid_staking::msg::{
+        ExecuteMsg, InstantiateMsg, QueryMsg, StrategyLayerMsg, StrategyResponse,
+    };
+    use cosmwasm_liquid_staking::state::{
+        LiquidStakeProtocol, RedemptionRateSource, StrategyAction, StrategyLayer, StrategyStatus,
+        StrategyStep,
+    };
+    use cosmwasm_liquid_staking::strategy;
+
+    const OWNER: &str = "wasm1ownerxyz";
+
+    fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    /// Instantiate the contract and register Stride and Quicksilver with reported rates of 1.0.
+    fn setup(app: &mut App) -> Addr {
+        let code_id = app.store_code(contract());
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    liquid_staking_interval: 100,
+                    arch_liquid_stake_interval: 100,
+                    redemption_rate_query_interval: 10,
+                    rewards_withdrawal_interval: 100,
+                    redemption_interval_threshold: 100,
+                },
+                &[],
+                "LiquidStaking",
+                None,
+            )
+            .unwrap();
+
+        for (zone_id, protocol, channel) in [
+            ("stride", LiquidStakeProtocol::Stride, "channel-0"),
+            ("quicksilver", LiquidStakeProtocol::Quicksilver, "channel-1"),
+        ] {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::AddHostZone {
+                    zone_id: zone_id.to_string(),
+                    protocol,
+                    channel_id: channel.to_string(),
+                    arch_denom: "aarch".to_string(),
+                    lst_denom: format!("ibc/st{}", zone_id),
+                    receiver: format!("{}1receiverxyz", zone_id),
+                    timeout_seconds: 600,
+                    redemption_rate_source: RedemptionRateSource::Reported,
+                    unbonding_period_seconds: 14 * 24 * 3600,
+                    refund_target: None,
+                },
+                &[],
+            )
+            .unwrap();
+            report_rate(app, &contract_addr, zone_id, Decimal::one());
+        }
+
+        contract_addr
+    }
+
+    fn report_rate(app: &mut App, contract_addr: &Addr, zone_id: &str, rate: Decimal) {
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::ReportRedemptionRate {
+                zone_id: zone_id.to_string(),
+                redemption_rate: rate,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    fn two_layer_path() -> Vec<StrategyLayerMsg> {
+        vec![
+            StrategyLayerMsg {
+                zone_id: "stride".to_string(),
+                input_denom: "aarch".to_string(),
+                output_denom: "starch".to_string(),
+            },
+            StrategyLayerMsg {
+                zone_id: "quicksilver".to_string(),
+                input_denom: "starch".to_string(),
+                output_denom: "qstarch".to_string(),
+            },
+        ]
+    }
+
+    fn get_strategy(app: &App, contract_addr: &Addr, strategy_id: u64) -> StrategyResponse {
+        app.wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetStrategy { strategy_id })
+            .unwrap()
+    }
+
+    fn run_cron(app: &mut App, contract_addr: &Addr) {
+        app.update_block(|b| b.time = b.time.plus_seconds(11));
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::CronJob {},
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_compounded_rate_and_unwind_math() {
+        let layer = |rate: u64, position: u128| StrategyLayer {
+            zone_id: "zone".to_string(),
+            input_denom: "in".to_string(),
+            output_denom: "out".to_string(),
+            position: Uint128::new(position),
+            redemption_rate: Decimal::percent(rate),
+        };
+        let mut layers = vec![layer(140, 1000), layer(140, 1000), layer(140, 1000)];
+
+        assert_eq!(strategy::compounded_rate(&layers), Decimal::permille(2744));
+        assert_eq!(strategy::base_value(&layers), Uint128::new(2744));
+
+        let steps = strategy::unwind(&mut layers);
+        let layers_unwound: Vec<u32> = steps.iter().map(|s| s.layer).collect();
+        assert_eq!(layers_unwound, vec![2, 1, 0]);
+        assert_eq!(steps[0].amount_out, Uint128::new(1400));
+        assert_eq!(steps[1].amount_in, Uint128::new(1400));
+        assert_eq!(steps[2].amount_out, Uint128::new(2744));
+        assert!(layers.iter().all(|l| l.position.is_zero()));
+    }
+
+    #[test]
+    fn test_create_strategy_validates_path() {
+        let mut app = App::default();
+        let contract_addr = setup(&mut app);
+
+        let mut layers = two_layer_path();
+        layers[1].input_denom = "aarch".to_string();
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::CreateStrategy {
+                    name: "broken".to_string(),
+                    layers,
+                    target_rate: Decimal::percent(150),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidStrategyPath { .. })
+        ));
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked("wasm1notownerxyz"),
+                contract_addr.clone(),
+                &ExecuteMsg::CreateStrategy {
+                    name: "rra".to_string(),
+                    layers: two_layer_path(),
+                    target_rate: Decimal::percent(150),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<ContractError>(), Some(ContractError::Unauthorized {})));
+    }
+
+    #[test]
+    fn test_strategy_unwinds_when_target_rate_reached() {
+        let mut app = App::default();
+        let contract_addr = setup(&mut app);
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::CreateStrategy {
+                name: "stride-quicksilver".to_string(),
+                layers: two_layer_path(),
+                target_rate: Decimal::percent(150),
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            contract_addr.clone(),
+            &ExecuteMsg::StakeStrategy {
+                strategy_id: 1,
+                amount: Uint128::new(1000),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let rra = get_strategy(&app, &contract_addr, 1);
+        assert_eq!(rra.strategy.layers[0].position, Uint128::new(1000));
+        assert_eq!(rra.strategy.layers[1].position, Uint128::new(1000));
+        assert_eq!(rra.compounded_rate, Decimal::one());
+
+        // 1.1 x 1.2 = 1.32 is below the target: the strategy stays active.
+        report_rate(&mut app, &contract_addr, "stride", Decimal::percent(110));
+        report_rate(&mut app, &contract_addr, "quicksilver", Decimal::percent(120));
+        run_cron(&mut app, &contract_addr);
+        let rra = get_strategy(&app, &contract_addr, 1);
+        assert_eq!(rra.strategy.status, StrategyStatus::Active);
+        assert_eq!(rra.compounded_rate, Decimal::permille(1320));
+        assert_eq!(rra.base_value, Uint128::new(1320));
+
+        // 1.25 x 1.2 = 1.5 reaches the target: the cron unwinds it layer by layer.
+        report_rate(&mut app, &contract_addr, "stride", Decimal::percent(125));
+        run_cron(&mut app, &contract_addr);
+        let rra = get_strategy(&app, &contract_addr, 1);
+        assert_eq!(rra.strategy.status, StrategyStatus::Unwound);
+        assert_eq!(rra.base_value, Uint128::zero());
+
+        let steps: Vec<StrategyStep> = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &QueryMsg::GetStrategySteps {
+                    strategy_id: 1,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0].action, StrategyAction::Stake);
+        assert_eq!(steps[2].action, StrategyAction::Unwind);
+        assert_eq!(steps[2].layer, 1);
+        assert_eq!(steps[2].amount_out, Uint128::new(1200));
+        assert_eq!(steps[3].layer, 0);
+        assert_eq!(steps[3].amount_out, Uint128::new(1500));
+
+        // An unwound strategy takes no further stakes.
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                contract_addr.clone(),
+                &ExecuteMsg::StakeStrategy {
+                    strategy_id: 1,
+                    amount: Uint128::new(1000),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::StrategyNotActive { .. })
+        ));
+    }
+}